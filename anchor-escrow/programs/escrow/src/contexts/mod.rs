@@ -1,6 +1,8 @@
 #![allow(unused_imports)]
 pub mod make;
+pub mod refund;
 pub mod take;
 
 pub use make::*;
+pub use refund::*;
 pub use take::*;
@@ -429,5 +429,87 @@ describe("escrow", () => {
     }
   });
 
+  it("Refund", async () => {
+    try {
+      // A second offer from the same maker that nobody takes
+      const { maker, mintA, mintB, makerAtaA } = global.escrowSetup;
+      const seed = new BN(randomBytes(8));
+      const depositAmount = new anchor.BN(20);
+      const receiveAmount = new anchor.BN(30);
+
+      const [escrowPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("escrow"),
+          maker.publicKey.toBuffer(),
+          seed.toArrayLike(Buffer, "le", 8)
+        ],
+        program.programId
+      );
+      const vaultPda = getAssociatedTokenAddressSync(mintA.publicKey, escrowPda, true, TOKEN_PROGRAM_ID);
+
+      const makerAtaABefore = await getAccount(provider.connection, makerAtaA, "confirmed");
+
+      await program.methods
+        .make(seed, depositAmount, receiveAmount)
+        .accounts({
+          maker: maker.publicKey,
+          mintA: mintA.publicKey,
+          mintB: mintB.publicKey,
+          makerAtaA: makerAtaA,
+          escrow: escrowPda,
+          vault: vaultPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([maker])
+        .rpc();
+
+      console.log("=== REFUND INSTRUCTION ===");
+      console.log("Escrow PDA:", escrowPda.toBase58());
+      console.log("Vault PDA:", vaultPda.toBase58());
+
+      const signature = await program.methods
+        .refund()
+        .accounts({
+          maker: maker.publicKey,
+          mintA: mintA.publicKey,
+          makerAtaA: makerAtaA,
+          escrow: escrowPda,
+          vault: vaultPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+
+      console.log(`\nRefund transaction signature: https://explorer.solana.com/transaction/${signature}?cluster=custom&customUrl=${provider.connection.rpcEndpoint}`);
+
+      // ===== VERIFICATION =====
+
+      // 1. Maker got the whole deposit back
+      const makerAtaAAfter = await getAccount(provider.connection, makerAtaA, "confirmed");
+      console.log("Maker's Token A balance after:", Number(makerAtaAAfter.amount));
+      assert.equal(Number(makerAtaAAfter.amount), Number(makerAtaABefore.amount), "Maker didn't get the deposit back");
+
+      // 2. Vault and escrow state are both closed
+      const [vaultInfo, escrowInfo] = await Promise.all([
+        provider.connection.getAccountInfo(vaultPda, "confirmed"),
+        provider.connection.getAccountInfo(escrowPda, "confirmed")
+      ]);
+      assert.isNull(vaultInfo, "Vault should be closed");
+      assert.isNull(escrowInfo, "Escrow state should be closed");
+
+      console.log("\n✅ Refund instruction executed successfully!");
+      console.log(`✅ Maker got ${depositAmount} Token A back`);
+
+      console.log("\n============================================================\n")
+
+    } catch (error) {
+      console.error(`❌ Something went wrong in Refund: ${error}`);
+      console.error("Full error:", error);
+      throw error;
+    }
+  });
+
 });
 
@@ -0,0 +1,519 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+declare_id!("DBaD4r3YCWg8bjs7Z716rVfzEXbAbiy9d6iTyMdHk7Bm");
+
+// A SOL vault controlled by M of N owners. Any owner can propose a withdrawal,
+// and once `threshold` owners have approved it anyone can execute it.
+#[program]
+pub mod anchor_multisig_vault {
+    use super::*;
+
+    // `seed` lets the same creator set up more than one vault
+    pub fn create_vault(
+        ctx: Context<CreateVault>,
+        seed: u64,
+        owners: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require!(
+            !owners.is_empty() && owners.len() <= VaultState::MAX_OWNERS,
+            VaultError::InvalidOwners
+        );
+        for (i, owner) in owners.iter().enumerate() {
+            require!(!owners[..i].contains(owner), VaultError::DuplicateOwner);
+        }
+        require!(
+            threshold > 0 && threshold as usize <= owners.len(),
+            VaultError::InvalidThreshold
+        );
+
+        let vault_state = &mut ctx.accounts.vault_state;
+        vault_state.creator = ctx.accounts.creator.key();
+        vault_state.seed = seed;
+        vault_state.owners = owners;
+        vault_state.threshold = threshold;
+        vault_state.proposal_count = 0;
+        vault_state.bump = ctx.bumps.vault_state;
+        vault_state.vault_bump = ctx.bumps.vault;
+
+        emit!(CreateVaultEvent {
+            vault_state: vault_state.key(),
+            owners: vault_state.owners.clone(),
+            threshold,
+        });
+
+        Ok(())
+    }
+
+    // Anyone can fund the vault
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        require!(amount > 0, VaultError::InvalidAmount);
+
+        // The first deposit also pays the vault's rent
+        let rent = Rent::get()?
+            .minimum_balance(0)
+            .saturating_sub(ctx.accounts.vault.lamports());
+
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.depositor.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+            },
+        );
+
+        system_program::transfer(
+            cpi_context,
+            amount.checked_add(rent).ok_or(VaultError::Overflow)?,
+        )?;
+
+        emit!(DepositEvent {
+            vault_state: ctx.accounts.vault_state.key(),
+            depositor: ctx.accounts.depositor.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Opens a proposal to send `amount` to `recipient`, counting the proposer's approval.
+    // It can't be executed from `expires_at` on, if given.
+    pub fn propose_withdraw(
+        ctx: Context<ProposeWithdraw>,
+        recipient: Pubkey,
+        amount: u64,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        require!(amount > 0, VaultError::InvalidAmount);
+        if let Some(expires_at) = expires_at {
+            require!(
+                expires_at > Clock::get()?.unix_timestamp,
+                VaultError::InvalidExpiry
+            );
+        }
+
+        let vault_state = &mut ctx.accounts.vault_state;
+        let proposer = ctx.accounts.proposer.key();
+        require!(vault_state.is_owner(&proposer), VaultError::NotAnOwner);
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.vault_state = vault_state.key();
+        proposal.index = vault_state.proposal_count;
+        proposal.proposer = proposer;
+        proposal.recipient = recipient;
+        proposal.amount = amount;
+        proposal.approvals = vec![proposer];
+        proposal.cancellations = Vec::new();
+        proposal.expires_at = expires_at;
+        proposal.bump = ctx.bumps.proposal;
+
+        vault_state.proposal_count = vault_state
+            .proposal_count
+            .checked_add(1)
+            .ok_or(VaultError::Overflow)?;
+
+        emit!(ProposalEvent {
+            vault_state: proposal.vault_state,
+            index: proposal.index,
+            proposer,
+            recipient,
+            amount,
+            expires_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn approve(ctx: Context<Approve>) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        require!(
+            ctx.accounts.vault_state.is_owner(&owner),
+            VaultError::NotAnOwner
+        );
+
+        let proposal = &mut ctx.accounts.proposal;
+        require!(
+            !proposal.approvals.contains(&owner),
+            VaultError::AlreadyApproved
+        );
+        proposal.approvals.push(owner);
+
+        emit!(ApproveEvent {
+            vault_state: proposal.vault_state,
+            index: proposal.index,
+            owner,
+            approvals: proposal.approvals.len() as u8,
+        });
+
+        Ok(())
+    }
+
+    // Pays out an approved proposal and closes it, refunding its rent to the proposer
+    pub fn execute(ctx: Context<Execute>) -> Result<()> {
+        let vault_state = &ctx.accounts.vault_state;
+        let proposal = &ctx.accounts.proposal;
+        let now = Clock::get()?.unix_timestamp;
+        require!(!proposal.is_expired(now), VaultError::ProposalExpired);
+        require!(
+            proposal.approvals.len() >= vault_state.threshold as usize,
+            VaultError::NotEnoughApprovals
+        );
+
+        // The vault keeps its rent, only what's above it can be paid out
+        let available = ctx
+            .accounts
+            .vault
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+        require!(
+            proposal.amount <= available,
+            VaultError::InsufficientBalance
+        );
+
+        let vault_state_key = vault_state.key();
+        let seeds: &[&[&[u8]]] = &[&[
+            b"vault",
+            vault_state_key.as_ref(),
+            &[vault_state.vault_bump],
+        ]];
+
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.recipient.to_account_info(),
+            },
+            seeds,
+        );
+
+        system_program::transfer(cpi_context, proposal.amount)?;
+
+        emit!(ExecuteEvent {
+            vault_state: vault_state_key,
+            index: proposal.index,
+            recipient: proposal.recipient,
+            amount: proposal.amount,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Records the owner's vote to drop the proposal. It's closed, refunding its
+    // rent to the proposer, straight away if the proposer cancels or it has
+    // expired, otherwise once `threshold` owners have cancelled it.
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        let vault_state = &ctx.accounts.vault_state;
+        require!(vault_state.is_owner(&owner), VaultError::NotAnOwner);
+
+        let proposal = &mut ctx.accounts.proposal;
+        require!(
+            !proposal.cancellations.contains(&owner),
+            VaultError::AlreadyCancelled
+        );
+        proposal.cancellations.push(owner);
+
+        let closed = owner == proposal.proposer
+            || proposal.is_expired(Clock::get()?.unix_timestamp)
+            || proposal.cancellations.len() >= vault_state.threshold as usize;
+
+        emit!(CancelProposalEvent {
+            vault_state: proposal.vault_state,
+            index: proposal.index,
+            owner,
+            cancellations: proposal.cancellations.len() as u8,
+            closed,
+        });
+
+        if closed {
+            proposal.close(ctx.accounts.proposer.to_account_info())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct CreateVault<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = VaultState::SIZE,
+        seeds = [b"multisig", creator.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    // Holds the lamports, funded by the first deposit
+    #[account(
+        seeds = [b"vault", vault_state.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.key().as_ref()],
+        bump = vault_state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeWithdraw<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(mut)]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::SIZE,
+        seeds = [
+            b"proposal",
+            vault_state.key().as_ref(),
+            vault_state.proposal_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Approve<'info> {
+    pub owner: Signer<'info>,
+
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        has_one = vault_state,
+        seeds = [
+            b"proposal",
+            vault_state.key().as_ref(),
+            proposal.index.to_le_bytes().as_ref()
+        ],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct Execute<'info> {
+    pub executor: Signer<'info>,
+
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.key().as_ref()],
+        bump = vault_state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = proposer,
+        has_one = vault_state,
+        has_one = proposer,
+        has_one = recipient,
+        seeds = [
+            b"proposal",
+            vault_state.key().as_ref(),
+            proposal.index.to_le_bytes().as_ref()
+        ],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: checked against proposal.proposer, only receives the proposal's rent
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    /// CHECK: checked against proposal.recipient, which can be any account,
+    /// including a PDA of another program, as it only receives lamports
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    pub owner: Signer<'info>,
+
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        has_one = vault_state,
+        has_one = proposer,
+        seeds = [
+            b"proposal",
+            vault_state.key().as_ref(),
+            proposal.index.to_le_bytes().as_ref()
+        ],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: checked against proposal.proposer, only receives the proposal's rent
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+// The owners are fixed when the vault is created
+#[account]
+pub struct VaultState {
+    pub creator: Pubkey,
+    pub seed: u64,
+    pub owners: Vec<Pubkey>,
+    pub threshold: u8,
+    pub proposal_count: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl VaultState {
+    pub const MAX_OWNERS: usize = 10;
+    // discriminator + creator + seed + owners + threshold + proposal_count + bumps
+    pub const SIZE: usize = 8 + 32 + 8 + (4 + Self::MAX_OWNERS * 32) + 1 + 8 + 1 + 1;
+
+    pub fn is_owner(&self, key: &Pubkey) -> bool {
+        self.owners.contains(key)
+    }
+}
+
+#[account]
+pub struct Proposal {
+    pub vault_state: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub approvals: Vec<Pubkey>,
+    pub cancellations: Vec<Pubkey>,
+    pub expires_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl Proposal {
+    // discriminator + vault_state + index + proposer + recipient + amount
+    // + approvals + cancellations + expires_at + bump
+    pub const SIZE: usize = 8
+        + 32
+        + 8
+        + 32
+        + 32
+        + 8
+        + (4 + VaultState::MAX_OWNERS * 32)
+        + (4 + VaultState::MAX_OWNERS * 32)
+        + (1 + 8)
+        + 1;
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+#[event]
+pub struct CreateVaultEvent {
+    pub vault_state: Pubkey,
+    pub owners: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct DepositEvent {
+    pub vault_state: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalEvent {
+    pub vault_state: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub expires_at: Option<i64>,
+}
+
+#[event]
+pub struct ApproveEvent {
+    pub vault_state: Pubkey,
+    pub index: u64,
+    pub owner: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct ExecuteEvent {
+    pub vault_state: Pubkey,
+    pub index: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CancelProposalEvent {
+    pub vault_state: Pubkey,
+    pub index: u64,
+    pub owner: Pubkey,
+    pub cancellations: u8,
+    pub closed: bool,
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Amount must be greater than 0")]
+    InvalidAmount,
+    #[msg("Insufficient balance for withdrawal")]
+    InsufficientBalance,
+    #[msg("Arithmetic overflow")]
+    Overflow,
+    #[msg("A vault needs between 1 and 10 owners")]
+    InvalidOwners,
+    #[msg("Owners must be unique")]
+    DuplicateOwner,
+    #[msg("Threshold must be between 1 and the number of owners")]
+    InvalidThreshold,
+    #[msg("Signer is not an owner of this vault")]
+    NotAnOwner,
+    #[msg("Owner has already approved this proposal")]
+    AlreadyApproved,
+    #[msg("Proposal doesn't have enough approvals yet")]
+    NotEnoughApprovals,
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Proposal has expired")]
+    ProposalExpired,
+    #[msg("Owner has already cancelled this proposal")]
+    AlreadyCancelled,
+}
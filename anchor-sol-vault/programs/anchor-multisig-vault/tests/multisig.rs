@@ -0,0 +1,528 @@
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::entrypoint::ProgramResult,
+    AccountDeserialize, InstructionData, ToAccountMetas,
+};
+use anchor_multisig_vault::{Proposal, VaultError, VaultState};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+const STARTING_LAMPORTS: u64 = 10 * LAMPORTS_PER_SOL;
+const DEPOSIT: u64 = 5 * LAMPORTS_PER_SOL;
+const SEED: u64 = 7;
+
+// Anchor's entrypoint ties the account slice to the `AccountInfo` lifetime,
+// which `processor!` can't express, so leak the slice for the test run.
+fn entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    anchor_multisig_vault::entry(program_id, accounts, data)
+}
+
+// A 2-of-3 vault owned by the first three keypairs, `outsider` owns nothing
+struct TestMultisig {
+    context: ProgramTestContext,
+    owners: [Keypair; 3],
+    outsider: Keypair,
+    vault_state: Pubkey,
+}
+
+impl TestMultisig {
+    async fn new() -> Self {
+        let mut program_test = ProgramTest::new(
+            "anchor_multisig_vault",
+            anchor_multisig_vault::ID,
+            processor!(entry),
+        );
+        let owners = [Keypair::new(), Keypair::new(), Keypair::new()];
+        let outsider = Keypair::new();
+        for user in owners.iter().chain([&outsider]) {
+            program_test.add_account(
+                user.pubkey(),
+                Account::new(STARTING_LAMPORTS, 0, &system_program::ID),
+            );
+        }
+
+        let vault_state = vault_state_address(&owners[0].pubkey(), SEED);
+        let mut test = Self {
+            context: program_test.start_with_context().await,
+            owners,
+            outsider,
+            vault_state,
+        };
+
+        let owners = test.owners.iter().map(|owner| owner.pubkey()).collect();
+        let creator = test.owners[0].insecure_clone();
+        test.create_vault(&creator, owners, 2).await.unwrap();
+        test.deposit(&creator, DEPOSIT).await.unwrap();
+        test
+    }
+
+    async fn create_vault(
+        &mut self,
+        creator: &Keypair,
+        owners: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<(), BanksClientError> {
+        let vault_state = vault_state_address(&creator.pubkey(), SEED);
+        let ix = Instruction {
+            program_id: anchor_multisig_vault::ID,
+            accounts: anchor_multisig_vault::accounts::CreateVault {
+                creator: creator.pubkey(),
+                vault_state,
+                vault: vault_address(&vault_state),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_multisig_vault::instruction::CreateVault {
+                seed: SEED,
+                owners,
+                threshold,
+            }
+            .data(),
+        };
+        self.send(ix, creator).await
+    }
+
+    async fn deposit(&mut self, depositor: &Keypair, amount: u64) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_multisig_vault::ID,
+            accounts: anchor_multisig_vault::accounts::Deposit {
+                depositor: depositor.pubkey(),
+                vault_state: self.vault_state,
+                vault: vault_address(&self.vault_state),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_multisig_vault::instruction::Deposit { amount }.data(),
+        };
+        self.send(ix, depositor).await
+    }
+
+    async fn propose_withdraw(
+        &mut self,
+        proposer: &Keypair,
+        recipient: Pubkey,
+        amount: u64,
+    ) -> Result<Pubkey, BanksClientError> {
+        self.propose_withdraw_until(proposer, recipient, amount, None)
+            .await
+    }
+
+    // Returns the address of the new proposal
+    async fn propose_withdraw_until(
+        &mut self,
+        proposer: &Keypair,
+        recipient: Pubkey,
+        amount: u64,
+        expires_at: Option<i64>,
+    ) -> Result<Pubkey, BanksClientError> {
+        let index = self.vault().await.proposal_count;
+        let proposal = proposal_address(&self.vault_state, index);
+        let ix = Instruction {
+            program_id: anchor_multisig_vault::ID,
+            accounts: anchor_multisig_vault::accounts::ProposeWithdraw {
+                proposer: proposer.pubkey(),
+                vault_state: self.vault_state,
+                proposal,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_multisig_vault::instruction::ProposeWithdraw {
+                recipient,
+                amount,
+                expires_at,
+            }
+            .data(),
+        };
+        self.send(ix, proposer).await.map(|_| proposal)
+    }
+
+    async fn approve(&mut self, owner: &Keypair, proposal: Pubkey) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_multisig_vault::ID,
+            accounts: anchor_multisig_vault::accounts::Approve {
+                owner: owner.pubkey(),
+                vault_state: self.vault_state,
+                proposal,
+            }
+            .to_account_metas(None),
+            data: anchor_multisig_vault::instruction::Approve {}.data(),
+        };
+        self.send(ix, owner).await
+    }
+
+    async fn execute(
+        &mut self,
+        executor: &Keypair,
+        proposal: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let state = self.proposal(proposal).await;
+        let ix = Instruction {
+            program_id: anchor_multisig_vault::ID,
+            accounts: anchor_multisig_vault::accounts::Execute {
+                executor: executor.pubkey(),
+                vault_state: self.vault_state,
+                vault: vault_address(&self.vault_state),
+                proposal,
+                proposer: state.proposer,
+                recipient: state.recipient,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_multisig_vault::instruction::Execute {}.data(),
+        };
+        self.send(ix, executor).await
+    }
+
+    async fn cancel_proposal(
+        &mut self,
+        owner: &Keypair,
+        proposal: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let proposer = self.proposal(proposal).await.proposer;
+        let ix = Instruction {
+            program_id: anchor_multisig_vault::ID,
+            accounts: anchor_multisig_vault::accounts::CancelProposal {
+                owner: owner.pubkey(),
+                vault_state: self.vault_state,
+                proposal,
+                proposer,
+            }
+            .to_account_metas(None),
+            data: anchor_multisig_vault::instruction::CancelProposal {}.data(),
+        };
+        self.send(ix, owner).await
+    }
+
+    async fn send(&mut self, ix: Instruction, signer: &Keypair) -> Result<(), BanksClientError> {
+        // Refresh the blockhash so identical instructions don't dedupe as the same transaction
+        let recent_blockhash = self.context.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&self.context.payer.pubkey()),
+            &[&self.context.payer, signer],
+            recent_blockhash,
+        );
+        self.context.banks_client.process_transaction(tx).await
+    }
+
+    async fn now(&mut self) -> i64 {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+    }
+
+    async fn advance_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    async fn is_closed(&mut self, address: Pubkey) -> bool {
+        self.context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .is_none()
+    }
+
+    async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(address)
+            .await
+            .unwrap()
+    }
+
+    async fn vault(&mut self) -> VaultState {
+        let account = self
+            .context
+            .banks_client
+            .get_account(self.vault_state)
+            .await
+            .unwrap()
+            .unwrap();
+        VaultState::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    async fn proposal(&mut self, address: Pubkey) -> Proposal {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        Proposal::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+}
+
+fn vault_state_address(creator: &Pubkey, seed: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"multisig", creator.as_ref(), &seed.to_le_bytes()],
+        &anchor_multisig_vault::ID,
+    )
+    .0
+}
+
+fn vault_address(vault_state: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"vault", vault_state.as_ref()],
+        &anchor_multisig_vault::ID,
+    )
+    .0
+}
+
+fn proposal_address(vault_state: &Pubkey, index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"proposal", vault_state.as_ref(), &index.to_le_bytes()],
+        &anchor_multisig_vault::ID,
+    )
+    .0
+}
+
+fn assert_error<T: std::fmt::Debug>(result: Result<T, BanksClientError>, error: VaultError) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, error as u32 + anchor_lang::error::ERROR_CODE_OFFSET)
+        }
+        other => panic!("expected {error:?}, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn executes_once_threshold_is_met() {
+    let mut test = TestMultisig::new().await;
+    let [alice, bob, _] = test.owners.each_ref().map(|owner| owner.insecure_clone());
+    let recipient = Keypair::new().pubkey();
+
+    let proposal = test
+        .propose_withdraw(&alice, recipient, LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    // The proposer's approval alone is one short of 2-of-3
+    assert_error(
+        test.execute(&alice, proposal).await,
+        VaultError::NotEnoughApprovals,
+    );
+
+    test.approve(&bob, proposal).await.unwrap();
+    test.execute(&alice, proposal).await.unwrap();
+
+    assert_eq!(test.lamports(recipient).await, LAMPORTS_PER_SOL);
+    assert_eq!(
+        test.lamports(vault_address(&test.vault_state)).await,
+        DEPOSIT - LAMPORTS_PER_SOL + Rent::default().minimum_balance(0)
+    );
+
+    // Executing closes the proposal, so it can't be paid out twice
+    assert!(test.is_closed(proposal).await);
+}
+
+#[tokio::test]
+async fn only_owners_propose_and_approve() {
+    let mut test = TestMultisig::new().await;
+    let alice = test.owners[0].insecure_clone();
+    let outsider = test.outsider.insecure_clone();
+
+    assert_error(
+        test.propose_withdraw(&outsider, outsider.pubkey(), LAMPORTS_PER_SOL)
+            .await,
+        VaultError::NotAnOwner,
+    );
+
+    let proposal = test
+        .propose_withdraw(&alice, outsider.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    assert_error(
+        test.approve(&outsider, proposal).await,
+        VaultError::NotAnOwner,
+    );
+    assert_error(
+        test.approve(&alice, proposal).await,
+        VaultError::AlreadyApproved,
+    );
+    assert_eq!(
+        test.proposal(proposal).await.approvals,
+        vec![alice.pubkey()]
+    );
+}
+
+#[tokio::test]
+async fn execute_keeps_the_vault_rent_exempt() {
+    let mut test = TestMultisig::new().await;
+    let [alice, bob, _] = test.owners.each_ref().map(|owner| owner.insecure_clone());
+
+    let proposal = test
+        .propose_withdraw(&alice, alice.pubkey(), DEPOSIT + 1)
+        .await
+        .unwrap();
+    test.approve(&bob, proposal).await.unwrap();
+
+    assert_error(
+        test.execute(&bob, proposal).await,
+        VaultError::InsufficientBalance,
+    );
+}
+
+#[tokio::test]
+async fn proposals_are_numbered() {
+    let mut test = TestMultisig::new().await;
+    let [alice, bob, _] = test.owners.each_ref().map(|owner| owner.insecure_clone());
+
+    let first = test
+        .propose_withdraw(&alice, alice.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let second = test
+        .propose_withdraw(&bob, bob.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    assert_ne!(first, second);
+    assert_eq!(test.proposal(second).await.index, 1);
+    assert_eq!(test.vault().await.proposal_count, 2);
+}
+
+#[tokio::test]
+async fn rejects_invalid_owner_sets() {
+    let mut test = TestMultisig::new().await;
+    let creator = test.outsider.insecure_clone();
+    let owner = creator.pubkey();
+
+    assert_error(
+        test.create_vault(&creator, vec![], 1).await,
+        VaultError::InvalidOwners,
+    );
+    assert_error(
+        test.create_vault(&creator, vec![owner, owner], 1).await,
+        VaultError::DuplicateOwner,
+    );
+    assert_error(
+        test.create_vault(&creator, vec![owner], 2).await,
+        VaultError::InvalidThreshold,
+    );
+    assert_error(
+        test.create_vault(&creator, vec![owner], 0).await,
+        VaultError::InvalidThreshold,
+    );
+}
+
+#[tokio::test]
+async fn pays_out_to_a_program_owned_recipient() {
+    let mut test = TestMultisig::new().await;
+    let [alice, bob, _] = test.owners.each_ref().map(|owner| owner.insecure_clone());
+
+    // e.g. another program's PDA, which a SystemAccount would have rejected
+    let recipient = Pubkey::new_unique();
+    let rent = Rent::default().minimum_balance(8);
+    test.context.set_account(
+        &recipient,
+        &Account::new(rent, 8, &anchor_multisig_vault::ID).into(),
+    );
+
+    let proposal = test
+        .propose_withdraw(&alice, recipient, LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    test.approve(&bob, proposal).await.unwrap();
+    test.execute(&bob, proposal).await.unwrap();
+
+    assert_eq!(test.lamports(recipient).await, rent + LAMPORTS_PER_SOL);
+}
+
+#[tokio::test]
+async fn expired_proposals_cannot_execute() {
+    let mut test = TestMultisig::new().await;
+    let [alice, bob, _] = test.owners.each_ref().map(|owner| owner.insecure_clone());
+    let now = test.now().await;
+
+    assert_error(
+        test.propose_withdraw_until(&alice, alice.pubkey(), LAMPORTS_PER_SOL, Some(now))
+            .await,
+        VaultError::InvalidExpiry,
+    );
+
+    let proposal = test
+        .propose_withdraw_until(&alice, alice.pubkey(), LAMPORTS_PER_SOL, Some(now + 60))
+        .await
+        .unwrap();
+    test.approve(&bob, proposal).await.unwrap();
+    test.advance_clock(60).await;
+
+    assert_error(
+        test.execute(&bob, proposal).await,
+        VaultError::ProposalExpired,
+    );
+
+    // Any owner can then clear it away
+    let proposer_lamports = test.lamports(alice.pubkey()).await;
+    test.cancel_proposal(&bob, proposal).await.unwrap();
+    assert!(test.is_closed(proposal).await);
+    assert!(test.lamports(alice.pubkey()).await > proposer_lamports);
+}
+
+#[tokio::test]
+async fn proposer_cancels_alone() {
+    let mut test = TestMultisig::new().await;
+    let alice = test.owners[0].insecure_clone();
+
+    let proposal = test
+        .propose_withdraw(&alice, alice.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let proposer_lamports = test.lamports(alice.pubkey()).await;
+    test.cancel_proposal(&alice, proposal).await.unwrap();
+
+    assert!(test.is_closed(proposal).await);
+    assert_eq!(
+        test.lamports(alice.pubkey()).await,
+        proposer_lamports + Rent::default().minimum_balance(Proposal::SIZE)
+    );
+}
+
+#[tokio::test]
+async fn other_owners_cancel_at_the_threshold() {
+    let mut test = TestMultisig::new().await;
+    let [alice, bob, carol] = test.owners.each_ref().map(|owner| owner.insecure_clone());
+    let outsider = test.outsider.insecure_clone();
+
+    let proposal = test
+        .propose_withdraw(&alice, alice.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    assert_error(
+        test.cancel_proposal(&outsider, proposal).await,
+        VaultError::NotAnOwner,
+    );
+
+    test.cancel_proposal(&bob, proposal).await.unwrap();
+    assert_error(
+        test.cancel_proposal(&bob, proposal).await,
+        VaultError::AlreadyCancelled,
+    );
+    assert_eq!(
+        test.proposal(proposal).await.cancellations,
+        vec![bob.pubkey()]
+    );
+
+    test.cancel_proposal(&carol, proposal).await.unwrap();
+    assert!(test.is_closed(proposal).await);
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+
+use crate::VaultError;
+
+// Program-wide settings, one account at [b"config"]. The guardian can pause
+// deposits, withdrawals (delegated ones too), vault settings, create_stream,
+// close_vault and migrate. claim_stream and cancel_stream keep working, and
+// only the admin can unpause. Handing over the admin
+// role takes two steps so a typo in the new key can't lock it out.
+//
+// Only the program's upgrade authority can create the config, so nobody can
+// front-run the deploy and make themselves admin.
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+    pub guardian: Pubkey,
+    pub paused: bool,
+    pub bump: u8,
+}
+
+impl Config {
+    // discriminator + admin + pending_admin + guardian + paused + bump
+    pub const SIZE: usize = 8 + 32 + (1 + 32) + 32 + 1 + 1;
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Config::SIZE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ VaultError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Either the admin or the guardian can pause
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = authority.key() == config.guardian
+            || authority.key() == config.admin @ VaultError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+}
+
+// Unpausing and changing roles
+#[derive(Accounts)]
+pub struct AdminOnly<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VaultError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.pending_admin == Some(new_admin.key()) @ VaultError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+}
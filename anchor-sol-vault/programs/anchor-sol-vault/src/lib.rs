@@ -1,10 +1,16 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
+use anchor_lang::Discriminator;
 
+pub mod config;
+pub mod limit;
+pub mod stream;
+pub use config::*;
+pub use limit::*;
+pub use stream::*;
 
 declare_id!("EikP4VFoFwUazfDZrHNp4ZjVgofW1grYy9pp55dupHHy");
 
-
 #[program]
 pub mod anchor_sol_vault {
     use super::*;
@@ -14,25 +20,83 @@ pub mod anchor_sol_vault {
     //     Ok(())
     // }
 
- pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-        // Check if amount is greater than 0
-        require!(amount > 0, VaultError::InvalidAmount);
+    pub fn initialize_config(ctx: Context<InitializeConfig>, guardian: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.pending_admin = None;
+        config.guardian = guardian;
+        config.paused = false;
+        config.bump = ctx.bumps.config;
 
-        // Transfer SOL from user to vault
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.user.to_account_info(),
-                to: ctx.accounts.vault.to_account_info(),
-            },
-        );
+        Ok(())
+    }
 
-        system_program::transfer(cpi_context, amount)?;
+    pub fn pause(ctx: Context<Pause>) -> Result<()> {
+        ctx.accounts.config.paused = true;
 
-        // Update user's balance in vault account
-        let vault = &mut ctx.accounts.vault_state;
-        vault.balance = vault.balance.checked_add(amount).ok_or(VaultError::Overflow)?;
-        vault.owner = ctx.accounts.user.key();
+        emit!(PauseEvent {
+            paused: true,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn unpause(ctx: Context<AdminOnly>) -> Result<()> {
+        ctx.accounts.config.paused = false;
+
+        emit!(PauseEvent {
+            paused: false,
+            authority: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_guardian(ctx: Context<AdminOnly>, guardian: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let previous_guardian = config.guardian;
+        config.guardian = guardian;
+
+        emit!(GuardianChangedEvent {
+            previous_guardian,
+            new_guardian: guardian,
+        });
+        Ok(())
+    }
+
+    // The new admin takes over once they call accept_admin
+    pub fn propose_admin(ctx: Context<AdminOnly>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.config.pending_admin = Some(new_admin);
+
+        emit!(AdminProposedEvent {
+            admin: ctx.accounts.admin.key(),
+            pending_admin: new_admin,
+        });
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let previous_admin = config.admin;
+        config.admin = ctx.accounts.new_admin.key();
+        config.pending_admin = None;
+
+        emit!(AdminChangedEvent {
+            previous_admin,
+            new_admin: config.admin,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        // Check if amount is greater than 0
+        require!(amount > 0, VaultError::InvalidAmount);
+
+        ctx.accounts.transfer_in(amount)?;
 
         emit!(DepositEvent {
             user: ctx.accounts.user.key(),
@@ -43,19 +107,39 @@ pub mod anchor_sol_vault {
         Ok(())
     }
 
+    // Same as deposit, but the amount can't be withdrawn before unlock_ts
+    pub fn deposit_locked(ctx: Context<Deposit>, amount: u64, unlock_ts: i64) -> Result<()> {
+        require!(amount > 0, VaultError::InvalidAmount);
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(unlock_ts > now, VaultError::InvalidUnlockTime);
+
+        ctx.accounts.transfer_in(amount)?;
+        ctx.accounts.vault_state.lock(amount, unlock_ts, now)?;
+
+        emit!(LockedDepositEvent {
+            user: ctx.accounts.user.key(),
+            amount,
+            unlock_ts,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
     pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         // Check if amount is greater than 0
         require!(amount > 0, VaultError::InvalidAmount);
 
         let vault = &mut ctx.accounts.vault_state;
-        
-        // Check if user has sufficient balance
-        require!(vault.balance >= amount, VaultError::InsufficientBalance);
+        let now = Clock::get()?.unix_timestamp;
+        vault.check_withdrawal(amount, now)?;
 
         // Create the seeds array first to extend its lifetime
         let bump_seed = ctx.bumps.vault;
-        let seeds: &[&[&[u8]]] = &[&[b"vault", &[bump_seed]]];
-        
+        let user_key = ctx.accounts.user.key();
+        let seeds: &[&[&[u8]]] = &[&[b"vault", user_key.as_ref(), &[bump_seed]]];
+
         // Transfer SOL from vault to user
         let cpi_context = CpiContext::new_with_signer(
             ctx.accounts.system_program.to_account_info(),
@@ -69,11 +153,336 @@ pub mod anchor_sol_vault {
         system_program::transfer(cpi_context, amount)?;
 
         // Update user's balance
-        vault.balance = vault.balance.checked_sub(amount).ok_or(VaultError::Overflow)?;
+        vault.balance = vault
+            .balance
+            .checked_sub(amount)
+            .ok_or(VaultError::Overflow)?;
 
         emit!(WithdrawEvent {
             user: ctx.accounts.user.key(),
             amount,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Lets `delegate` pull up to `allowance` lamports out of the vault with
+    // withdraw_as_delegate, until `expires_at` if given. Approving a delegate
+    // again replaces its allowance rather than adding to it.
+    pub fn approve_delegate(
+        ctx: Context<ConfigureVault>,
+        delegate: Pubkey,
+        allowance: u64,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        require!(allowance > 0, VaultError::InvalidAmount);
+
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(expires_at) = expires_at {
+            require!(expires_at > now, VaultError::InvalidExpiry);
+        }
+        ctx.accounts
+            .vault_state
+            .approve(delegate, allowance, expires_at, now)?;
+
+        emit!(DelegateApprovedEvent {
+            user: ctx.accounts.user.key(),
+            delegate,
+            allowance,
+            expires_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn revoke_delegate(ctx: Context<ConfigureVault>, delegate: Pubkey) -> Result<()> {
+        let delegates = &mut ctx.accounts.vault_state.delegates;
+        let index = delegates
+            .iter()
+            .position(|approved| approved.authority == delegate)
+            .ok_or(VaultError::DelegateNotFound)?;
+        delegates.remove(index);
+
+        emit!(DelegateRevokedEvent {
+            user: ctx.accounts.user.key(),
+            delegate,
+        });
+
+        Ok(())
+    }
+
+    // Same rules as withdraw, plus the delegate's own allowance and expiry.
+    // The lamports go to the delegate.
+    pub fn withdraw_as_delegate(ctx: Context<WithdrawAsDelegate>, amount: u64) -> Result<()> {
+        require!(amount > 0, VaultError::InvalidAmount);
+
+        let vault = &mut ctx.accounts.vault_state;
+        let now = Clock::get()?.unix_timestamp;
+        vault.spend_allowance(&ctx.accounts.delegate.key(), amount, now)?;
+        vault.check_withdrawal(amount, now)?;
+
+        let bump_seed = ctx.bumps.vault;
+        let owner_key = ctx.accounts.owner.key();
+        let seeds: &[&[&[u8]]] = &[&[b"vault", owner_key.as_ref(), &[bump_seed]]];
+
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.delegate.to_account_info(),
+            },
+            seeds,
+        );
+
+        system_program::transfer(cpi_context, amount)?;
+
+        vault.balance = vault
+            .balance
+            .checked_sub(amount)
+            .ok_or(VaultError::Overflow)?;
+
+        emit!(DelegateWithdrawEvent {
+            user: owner_key,
+            delegate: ctx.accounts.delegate.key(),
+            amount,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Caps withdrawals to `cap` lamports per `window` seconds, a window of 0
+    // removes the cap. Tightening applies straight away, loosening is queued
+    // for WithdrawLimit::RAISE_DELAY and then needs apply_withdraw_limit.
+    pub fn set_withdraw_limit(ctx: Context<ConfigureVault>, cap: u64, window: i64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let effective_at = ctx.accounts.vault_state.limit.set(cap, window, now)?;
+
+        emit!(WithdrawLimitEvent {
+            user: ctx.accounts.user.key(),
+            cap,
+            window,
+            effective_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn apply_withdraw_limit(ctx: Context<ConfigureVault>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let applied = ctx.accounts.vault_state.limit.apply_pending(now)?;
+
+        emit!(WithdrawLimitEvent {
+            user: ctx.accounts.user.key(),
+            cap: applied.cap,
+            window: applied.window,
+            effective_at: now,
+        });
+
+        Ok(())
+    }
+
+    // Sets aside rate * (end - start) lamports of the vault balance for
+    // `recipient`, who can claim them as they vest
+    pub fn create_stream(
+        ctx: Context<CreateStream>,
+        recipient: Pubkey,
+        rate: u64,
+        start: i64,
+        end: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            rate > 0 && end > start && end > now,
+            VaultError::InvalidStream
+        );
+        let total = rate
+            .checked_mul((end - start) as u64)
+            .ok_or(VaultError::Overflow)?;
+
+        // Setting funds aside counts as a withdrawal from the owner's point of view
+        let vault = &mut ctx.accounts.vault_state;
+        vault.check_withdrawal(total, now)?;
+        vault.balance -= total;
+        vault.reserved = vault
+            .reserved
+            .checked_add(total)
+            .ok_or(VaultError::Overflow)?;
+
+        ctx.accounts.stream.set_inner(Stream {
+            payer: ctx.accounts.user.key(),
+            recipient,
+            rate,
+            start,
+            end,
+            withdrawn: 0,
+            bump: ctx.bumps.stream,
+        });
+
+        emit!(StreamCreatedEvent {
+            user: ctx.accounts.user.key(),
+            recipient,
+            rate,
+            start,
+            end,
+        });
+
+        Ok(())
+    }
+
+    // Pays out whatever has vested so far, closing the stream once it's all paid
+    pub fn claim_stream(ctx: Context<ClaimStream>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let stream = &mut ctx.accounts.stream;
+        let amount = stream.claimable(now);
+        require!(amount > 0, VaultError::NothingToClaim);
+
+        transfer_from_vault(
+            &ctx.accounts.system_program,
+            &ctx.accounts.vault,
+            ctx.accounts.recipient.to_account_info(),
+            &stream.payer,
+            ctx.bumps.vault,
+            amount,
+        )?;
+        stream.withdrawn += amount;
+        ctx.accounts.vault_state.reserved -= amount;
+
+        emit!(StreamClaimEvent {
+            user: stream.payer,
+            recipient: stream.recipient,
+            amount,
+            timestamp: now,
+        });
+
+        if stream.withdrawn == stream.total() {
+            stream.close(ctx.accounts.payer.to_account_info())?;
+        }
+
+        Ok(())
+    }
+
+    // Pays the recipient what has vested and returns the rest to the vault balance
+    pub fn cancel_stream(ctx: Context<CancelStream>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let stream = &ctx.accounts.stream;
+        let owed = stream.claimable(now);
+        let refund = stream.total() - stream.vested(now);
+
+        if owed > 0 {
+            transfer_from_vault(
+                &ctx.accounts.system_program,
+                &ctx.accounts.vault,
+                ctx.accounts.recipient.to_account_info(),
+                &stream.payer,
+                ctx.bumps.vault,
+                owed,
+            )?;
+        }
+
+        let vault = &mut ctx.accounts.vault_state;
+        vault.reserved -= owed + refund;
+        vault.balance = vault
+            .balance
+            .checked_add(refund)
+            .ok_or(VaultError::Overflow)?;
+
+        emit!(StreamCancelledEvent {
+            user: stream.payer,
+            recipient: stream.recipient,
+            paid: owed,
+            refunded: refund,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
+        // Whatever is left in the vault is its rent, plus anything sent to it directly
+        let bump_seed = ctx.bumps.vault;
+        let user_key = ctx.accounts.user.key();
+        let seeds: &[&[&[u8]]] = &[&[b"vault", user_key.as_ref(), &[bump_seed]]];
+
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.user.to_account_info(),
+            },
+            seeds,
+        );
+
+        system_program::transfer(cpi_context, ctx.accounts.vault.lamports())?;
+
+        emit!(CloseVaultEvent {
+            user: ctx.accounts.user.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Moves a vault over from the old layout, where every user's lamports sat
+    // in one shared [b"vault"] PDA and VaultState was only owner and balance.
+    // The state grows to the current size and the user's balance moves out of
+    // the shared vault into their own.
+    pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+        let user_key = ctx.accounts.user.key();
+        let state_info = ctx.accounts.vault_state.to_account_info();
+        let legacy = LegacyVaultState::read(&state_info)?;
+        require_keys_eq!(legacy.owner, user_key, VaultError::Unauthorized);
+
+        // The user pays for the bigger state account and, if it's new, the rent of their vault
+        let rent = Rent::get()?;
+        let state_rent = rent
+            .minimum_balance(VaultState::SIZE)
+            .saturating_sub(state_info.lamports());
+        let vault_rent = rent
+            .minimum_balance(0)
+            .saturating_sub(ctx.accounts.vault.lamports());
+        for (to, amount) in [
+            (state_info.clone(), state_rent),
+            (ctx.accounts.vault.to_account_info(), vault_rent),
+        ] {
+            if amount > 0 {
+                let cpi_context = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.user.to_account_info(),
+                        to,
+                    },
+                );
+                system_program::transfer(cpi_context, amount)?;
+            }
+        }
+
+        let seeds: &[&[&[u8]]] = &[&[b"vault", &[ctx.bumps.legacy_vault]]];
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.legacy_vault.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+            },
+            seeds,
+        );
+        system_program::transfer(cpi_context, legacy.balance)?;
+
+        state_info.realloc(VaultState::SIZE, true)?;
+        let state = VaultState {
+            owner: user_key,
+            balance: legacy.balance,
+            locks: Vec::new(),
+            limit: WithdrawLimit::default(),
+            delegates: Vec::new(),
+            reserved: 0,
+        };
+        state.try_serialize(&mut &mut state_info.try_borrow_mut_data()?[..])?;
+
+        emit!(MigrateEvent {
+            user: user_key,
+            amount: legacy.balance,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
@@ -81,18 +490,47 @@ pub mod anchor_sol_vault {
     }
 }
 
+// Signed transfer out of `owner`'s vault PDA
+fn transfer_from_vault<'info>(
+    system_program: &Program<'info, System>,
+    vault: &SystemAccount<'info>,
+    to: AccountInfo<'info>,
+    owner: &Pubkey,
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let seeds: &[&[&[u8]]] = &[&[b"vault", owner.as_ref(), &[bump]]];
+    let cpi_context = CpiContext::new_with_signer(
+        system_program.to_account_info(),
+        system_program::Transfer {
+            from: vault.to_account_info(),
+            to,
+        },
+        seeds,
+    );
+
+    system_program::transfer(cpi_context, amount)
+}
+
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
-    
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ VaultError::Paused
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(
         mut,
-        seeds = [b"vault"],
+        seeds = [b"vault", user.key().as_ref()],
         bump
     )]
     pub vault: SystemAccount<'info>,
-    
+
     #[account(
         init_if_needed,
         payer = user,
@@ -101,7 +539,7 @@ pub struct Deposit<'info> {
         bump
     )]
     pub vault_state: Account<'info, VaultState>,
-    
+
     pub system_program: Program<'info, System>,
 }
 
@@ -109,14 +547,21 @@ pub struct Deposit<'info> {
 pub struct Withdraw<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
-    
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ VaultError::Paused
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(
         mut,
-        seeds = [b"vault"],
+        seeds = [b"vault", user.key().as_ref()],
         bump
     )]
     pub vault: SystemAccount<'info>,
-    
+
     #[account(
         mut,
         seeds = [b"vault_state", user.key().as_ref()],
@@ -124,18 +569,341 @@ pub struct Withdraw<'info> {
         constraint = vault_state.owner == user.key()
     )]
     pub vault_state: Account<'info, VaultState>,
-    
+
     pub system_program: Program<'info, System>,
 }
 
+impl<'info> Deposit<'info> {
+    fn transfer_in(&mut self, amount: u64) -> Result<()> {
+        // The first deposit also pays the vault's rent, which close_vault hands back
+        let rent = Rent::get()?
+            .minimum_balance(0)
+            .saturating_sub(self.vault.lamports());
+
+        // Transfer SOL from user to vault
+        let cpi_context = CpiContext::new(
+            self.system_program.to_account_info(),
+            system_program::Transfer {
+                from: self.user.to_account_info(),
+                to: self.vault.to_account_info(),
+            },
+        );
+
+        system_program::transfer(
+            cpi_context,
+            amount.checked_add(rent).ok_or(VaultError::Overflow)?,
+        )?;
+
+        // Update user's balance in vault account
+        let vault = &mut self.vault_state;
+        vault.balance = vault
+            .balance
+            .checked_add(amount)
+            .ok_or(VaultError::Overflow)?;
+        vault.owner = self.user.key();
+
+        Ok(())
+    }
+}
+
+// Owner-only settings on the vault state: withdraw limits and delegates
+#[derive(Accounts)]
+pub struct ConfigureVault<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ VaultError::Paused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump,
+        constraint = vault_state.owner == user.key()
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawAsDelegate<'info> {
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ VaultError::Paused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: only used to derive the vault addresses, vault_state checks it's the owner
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_state", owner.key().as_ref()],
+        bump,
+        constraint = vault_state.owner == owner.key()
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Closes the user's vault once everything has been withdrawn, returning the
+// rent of both the vault and its state account
+#[derive(Accounts)]
+pub struct CloseVault<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ VaultError::Paused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump,
+        constraint = vault_state.owner == user.key(),
+        constraint = vault_state.balance == 0 @ VaultError::VaultNotEmpty,
+        constraint = vault_state.reserved == 0 @ VaultError::StreamsActive
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ VaultError::Paused
+    )]
+    pub config: Account<'info, Config>,
+
+    // The shared vault every deposit went into before vaults were per user
+    #[account(
+        mut,
+        seeds = [b"vault"],
+        bump
+    )]
+    pub legacy_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: still in the legacy layout, which Account<VaultState> can't
+    /// deserialize; LegacyVaultState::read checks its owner and discriminator
+    #[account(
+        mut,
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump
+    )]
+    pub vault_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// VaultState as it was stored before vaults were per user
+pub struct LegacyVaultState {
+    pub owner: Pubkey,
+    pub balance: u64,
+}
+
+impl LegacyVaultState {
+    // discriminator + pubkey + u64
+    pub const SIZE: usize = 8 + 32 + 8;
+
+    fn read(info: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(*info.owner, crate::ID, VaultError::NotLegacyVault);
+        let data = info.try_borrow_data()?;
+        require!(
+            data.len() == Self::SIZE && data[..8] == VaultState::DISCRIMINATOR,
+            VaultError::NotLegacyVault
+        );
+
+        let owner = Pubkey::try_from(&data[8..40]).unwrap();
+        let balance = u64::from_le_bytes(data[40..48].try_into().unwrap());
+        Ok(Self { owner, balance })
+    }
+}
+
+// Each user's lamports sit in their own vault PDA, seeded by [b"vault", user],
+// so balance only ever accounts for that one vault.
+// balance includes the amounts still in locks. reserved is what's been set
+// aside for streams and not claimed yet, it's in the vault but not in balance.
 #[account]
 pub struct VaultState {
     pub owner: Pubkey,
     pub balance: u64,
+    pub locks: Vec<Lock>,
+    pub limit: WithdrawLimit,
+    pub delegates: Vec<Delegate>,
+    pub reserved: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lock {
+    pub amount: u64,
+    pub unlock_ts: i64,
+}
+
+impl Lock {
+    pub const SIZE: usize = 8 + 8;
+}
+
+// A wallet allowed to withdraw up to `allowance` on the owner's behalf
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Delegate {
+    pub authority: Pubkey,
+    pub allowance: u64,
+    pub expires_at: Option<i64>,
+}
+
+impl Delegate {
+    pub const SIZE: usize = 32 + 8 + (1 + 8);
+
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
 }
 
 impl VaultState {
-    pub const SIZE: usize = 8 + 32 + 8; // discriminator + pubkey + u64
+    pub const MAX_LOCKS: usize = 16;
+    pub const MAX_DELEGATES: usize = 8;
+    // discriminator + pubkey + u64 + locks + limit + delegates + u64
+    pub const SIZE: usize = 8
+        + 32
+        + 8
+        + (4 + Self::MAX_LOCKS * Lock::SIZE)
+        + WithdrawLimit::SIZE
+        + (4 + Self::MAX_DELEGATES * Delegate::SIZE)
+        + 8;
+
+    fn lock(&mut self, amount: u64, unlock_ts: i64, now: i64) -> Result<()> {
+        // Expired locks are plain balance by now, so their slots can be reused
+        self.release_expired_locks(now);
+        require!(self.locks.len() < Self::MAX_LOCKS, VaultError::TooManyLocks);
+
+        self.locks.push(Lock { amount, unlock_ts });
+        Ok(())
+    }
+
+    // Everything a withdrawal has to pass, whoever signs it
+    fn check_withdrawal(&mut self, amount: u64, now: i64) -> Result<()> {
+        // Check if user has sufficient balance
+        require!(self.balance >= amount, VaultError::InsufficientBalance);
+
+        // Locked deposits count towards the balance but can't leave yet.
+        // The error logs how long until enough will have unlocked.
+        self.release_expired_locks(now);
+        if let Some(unlock_at) = self.unlocks_at(amount) {
+            msg!(
+                "Funds unlock in {} seconds, at {}",
+                unlock_at - now,
+                unlock_at
+            );
+            return err!(VaultError::StillLocked);
+        }
+        self.limit.record(amount, now)
+    }
+
+    fn approve(
+        &mut self,
+        authority: Pubkey,
+        allowance: u64,
+        expires_at: Option<i64>,
+        now: i64,
+    ) -> Result<()> {
+        self.delegates
+            .retain(|delegate| delegate.authority != authority && !delegate.is_expired(now));
+        require!(
+            self.delegates.len() < Self::MAX_DELEGATES,
+            VaultError::TooManyDelegates
+        );
+
+        self.delegates.push(Delegate {
+            authority,
+            allowance,
+            expires_at,
+        });
+        Ok(())
+    }
+
+    fn spend_allowance(&mut self, authority: &Pubkey, amount: u64, now: i64) -> Result<()> {
+        let index = self
+            .delegates
+            .iter()
+            .position(|delegate| delegate.authority == *authority)
+            .ok_or(VaultError::DelegateNotFound)?;
+        let delegate = &mut self.delegates[index];
+        require!(!delegate.is_expired(now), VaultError::DelegateExpired);
+        require!(
+            delegate.allowance >= amount,
+            VaultError::DelegateAllowanceExceeded
+        );
+
+        // A spent allowance frees up the slot
+        delegate.allowance -= amount;
+        if delegate.allowance == 0 {
+            self.delegates.remove(index);
+        }
+        Ok(())
+    }
+
+    fn release_expired_locks(&mut self, now: i64) {
+        self.locks.retain(|lock| lock.unlock_ts > now);
+    }
+
+    pub fn locked_balance(&self) -> u64 {
+        self.locks.iter().map(|lock| lock.amount).sum()
+    }
+
+    // When enough locks expire to cover amount, None if it can go now.
+    // Expects expired locks to have been released.
+    fn unlocks_at(&self, amount: u64) -> Option<i64> {
+        let mut available = self.balance - self.locked_balance();
+        if amount <= available {
+            return None;
+        }
+
+        let mut locks = self.locks.clone();
+        locks.sort_by_key(|lock| lock.unlock_ts);
+        locks.iter().find_map(|lock| {
+            available += lock.amount;
+            (amount <= available).then_some(lock.unlock_ts)
+        })
+    }
 }
 
 #[event]
@@ -152,6 +920,109 @@ pub struct WithdrawEvent {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct LockedDepositEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub unlock_ts: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DelegateApprovedEvent {
+    pub user: Pubkey,
+    pub delegate: Pubkey,
+    pub allowance: u64,
+    pub expires_at: Option<i64>,
+}
+
+#[event]
+pub struct DelegateRevokedEvent {
+    pub user: Pubkey,
+    pub delegate: Pubkey,
+}
+
+#[event]
+pub struct DelegateWithdrawEvent {
+    pub user: Pubkey,
+    pub delegate: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+// effective_at is when a queued change can be applied, or now if it already was
+#[event]
+pub struct WithdrawLimitEvent {
+    pub user: Pubkey,
+    pub cap: u64,
+    pub window: i64,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct CloseVaultEvent {
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MigrateEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StreamCreatedEvent {
+    pub user: Pubkey,
+    pub recipient: Pubkey,
+    pub rate: u64,
+    pub start: i64,
+    pub end: i64,
+}
+
+#[event]
+pub struct StreamClaimEvent {
+    pub user: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StreamCancelledEvent {
+    pub user: Pubkey,
+    pub recipient: Pubkey,
+    pub paid: u64,
+    pub refunded: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseEvent {
+    pub paused: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuardianChangedEvent {
+    pub previous_guardian: Pubkey,
+    pub new_guardian: Pubkey,
+}
+
+#[event]
+pub struct AdminProposedEvent {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminChangedEvent {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
 #[error_code]
 pub enum VaultError {
     #[msg("Amount must be greater than 0")]
@@ -160,4 +1031,42 @@ pub enum VaultError {
     InsufficientBalance,
     #[msg("Arithmetic overflow")]
     Overflow,
+    #[msg("Withdraw the whole balance before closing the vault")]
+    VaultNotEmpty,
+    #[msg("Unlock time must be in the future")]
+    InvalidUnlockTime,
+    #[msg("Too many locked deposits, wait for one to unlock")]
+    TooManyLocks,
+    #[msg("Funds are still locked")]
+    StillLocked,
+    #[msg("Withdraw limit needs a cap above 0 and a window of at most 30 days")]
+    InvalidWithdrawLimit,
+    #[msg("Withdrawal is over the limit for this window")]
+    WithdrawLimitExceeded,
+    #[msg("No withdraw limit change is pending")]
+    NoPendingLimit,
+    #[msg("Withdraw limit change is still timelocked")]
+    LimitChangeNotReady,
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Too many delegates, revoke one first")]
+    TooManyDelegates,
+    #[msg("Signer is not a delegate of this vault")]
+    DelegateNotFound,
+    #[msg("Delegate approval has expired")]
+    DelegateExpired,
+    #[msg("Amount is over the delegate's allowance")]
+    DelegateAllowanceExceeded,
+    #[msg("Vaults are paused")]
+    Paused,
+    #[msg("Signer isn't allowed to change the config")]
+    Unauthorized,
+    #[msg("Stream needs a rate above 0 and an end after its start and now")]
+    InvalidStream,
+    #[msg("Nothing has vested since the last claim")]
+    NothingToClaim,
+    #[msg("Cancel or pay out every stream before closing the vault")]
+    StreamsActive,
+    #[msg("Vault state isn't in the legacy layout, nothing to migrate")]
+    NotLegacyVault,
 }
@@ -0,0 +1,155 @@
+use anchor_lang::prelude::*;
+
+use crate::{Config, VaultError, VaultState};
+
+// Pays `recipient` `rate` lamports a second from `start` to `end` out of the
+// payer's vault. The whole amount is set aside from the vault balance when the
+// stream is created, so the payer can't withdraw it from under the recipient.
+// One stream per payer and recipient at a time.
+#[account]
+pub struct Stream {
+    pub payer: Pubkey,
+    pub recipient: Pubkey,
+    pub rate: u64,
+    pub start: i64,
+    pub end: i64,
+    pub withdrawn: u64,
+    pub bump: u8,
+}
+
+impl Stream {
+    // discriminator + payer + recipient + rate + start + end + withdrawn + bump
+    pub const SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1;
+
+    pub fn total(&self) -> u64 {
+        // Checked when the stream was created
+        self.rate * (self.end - self.start) as u64
+    }
+
+    pub fn vested(&self, now: i64) -> u64 {
+        let elapsed = now.clamp(self.start, self.end) - self.start;
+        self.rate * elapsed as u64
+    }
+
+    pub fn claimable(&self, now: i64) -> u64 {
+        self.vested(now) - self.withdrawn
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(recipient: Pubkey)]
+pub struct CreateStream<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ VaultError::Paused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump,
+        constraint = vault_state.owner == user.key()
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        init,
+        payer = user,
+        space = Stream::SIZE,
+        seeds = [b"stream", user.key().as_ref(), recipient.as_ref()],
+        bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStream<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ VaultError::Paused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: checked against stream.payer, gets the stream's rent back once it's paid out
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", payer.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_state", payer.key().as_ref()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        has_one = payer,
+        has_one = recipient,
+        seeds = [b"stream", payer.key().as_ref(), recipient.key().as_ref()],
+        bump = stream.bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelStream<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ VaultError::Paused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump,
+        constraint = vault_state.owner == user.key()
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close = user,
+        constraint = stream.payer == user.key(),
+        has_one = recipient,
+        seeds = [b"stream", user.key().as_ref(), recipient.key().as_ref()],
+        bump = stream.bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    pub system_program: Program<'info, System>,
+}
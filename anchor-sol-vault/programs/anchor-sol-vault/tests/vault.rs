@@ -0,0 +1,1223 @@
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::entrypoint::ProgramResult,
+    AccountDeserialize, Discriminator, InstructionData, ToAccountMetas,
+};
+use anchor_sol_vault::{LegacyVaultState, PendingLimit, VaultError, VaultState, WithdrawLimit};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+const STARTING_LAMPORTS: u64 = 10 * LAMPORTS_PER_SOL;
+const DEPOSIT: u64 = LAMPORTS_PER_SOL;
+const DAY: i64 = 24 * 60 * 60;
+
+// Anchor's entrypoint ties the account slice to the `AccountInfo` lifetime,
+// which `processor!` can't express, so leak the slice for the test run.
+fn entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    anchor_sol_vault::entry(program_id, accounts, data)
+}
+
+// The test payer is the program's upgrade authority and the config admin
+struct TestVault {
+    context: ProgramTestContext,
+    alice: Keypair,
+    bob: Keypair,
+    guardian: Keypair,
+}
+
+impl TestVault {
+    async fn new() -> Self {
+        let mut test = Self::without_config().await;
+        let admin = test.context.payer.insecure_clone();
+        test.initialize_config(&admin).await.unwrap();
+        test
+    }
+
+    async fn without_config() -> Self {
+        let mut program_test =
+            ProgramTest::new("anchor_sol_vault", anchor_sol_vault::ID, processor!(entry));
+        let (alice, bob, guardian) = (Keypair::new(), Keypair::new(), Keypair::new());
+        for user in [&alice, &bob, &guardian] {
+            program_test.add_account(
+                user.pubkey(),
+                Account::new(STARTING_LAMPORTS, 0, &system_program::ID),
+            );
+        }
+
+        let mut context = program_test.start_with_context().await;
+        // processor! registers the program as a builtin, so stand in the
+        // ProgramData account an upgradeable deploy would have created
+        let program_data = Account::new_data(
+            LAMPORTS_PER_SOL,
+            &UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(context.payer.pubkey()),
+            },
+            &bpf_loader_upgradeable::ID,
+        )
+        .unwrap();
+        context.set_account(&program_data_address(), &program_data.into());
+
+        Self {
+            context,
+            alice,
+            bob,
+            guardian,
+        }
+    }
+
+    async fn initialize_config(&mut self, admin: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::InitializeConfig {
+                admin: admin.pubkey(),
+                config: config_address(),
+                program_data: program_data_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::InitializeConfig {
+                guardian: self.guardian.pubkey(),
+            }
+            .data(),
+        };
+        self.send(ix, admin).await
+    }
+
+    async fn deposit(&mut self, user: &Keypair, amount: u64) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::Deposit {
+                user: user.pubkey(),
+                vault: vault_address(&user.pubkey()),
+                vault_state: vault_state_address(&user.pubkey()),
+                config: config_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::Deposit { amount }.data(),
+        };
+        self.send(ix, user).await
+    }
+
+    async fn deposit_locked(
+        &mut self,
+        user: &Keypair,
+        amount: u64,
+        unlock_ts: i64,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::Deposit {
+                user: user.pubkey(),
+                vault: vault_address(&user.pubkey()),
+                vault_state: vault_state_address(&user.pubkey()),
+                config: config_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::DepositLocked { amount, unlock_ts }.data(),
+        };
+        self.send(ix, user).await
+    }
+
+    async fn withdraw(&mut self, user: &Keypair, amount: u64) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::Withdraw {
+                user: user.pubkey(),
+                vault: vault_address(&user.pubkey()),
+                vault_state: vault_state_address(&user.pubkey()),
+                config: config_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::Withdraw { amount }.data(),
+        };
+        self.send(ix, user).await
+    }
+
+    async fn close_vault(&mut self, user: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::CloseVault {
+                user: user.pubkey(),
+                vault: vault_address(&user.pubkey()),
+                vault_state: vault_state_address(&user.pubkey()),
+                config: config_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::CloseVault {}.data(),
+        };
+        self.send(ix, user).await
+    }
+
+    async fn migrate(&mut self, user: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::Migrate {
+                user: user.pubkey(),
+                legacy_vault: legacy_vault_address(),
+                vault: vault_address(&user.pubkey()),
+                vault_state: vault_state_address(&user.pubkey()),
+                config: config_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::Migrate {}.data(),
+        };
+        self.send(ix, user).await
+    }
+
+    // What a deposit of `balance` left behind before vaults were per user
+    async fn add_legacy_vault(&mut self, user: &Pubkey, balance: u64) {
+        let mut data = VaultState::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(user.as_ref());
+        data.extend_from_slice(&balance.to_le_bytes());
+        let state = Account {
+            lamports: Rent::default().minimum_balance(LegacyVaultState::SIZE),
+            data,
+            owner: anchor_sol_vault::ID,
+            ..Account::default()
+        };
+        self.context
+            .set_account(&vault_state_address(user), &state.into());
+
+        let lamports = self.lamports(legacy_vault_address()).await + balance;
+        let legacy_vault = Account::new(lamports, 0, &system_program::ID);
+        self.context
+            .set_account(&legacy_vault_address(), &legacy_vault.into());
+    }
+
+    async fn set_withdraw_limit(
+        &mut self,
+        user: &Keypair,
+        cap: u64,
+        window: i64,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::ConfigureVault {
+                user: user.pubkey(),
+                vault_state: vault_state_address(&user.pubkey()),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::SetWithdrawLimit { cap, window }.data(),
+        };
+        self.send(ix, user).await
+    }
+
+    async fn apply_withdraw_limit(&mut self, user: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::ConfigureVault {
+                user: user.pubkey(),
+                vault_state: vault_state_address(&user.pubkey()),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::ApplyWithdrawLimit {}.data(),
+        };
+        self.send(ix, user).await
+    }
+
+    async fn approve_delegate(
+        &mut self,
+        user: &Keypair,
+        delegate: Pubkey,
+        allowance: u64,
+        expires_at: Option<i64>,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::ConfigureVault {
+                user: user.pubkey(),
+                vault_state: vault_state_address(&user.pubkey()),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::ApproveDelegate {
+                delegate,
+                allowance,
+                expires_at,
+            }
+            .data(),
+        };
+        self.send(ix, user).await
+    }
+
+    async fn revoke_delegate(
+        &mut self,
+        user: &Keypair,
+        delegate: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::ConfigureVault {
+                user: user.pubkey(),
+                vault_state: vault_state_address(&user.pubkey()),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::RevokeDelegate { delegate }.data(),
+        };
+        self.send(ix, user).await
+    }
+
+    async fn withdraw_as_delegate(
+        &mut self,
+        delegate: &Keypair,
+        owner: &Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::WithdrawAsDelegate {
+                delegate: delegate.pubkey(),
+                owner: *owner,
+                vault: vault_address(owner),
+                vault_state: vault_state_address(owner),
+                config: config_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::WithdrawAsDelegate { amount }.data(),
+        };
+        self.send(ix, delegate).await
+    }
+
+    async fn create_stream(
+        &mut self,
+        user: &Keypair,
+        recipient: Pubkey,
+        rate: u64,
+        start: i64,
+        end: i64,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::CreateStream {
+                user: user.pubkey(),
+                config: config_address(),
+                vault_state: vault_state_address(&user.pubkey()),
+                stream: stream_address(&user.pubkey(), &recipient),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::CreateStream {
+                recipient,
+                rate,
+                start,
+                end,
+            }
+            .data(),
+        };
+        self.send(ix, user).await
+    }
+
+    async fn claim_stream(
+        &mut self,
+        recipient: &Keypair,
+        payer: &Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::ClaimStream {
+                recipient: recipient.pubkey(),
+                config: config_address(),
+                payer: *payer,
+                vault: vault_address(payer),
+                vault_state: vault_state_address(payer),
+                stream: stream_address(payer, &recipient.pubkey()),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::ClaimStream {}.data(),
+        };
+        self.send(ix, recipient).await
+    }
+
+    async fn cancel_stream(
+        &mut self,
+        user: &Keypair,
+        recipient: &Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::CancelStream {
+                user: user.pubkey(),
+                config: config_address(),
+                recipient: *recipient,
+                vault: vault_address(&user.pubkey()),
+                vault_state: vault_state_address(&user.pubkey()),
+                stream: stream_address(&user.pubkey(), recipient),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::CancelStream {}.data(),
+        };
+        self.send(ix, user).await
+    }
+
+    async fn pause(&mut self, authority: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::Pause {
+                authority: authority.pubkey(),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::Pause {}.data(),
+        };
+        self.send(ix, authority).await
+    }
+
+    async fn unpause(&mut self, admin: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::AdminOnly {
+                admin: admin.pubkey(),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::Unpause {}.data(),
+        };
+        self.send(ix, admin).await
+    }
+
+    async fn propose_admin(
+        &mut self,
+        admin: &Keypair,
+        new_admin: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::AdminOnly {
+                admin: admin.pubkey(),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::ProposeAdmin { new_admin }.data(),
+        };
+        self.send(ix, admin).await
+    }
+
+    async fn accept_admin(&mut self, new_admin: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::AcceptAdmin {
+                new_admin: new_admin.pubkey(),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::AcceptAdmin {}.data(),
+        };
+        self.send(ix, new_admin).await
+    }
+
+    async fn send(&mut self, ix: Instruction, signer: &Keypair) -> Result<(), BanksClientError> {
+        // Refresh the blockhash so identical instructions don't dedupe as the same transaction
+        let recent_blockhash = self.context.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&self.context.payer.pubkey()),
+            &[&self.context.payer, signer],
+            recent_blockhash,
+        );
+        self.context.banks_client.process_transaction(tx).await
+    }
+
+    // The program logs of a withdrawal, which fail or not
+    async fn withdraw_logs(&mut self, user: &Keypair, amount: u64) -> Vec<String> {
+        let ix = Instruction {
+            program_id: anchor_sol_vault::ID,
+            accounts: anchor_sol_vault::accounts::Withdraw {
+                user: user.pubkey(),
+                vault: vault_address(&user.pubkey()),
+                vault_state: vault_state_address(&user.pubkey()),
+                config: config_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_sol_vault::instruction::Withdraw { amount }.data(),
+        };
+        let recent_blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&self.context.payer.pubkey()),
+            &[&self.context.payer, user],
+            recent_blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction_with_metadata(tx)
+            .await
+            .unwrap()
+            .metadata
+            .unwrap()
+            .log_messages
+    }
+
+    async fn now(&mut self) -> i64 {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+    }
+
+    async fn advance_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(address)
+            .await
+            .unwrap()
+    }
+
+    async fn vault_state(&mut self, user: &Pubkey) -> Option<VaultState> {
+        self.context
+            .banks_client
+            .get_account(vault_state_address(user))
+            .await
+            .unwrap()
+            .map(|account| VaultState::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+}
+
+fn vault_address(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", user.as_ref()], &anchor_sol_vault::ID).0
+}
+
+fn legacy_vault_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"vault"], &anchor_sol_vault::ID).0
+}
+
+fn vault_state_address(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault_state", user.as_ref()], &anchor_sol_vault::ID).0
+}
+
+fn stream_address(payer: &Pubkey, recipient: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"stream", payer.as_ref(), recipient.as_ref()],
+        &anchor_sol_vault::ID,
+    )
+    .0
+}
+
+fn config_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &anchor_sol_vault::ID).0
+}
+
+fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(
+        &[anchor_sol_vault::ID.as_ref()],
+        &bpf_loader_upgradeable::ID,
+    )
+    .0
+}
+
+fn vault_rent() -> u64 {
+    Rent::default().minimum_balance(0)
+}
+
+fn assert_error(result: Result<(), BanksClientError>, error: VaultError) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, error as u32 + anchor_lang::error::ERROR_CODE_OFFSET)
+        }
+        other => panic!("expected {error:?}, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn each_user_gets_their_own_vault() {
+    let mut test = TestVault::new().await;
+    let (alice, bob) = (test.alice.insecure_clone(), test.bob.insecure_clone());
+
+    test.deposit(&alice, DEPOSIT).await.unwrap();
+    test.deposit(&bob, 2 * DEPOSIT).await.unwrap();
+
+    // The first deposit also funds the vault's rent
+    assert_eq!(
+        test.lamports(vault_address(&alice.pubkey())).await,
+        DEPOSIT + vault_rent()
+    );
+    assert_eq!(
+        test.lamports(vault_address(&bob.pubkey())).await,
+        2 * DEPOSIT + vault_rent()
+    );
+
+    test.deposit(&alice, DEPOSIT).await.unwrap();
+    assert_eq!(
+        test.lamports(vault_address(&alice.pubkey())).await,
+        2 * DEPOSIT + vault_rent()
+    );
+    assert_eq!(
+        test.vault_state(&alice.pubkey()).await.unwrap().balance,
+        2 * DEPOSIT
+    );
+}
+
+#[tokio::test]
+async fn rejects_empty_amounts() {
+    let mut test = TestVault::new().await;
+    let alice = test.alice.insecure_clone();
+
+    assert_error(test.deposit(&alice, 0).await, VaultError::InvalidAmount);
+
+    test.deposit(&alice, DEPOSIT).await.unwrap();
+    assert_error(test.withdraw(&alice, 0).await, VaultError::InvalidAmount);
+}
+
+#[tokio::test]
+async fn withdraw_is_limited_to_own_balance() {
+    let mut test = TestVault::new().await;
+    let (alice, bob) = (test.alice.insecure_clone(), test.bob.insecure_clone());
+    test.deposit(&alice, 5 * DEPOSIT).await.unwrap();
+    test.deposit(&bob, DEPOSIT).await.unwrap();
+
+    // Alice's funds are in another account, out of reach of Bob's withdrawals
+    assert_error(
+        test.withdraw(&bob, 2 * DEPOSIT).await,
+        VaultError::InsufficientBalance,
+    );
+
+    test.withdraw(&bob, DEPOSIT).await.unwrap();
+    assert_eq!(
+        test.lamports(vault_address(&bob.pubkey())).await,
+        vault_rent()
+    );
+    assert_eq!(
+        test.lamports(vault_address(&alice.pubkey())).await,
+        5 * DEPOSIT + vault_rent()
+    );
+}
+
+#[tokio::test]
+async fn close_vault_needs_an_empty_balance() {
+    let mut test = TestVault::new().await;
+    let alice = test.alice.insecure_clone();
+    test.deposit(&alice, DEPOSIT).await.unwrap();
+
+    assert_error(test.close_vault(&alice).await, VaultError::VaultNotEmpty);
+}
+
+#[tokio::test]
+async fn close_vault_returns_the_rent() {
+    let mut test = TestVault::new().await;
+    let alice = test.alice.insecure_clone();
+    test.deposit(&alice, DEPOSIT).await.unwrap();
+    test.withdraw(&alice, DEPOSIT).await.unwrap();
+
+    test.close_vault(&alice).await.unwrap();
+
+    assert!(test.vault_state(&alice.pubkey()).await.is_none());
+    assert_eq!(test.lamports(vault_address(&alice.pubkey())).await, 0);
+    assert_eq!(test.lamports(alice.pubkey()).await, STARTING_LAMPORTS);
+
+    // The vault can be opened again afterwards
+    test.deposit(&alice, DEPOSIT).await.unwrap();
+    assert_eq!(
+        test.vault_state(&alice.pubkey()).await.unwrap().balance,
+        DEPOSIT
+    );
+}
+
+#[tokio::test]
+async fn locked_deposit_waits_for_unlock_time() {
+    let mut test = TestVault::new().await;
+    let alice = test.alice.insecure_clone();
+    let unlock_ts = test.now().await + DAY;
+
+    test.deposit_locked(&alice, DEPOSIT, unlock_ts)
+        .await
+        .unwrap();
+    let state = test.vault_state(&alice.pubkey()).await.unwrap();
+    assert_eq!(state.balance, DEPOSIT);
+    assert_eq!(state.locked_balance(), DEPOSIT);
+
+    assert_error(
+        test.withdraw(&alice, DEPOSIT).await,
+        VaultError::StillLocked,
+    );
+
+    test.advance_clock(DAY - 1).await;
+    assert_error(test.withdraw(&alice, 1).await, VaultError::StillLocked);
+
+    test.advance_clock(1).await;
+    test.withdraw(&alice, DEPOSIT).await.unwrap();
+
+    let state = test.vault_state(&alice.pubkey()).await.unwrap();
+    assert_eq!(state.balance, 0);
+    assert!(state.locks.is_empty());
+}
+
+#[tokio::test]
+async fn still_locked_error_logs_the_time_left() {
+    let mut test = TestVault::new().await;
+    let alice = test.alice.insecure_clone();
+    let now = test.now().await;
+
+    test.deposit_locked(&alice, DEPOSIT, now + 2 * DAY)
+        .await
+        .unwrap();
+    test.deposit_locked(&alice, DEPOSIT, now + DAY)
+        .await
+        .unwrap();
+
+    // One lock covers it, so it's the earlier one that matters
+    let logs = test.withdraw_logs(&alice, DEPOSIT).await;
+    assert!(logs.iter().any(|log| log.contains("StillLocked")));
+    assert!(logs.contains(&format!(
+        "Program log: Funds unlock in {DAY} seconds, at {}",
+        now + DAY
+    )));
+}
+
+#[tokio::test]
+async fn unlocked_balance_stays_available() {
+    let mut test = TestVault::new().await;
+    let alice = test.alice.insecure_clone();
+    let now = test.now().await;
+
+    test.deposit(&alice, DEPOSIT).await.unwrap();
+    test.deposit_locked(&alice, 2 * DEPOSIT, now + DAY)
+        .await
+        .unwrap();
+    test.deposit_locked(&alice, 3 * DEPOSIT, now + 2 * DAY)
+        .await
+        .unwrap();
+
+    test.withdraw(&alice, DEPOSIT).await.unwrap();
+    assert_error(test.withdraw(&alice, 1).await, VaultError::StillLocked);
+
+    // Only the first lock has expired
+    test.advance_clock(DAY).await;
+    assert_error(
+        test.withdraw(&alice, 2 * DEPOSIT + 1).await,
+        VaultError::StillLocked,
+    );
+    test.withdraw(&alice, 2 * DEPOSIT).await.unwrap();
+
+    // Asking for more than the vault holds is still a plain balance error
+    assert_error(
+        test.withdraw(&alice, 4 * DEPOSIT).await,
+        VaultError::InsufficientBalance,
+    );
+
+    let state = test.vault_state(&alice.pubkey()).await.unwrap();
+    assert_eq!(state.locks.len(), 1);
+    assert_eq!(state.locked_balance(), 3 * DEPOSIT);
+}
+
+#[tokio::test]
+async fn unlock_time_must_be_in_the_future() {
+    let mut test = TestVault::new().await;
+    let alice = test.alice.insecure_clone();
+    let now = test.now().await;
+
+    assert_error(
+        test.deposit_locked(&alice, DEPOSIT, now).await,
+        VaultError::InvalidUnlockTime,
+    );
+}
+
+#[tokio::test]
+async fn lock_slots_are_reused_once_expired() {
+    let mut test = TestVault::new().await;
+    let alice = test.alice.insecure_clone();
+    let now = test.now().await;
+
+    for i in 0..VaultState::MAX_LOCKS as i64 {
+        test.deposit_locked(&alice, DEPOSIT / 100, now + DAY + i)
+            .await
+            .unwrap();
+    }
+    assert_error(
+        test.deposit_locked(&alice, DEPOSIT / 100, now + 2 * DAY)
+            .await,
+        VaultError::TooManyLocks,
+    );
+
+    test.advance_clock(DAY).await;
+    test.deposit_locked(&alice, DEPOSIT / 100, now + 2 * DAY)
+        .await
+        .unwrap();
+    assert_eq!(
+        test.vault_state(&alice.pubkey()).await.unwrap().locks.len(),
+        VaultState::MAX_LOCKS
+    );
+}
+
+#[tokio::test]
+async fn withdrawals_are_capped_per_window() {
+    let mut test = TestVault::new().await;
+    let alice = test.alice.insecure_clone();
+    test.deposit(&alice, 5 * DEPOSIT).await.unwrap();
+    test.set_withdraw_limit(&alice, 2 * DEPOSIT, DAY)
+        .await
+        .unwrap();
+
+    test.withdraw(&alice, DEPOSIT).await.unwrap();
+    test.advance_clock(DAY / 2).await;
+    test.withdraw(&alice, DEPOSIT).await.unwrap();
+    assert_error(
+        test.withdraw(&alice, 1).await,
+        VaultError::WithdrawLimitExceeded,
+    );
+
+    // The window rolls, so only the first withdrawal has aged out
+    test.advance_clock(DAY / 2).await;
+    assert_error(
+        test.withdraw(&alice, DEPOSIT + 1).await,
+        VaultError::WithdrawLimitExceeded,
+    );
+    test.withdraw(&alice, DEPOSIT).await.unwrap();
+
+    assert_eq!(
+        test.vault_state(&alice.pubkey()).await.unwrap().balance,
+        2 * DEPOSIT
+    );
+}
+
+#[tokio::test]
+async fn tightening_the_limit_applies_at_once() {
+    let mut test = TestVault::new().await;
+    let alice = test.alice.insecure_clone();
+    test.deposit(&alice, 5 * DEPOSIT).await.unwrap();
+    test.set_withdraw_limit(&alice, 2 * DEPOSIT, DAY)
+        .await
+        .unwrap();
+
+    test.set_withdraw_limit(&alice, DEPOSIT, 2 * DAY)
+        .await
+        .unwrap();
+
+    let limit = test.vault_state(&alice.pubkey()).await.unwrap().limit;
+    assert_eq!((limit.cap, limit.window), (DEPOSIT, 2 * DAY));
+    assert_eq!(limit.pending, None);
+    assert_error(
+        test.withdraw(&alice, DEPOSIT + 1).await,
+        VaultError::WithdrawLimitExceeded,
+    );
+}
+
+#[tokio::test]
+async fn raising_the_limit_is_timelocked() {
+    let mut test = TestVault::new().await;
+    let alice = test.alice.insecure_clone();
+    test.deposit(&alice, 5 * DEPOSIT).await.unwrap();
+    test.set_withdraw_limit(&alice, DEPOSIT, DAY).await.unwrap();
+
+    let now = test.now().await;
+    test.set_withdraw_limit(&alice, 5 * DEPOSIT, DAY)
+        .await
+        .unwrap();
+    let limit = test.vault_state(&alice.pubkey()).await.unwrap().limit;
+    assert_eq!(limit.cap, DEPOSIT);
+    assert_eq!(
+        limit.pending,
+        Some(PendingLimit {
+            cap: 5 * DEPOSIT,
+            window: DAY,
+            effective_at: now + WithdrawLimit::RAISE_DELAY,
+        })
+    );
+
+    // A stolen key still only gets the old cap out while the change waits
+    assert_error(
+        test.withdraw(&alice, 2 * DEPOSIT).await,
+        VaultError::WithdrawLimitExceeded,
+    );
+    test.advance_clock(WithdrawLimit::RAISE_DELAY - 1).await;
+    assert_error(
+        test.apply_withdraw_limit(&alice).await,
+        VaultError::LimitChangeNotReady,
+    );
+
+    test.advance_clock(1).await;
+    test.apply_withdraw_limit(&alice).await.unwrap();
+    test.withdraw(&alice, 5 * DEPOSIT).await.unwrap();
+
+    assert_error(
+        test.apply_withdraw_limit(&alice).await,
+        VaultError::NoPendingLimit,
+    );
+}
+
+#[tokio::test]
+async fn removing_the_limit_is_timelocked() {
+    let mut test = TestVault::new().await;
+    let alice = test.alice.insecure_clone();
+    test.deposit(&alice, 5 * DEPOSIT).await.unwrap();
+    test.set_withdraw_limit(&alice, DEPOSIT, DAY).await.unwrap();
+
+    test.set_withdraw_limit(&alice, 0, 0).await.unwrap();
+    test.advance_clock(WithdrawLimit::RAISE_DELAY).await;
+    test.apply_withdraw_limit(&alice).await.unwrap();
+
+    test.withdraw(&alice, 5 * DEPOSIT).await.unwrap();
+}
+
+#[tokio::test]
+async fn rejects_invalid_limits() {
+    let mut test = TestVault::new().await;
+    let alice = test.alice.insecure_clone();
+    test.deposit(&alice, DEPOSIT).await.unwrap();
+
+    assert_error(
+        test.set_withdraw_limit(&alice, 0, DAY).await,
+        VaultError::InvalidWithdrawLimit,
+    );
+    assert_error(
+        test.set_withdraw_limit(&alice, DEPOSIT, -1).await,
+        VaultError::InvalidWithdrawLimit,
+    );
+    assert_error(
+        test.set_withdraw_limit(&alice, DEPOSIT, WithdrawLimit::MAX_WINDOW + 1)
+            .await,
+        VaultError::InvalidWithdrawLimit,
+    );
+}
+
+#[tokio::test]
+async fn delegate_withdraws_up_to_its_allowance() {
+    let mut test = TestVault::new().await;
+    let (alice, bob) = (test.alice.insecure_clone(), test.bob.insecure_clone());
+    test.deposit(&alice, 5 * DEPOSIT).await.unwrap();
+    test.approve_delegate(&alice, bob.pubkey(), 2 * DEPOSIT, None)
+        .await
+        .unwrap();
+
+    test.withdraw_as_delegate(&bob, &alice.pubkey(), DEPOSIT)
+        .await
+        .unwrap();
+    assert_error(
+        test.withdraw_as_delegate(&bob, &alice.pubkey(), DEPOSIT + 1)
+            .await,
+        VaultError::DelegateAllowanceExceeded,
+    );
+    test.withdraw_as_delegate(&bob, &alice.pubkey(), DEPOSIT)
+        .await
+        .unwrap();
+
+    // The lamports go to the delegate, and a spent allowance is dropped
+    assert_eq!(
+        test.lamports(bob.pubkey()).await,
+        STARTING_LAMPORTS + 2 * DEPOSIT
+    );
+    let state = test.vault_state(&alice.pubkey()).await.unwrap();
+    assert_eq!(state.balance, 3 * DEPOSIT);
+    assert!(state.delegates.is_empty());
+    assert_error(
+        test.withdraw_as_delegate(&bob, &alice.pubkey(), 1).await,
+        VaultError::DelegateNotFound,
+    );
+}
+
+#[tokio::test]
+async fn delegate_approval_expires() {
+    let mut test = TestVault::new().await;
+    let (alice, bob) = (test.alice.insecure_clone(), test.bob.insecure_clone());
+    let now = test.now().await;
+    test.deposit(&alice, 5 * DEPOSIT).await.unwrap();
+
+    assert_error(
+        test.approve_delegate(&alice, bob.pubkey(), DEPOSIT, Some(now))
+            .await,
+        VaultError::InvalidExpiry,
+    );
+    test.approve_delegate(&alice, bob.pubkey(), DEPOSIT, Some(now + DAY))
+        .await
+        .unwrap();
+
+    test.advance_clock(DAY).await;
+    assert_error(
+        test.withdraw_as_delegate(&bob, &alice.pubkey(), 1).await,
+        VaultError::DelegateExpired,
+    );
+}
+
+#[tokio::test]
+async fn revoked_delegate_cannot_withdraw() {
+    let mut test = TestVault::new().await;
+    let (alice, bob) = (test.alice.insecure_clone(), test.bob.insecure_clone());
+    test.deposit(&alice, 5 * DEPOSIT).await.unwrap();
+    test.approve_delegate(&alice, bob.pubkey(), DEPOSIT, None)
+        .await
+        .unwrap();
+
+    test.revoke_delegate(&alice, bob.pubkey()).await.unwrap();
+
+    assert_error(
+        test.withdraw_as_delegate(&bob, &alice.pubkey(), 1).await,
+        VaultError::DelegateNotFound,
+    );
+    assert_error(
+        test.revoke_delegate(&alice, bob.pubkey()).await,
+        VaultError::DelegateNotFound,
+    );
+}
+
+#[tokio::test]
+async fn delegate_withdrawals_count_against_the_limit() {
+    let mut test = TestVault::new().await;
+    let (alice, bob) = (test.alice.insecure_clone(), test.bob.insecure_clone());
+    test.deposit(&alice, 5 * DEPOSIT).await.unwrap();
+    test.set_withdraw_limit(&alice, 2 * DEPOSIT, DAY)
+        .await
+        .unwrap();
+    test.approve_delegate(&alice, bob.pubkey(), 5 * DEPOSIT, None)
+        .await
+        .unwrap();
+
+    test.withdraw(&alice, DEPOSIT).await.unwrap();
+    test.withdraw_as_delegate(&bob, &alice.pubkey(), DEPOSIT)
+        .await
+        .unwrap();
+    assert_error(
+        test.withdraw_as_delegate(&bob, &alice.pubkey(), 1).await,
+        VaultError::WithdrawLimitExceeded,
+    );
+}
+
+#[tokio::test]
+async fn only_the_upgrade_authority_initializes_the_config() {
+    let mut test = TestVault::without_config().await;
+    let alice = test.alice.insecure_clone();
+    let admin = test.context.payer.insecure_clone();
+
+    assert_error(
+        test.initialize_config(&alice).await,
+        VaultError::Unauthorized,
+    );
+    test.initialize_config(&admin).await.unwrap();
+    test.pause(&admin).await.unwrap();
+}
+
+#[tokio::test]
+async fn guardian_pauses_and_admin_unpauses() {
+    let mut test = TestVault::new().await;
+    let (alice, guardian) = (test.alice.insecure_clone(), test.guardian.insecure_clone());
+    let admin = test.context.payer.insecure_clone();
+    test.deposit(&alice, DEPOSIT).await.unwrap();
+
+    assert_error(test.pause(&alice).await, VaultError::Unauthorized);
+    test.pause(&guardian).await.unwrap();
+
+    assert_error(test.deposit(&alice, DEPOSIT).await, VaultError::Paused);
+    assert_error(test.withdraw(&alice, DEPOSIT).await, VaultError::Paused);
+    assert_error(
+        test.set_withdraw_limit(&alice, DEPOSIT, DAY).await,
+        VaultError::Paused,
+    );
+    assert_error(test.close_vault(&alice).await, VaultError::Paused);
+
+    // The guardian can only pull the brake
+    assert_error(test.unpause(&guardian).await, VaultError::Unauthorized);
+    test.unpause(&admin).await.unwrap();
+    test.withdraw(&alice, DEPOSIT).await.unwrap();
+}
+
+#[tokio::test]
+async fn admin_handover_takes_two_steps() {
+    let mut test = TestVault::new().await;
+    let (alice, bob) = (test.alice.insecure_clone(), test.bob.insecure_clone());
+    let admin = test.context.payer.insecure_clone();
+
+    test.propose_admin(&admin, bob.pubkey()).await.unwrap();
+    assert_error(test.accept_admin(&alice).await, VaultError::Unauthorized);
+
+    // Until bob accepts, the old admin is still in charge
+    test.pause(&admin).await.unwrap();
+    test.unpause(&admin).await.unwrap();
+
+    test.accept_admin(&bob).await.unwrap();
+    test.pause(&bob).await.unwrap();
+    assert_error(test.unpause(&admin).await, VaultError::Unauthorized);
+    test.unpause(&bob).await.unwrap();
+}
+
+// DEPOSIT over a day, rounded down to whole lamports a second
+const RATE: u64 = DEPOSIT / DAY as u64;
+
+#[tokio::test]
+async fn stream_vests_over_time() {
+    let mut test = TestVault::new().await;
+    let (alice, bob) = (test.alice.insecure_clone(), test.bob.insecure_clone());
+    let now = test.now().await;
+    test.deposit(&alice, 2 * DEPOSIT).await.unwrap();
+
+    test.create_stream(&alice, bob.pubkey(), RATE, now, now + DAY)
+        .await
+        .unwrap();
+    let state = test.vault_state(&alice.pubkey()).await.unwrap();
+    assert_eq!(state.balance, 2 * DEPOSIT - RATE * DAY as u64);
+    assert_eq!(state.reserved, RATE * DAY as u64);
+
+    // The stream's share is no longer the owner's to withdraw
+    assert_error(
+        test.withdraw(&alice, 2 * DEPOSIT).await,
+        VaultError::InsufficientBalance,
+    );
+
+    test.advance_clock(DAY / 4).await;
+    test.claim_stream(&bob, &alice.pubkey()).await.unwrap();
+    assert_eq!(
+        test.lamports(bob.pubkey()).await,
+        STARTING_LAMPORTS + RATE * (DAY / 4) as u64
+    );
+    assert_error(
+        test.claim_stream(&bob, &alice.pubkey()).await,
+        VaultError::NothingToClaim,
+    );
+
+    // Past the end only the total is paid, and the stream is closed
+    test.advance_clock(DAY).await;
+    test.claim_stream(&bob, &alice.pubkey()).await.unwrap();
+    assert_eq!(
+        test.lamports(bob.pubkey()).await,
+        STARTING_LAMPORTS + RATE * DAY as u64
+    );
+    assert_eq!(test.vault_state(&alice.pubkey()).await.unwrap().reserved, 0);
+    assert!(test
+        .context
+        .banks_client
+        .get_account(stream_address(&alice.pubkey(), &bob.pubkey()))
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn cancel_stream_settles_both_sides() {
+    let mut test = TestVault::new().await;
+    let (alice, bob) = (test.alice.insecure_clone(), test.bob.insecure_clone());
+    let now = test.now().await;
+    test.deposit(&alice, DEPOSIT).await.unwrap();
+    test.create_stream(&alice, bob.pubkey(), RATE, now, now + DAY)
+        .await
+        .unwrap();
+
+    test.advance_clock(DAY / 2).await;
+    test.cancel_stream(&alice, &bob.pubkey()).await.unwrap();
+
+    let vested = RATE * (DAY / 2) as u64;
+    assert_eq!(
+        test.lamports(bob.pubkey()).await,
+        STARTING_LAMPORTS + vested
+    );
+    let state = test.vault_state(&alice.pubkey()).await.unwrap();
+    assert_eq!(state.balance, DEPOSIT - vested);
+    assert_eq!(state.reserved, 0);
+
+    // With the stream gone the rest can be withdrawn and the vault closed
+    test.withdraw(&alice, DEPOSIT - vested).await.unwrap();
+    test.close_vault(&alice).await.unwrap();
+}
+
+#[tokio::test]
+async fn close_vault_waits_for_streams() {
+    let mut test = TestVault::new().await;
+    let (alice, bob) = (test.alice.insecure_clone(), test.bob.insecure_clone());
+    let now = test.now().await;
+    test.deposit(&alice, RATE * DAY as u64).await.unwrap();
+    test.create_stream(&alice, bob.pubkey(), RATE, now, now + DAY)
+        .await
+        .unwrap();
+
+    // The whole balance is in the stream, but it still belongs to bob
+    assert_error(test.close_vault(&alice).await, VaultError::StreamsActive);
+}
+
+#[tokio::test]
+async fn rejects_invalid_streams() {
+    let mut test = TestVault::new().await;
+    let (alice, bob) = (test.alice.insecure_clone(), test.bob.insecure_clone());
+    let now = test.now().await;
+    test.deposit(&alice, DEPOSIT).await.unwrap();
+
+    assert_error(
+        test.create_stream(&alice, bob.pubkey(), 0, now, now + DAY)
+            .await,
+        VaultError::InvalidStream,
+    );
+    assert_error(
+        test.create_stream(&alice, bob.pubkey(), RATE, now - DAY, now)
+            .await,
+        VaultError::InvalidStream,
+    );
+    assert_error(
+        test.create_stream(&alice, bob.pubkey(), RATE, now, now + 2 * DAY)
+            .await,
+        VaultError::InsufficientBalance,
+    );
+}
+
+#[tokio::test]
+async fn migrate_moves_legacy_funds_into_the_users_vault() {
+    let mut test = TestVault::new().await;
+    let (alice, bob) = (test.alice.insecure_clone(), test.bob.insecure_clone());
+    test.add_legacy_vault(&alice.pubkey(), DEPOSIT).await;
+    test.add_legacy_vault(&bob.pubkey(), 2 * DEPOSIT).await;
+
+    // The legacy state can't be read as a VaultState until it's migrated
+    assert!(test.withdraw(&alice, DEPOSIT).await.is_err());
+    test.migrate(&alice).await.unwrap();
+
+    let state = test.vault_state(&alice.pubkey()).await.unwrap();
+    assert_eq!((state.owner, state.balance), (alice.pubkey(), DEPOSIT));
+    assert!(state.locks.is_empty() && state.delegates.is_empty());
+    assert_eq!(state.limit, WithdrawLimit::default());
+    assert_eq!(
+        test.lamports(vault_address(&alice.pubkey())).await,
+        DEPOSIT + vault_rent()
+    );
+    // Bob's share stays behind until he migrates too
+    assert_eq!(test.lamports(legacy_vault_address()).await, 2 * DEPOSIT);
+
+    test.withdraw(&alice, DEPOSIT).await.unwrap();
+    test.close_vault(&alice).await.unwrap();
+    assert!(test.vault_state(&alice.pubkey()).await.is_none());
+
+    test.migrate(&bob).await.unwrap();
+    assert_eq!(test.lamports(legacy_vault_address()).await, 0);
+    assert_eq!(
+        test.vault_state(&bob.pubkey()).await.unwrap().balance,
+        2 * DEPOSIT
+    );
+}
+
+#[tokio::test]
+async fn migrate_only_takes_legacy_vaults() {
+    let mut test = TestVault::new().await;
+    let (alice, bob) = (test.alice.insecure_clone(), test.bob.insecure_clone());
+    test.add_legacy_vault(&alice.pubkey(), DEPOSIT).await;
+
+    test.migrate(&alice).await.unwrap();
+    assert_error(test.migrate(&alice).await, VaultError::NotLegacyVault);
+
+    test.deposit(&bob, DEPOSIT).await.unwrap();
+    assert_error(test.migrate(&bob).await, VaultError::NotLegacyVault);
+}
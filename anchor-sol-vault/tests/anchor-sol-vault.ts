@@ -28,7 +28,7 @@ describe("sol-vault", () => {
 
     // Derive PDAs
     [vaultPDA, vaultBump] = await PublicKey.findProgramAddress(
-      [Buffer.from("vault")],
+      [Buffer.from("vault"), user.publicKey.toBuffer()],
       program.programId
     );
 
@@ -36,6 +36,20 @@ describe("sol-vault", () => {
       [Buffer.from("vault_state"), user.publicKey.toBuffer()],
       program.programId
     );
+
+    // Every vault instruction reads the program config, set it up once with
+    // the provider wallet as admin and guardian. Only the upgrade authority
+    // can, and `[test] upgradeable` in Anchor.toml makes that the wallet.
+    const [configPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+    if (!(await provider.connection.getAccountInfo(configPDA))) {
+      await program.methods
+        .initializeConfig(provider.wallet.publicKey)
+        .accounts({ admin: provider.wallet.publicKey })
+        .rpc();
+    }
   });
 
   describe("deposit", () => {
@@ -77,7 +91,12 @@ describe("sol-vault", () => {
         amount,
         1000000 // Allow for transaction fees
       );
-      expect(vaultFinalBalance - vaultInitialBalance).to.equal(amount);
+      // The first deposit also pays the vault's rent
+      const vaultRent =
+        await provider.connection.getMinimumBalanceForRentExemption(0);
+      expect(vaultFinalBalance - vaultInitialBalance).to.equal(
+        amount + vaultRent
+      );
       expect(vaultState.balance.toNumber()).to.equal(amount);
       expect(vaultState.owner.toString()).to.equal(user.publicKey.toString());
     });
@@ -215,8 +234,59 @@ describe("sol-vault", () => {
 
         expect.fail("Expected transaction to fail");
       } catch (error) {
-        expect(error.toString()).to.include("Constraint was violated");
+        expect(error.toString()).to.include("Constraint");
+      }
+    });
+  });
+
+  describe("close_vault", () => {
+    it("should fail while the vault holds a balance", async () => {
+      try {
+        await program.methods
+          .closeVault()
+          .accounts({
+            user: user.publicKey,
+            vault: vaultPDA,
+            vaultState: vaultStatePDA,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc();
+
+        expect.fail("Expected transaction to fail");
+      } catch (error) {
+        expect(error.toString()).to.include("VaultNotEmpty");
       }
     });
+
+    it("should return the rent once everything is withdrawn", async () => {
+      const vaultState = await program.account.vaultState.fetch(vaultStatePDA);
+
+      await program.methods
+        .withdraw(vaultState.balance)
+        .accounts({
+          user: user.publicKey,
+          vault: vaultPDA,
+          vaultState: vaultStatePDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+
+      await program.methods
+        .closeVault()
+        .accounts({
+          user: user.publicKey,
+          vault: vaultPDA,
+          vaultState: vaultStatePDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+
+      expect(await provider.connection.getBalance(vaultPDA)).to.equal(0);
+      expect(await provider.connection.getAccountInfo(vaultStatePDA)).to.be
+        .null;
+    });
   });
 });
@@ -0,0 +1,126 @@
+use anchor_tic_tac_toe::{Game, GameState, Tile};
+use tic_tac_toe_core::{self as rules, Board, Grid, Outcome};
+
+// Scores are from the point of view of the player to move. A win is worth
+// more the fewer moves it took, so the bot goes for the quickest win and
+// drags out a loss it can't avoid.
+const WIN: i32 = 1_000;
+
+// Minimax with alpha-beta pruning over the same rules the program enforces.
+//
+// Without a depth limit the search is exhaustive and the bot plays perfectly,
+// which is instant on 3x3 but grows factorially with the board. Give larger
+// boards a depth, past which unfinished lines score as a draw.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Bot {
+    pub max_depth: Option<u8>,
+}
+
+impl Bot {
+    pub fn perfect() -> Self {
+        Self { max_depth: None }
+    }
+
+    pub fn with_depth(max_depth: u8) -> Self {
+        Self {
+            max_depth: Some(max_depth),
+        }
+    }
+
+    // The move for whoever's turn it is, None if the game isn't being played
+    pub fn next_move(&self, game: &Game) -> Option<Tile> {
+        if game.turn == 0 || game.state != GameState::Active {
+            return None;
+        }
+
+        self.best_move(game, game.turn).map(|tile| Tile {
+            row: tile.row,
+            column: tile.column,
+        })
+    }
+
+    // Works on any board the rules do, `turn` is the turn about to be played
+    pub fn best_move<B: Board>(&self, board: &B, turn: u8) -> Option<rules::Tile> {
+        let size = board.board_size();
+        let win_length = board.win_length();
+        let mut cells = Vec::with_capacity(rules::tile_count(size));
+        for row in 0..size {
+            for column in 0..size {
+                cells.push(Grid::encode(board.tile(row, column)));
+            }
+        }
+
+        let search = Search {
+            tiles: search_order(size),
+            board_size: size,
+            win_length,
+            max_depth: self.max_depth,
+        };
+        search.negamax(&cells, turn, 0, -i32::MAX, i32::MAX).1
+    }
+}
+
+struct Search {
+    tiles: Vec<rules::Tile>,
+    board_size: u8,
+    win_length: u8,
+    max_depth: Option<u8>,
+}
+
+impl Search {
+    fn negamax(
+        &self,
+        cells: &[u8],
+        turn: u8,
+        depth: u8,
+        mut alpha: i32,
+        beta: i32,
+    ) -> (i32, Option<rules::Tile>) {
+        let mut best = (-i32::MAX, None);
+
+        for &tile in &self.tiles {
+            let index = tile.row as usize * self.board_size as usize + tile.column as usize;
+            if cells[index] != Grid::EMPTY {
+                continue;
+            }
+
+            let mut child = cells.to_vec();
+            let mut grid = Grid::new(&mut child, self.board_size, self.win_length)
+                .expect("board config was valid for the parent");
+            let outcome =
+                rules::play(&mut grid, turn, tile).expect("only empty tiles are searched");
+
+            let score = match outcome {
+                Outcome::Won(_) => WIN + (cells.len() - turn as usize) as i32,
+                Outcome::Tie => 0,
+                Outcome::Active if self.max_depth.is_some_and(|max| depth + 1 >= max) => 0,
+                Outcome::Active => -self.negamax(&child, turn + 1, depth + 1, -beta, -alpha).0,
+            };
+
+            if score > best.0 {
+                best = (score, Some(tile));
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        best
+    }
+}
+
+// Center outwards, good moves first makes the pruning cut early
+fn search_order(board_size: u8) -> Vec<rules::Tile> {
+    let mut tiles: Vec<_> = (0..board_size)
+        .flat_map(|row| (0..board_size).map(move |column| rules::Tile { row, column }))
+        .collect();
+    let center = (board_size as i16 - 1) as f32 / 2.0;
+    tiles.sort_by(|a, b| {
+        let distance = |tile: &rules::Tile| {
+            (tile.row as f32 - center).abs() + (tile.column as f32 - center).abs()
+        };
+        distance(a).total_cmp(&distance(b))
+    });
+    tiles
+}
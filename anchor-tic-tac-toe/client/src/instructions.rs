@@ -0,0 +1,169 @@
+use anchor_lang::{
+    prelude::Pubkey, solana_program::instruction::Instruction, system_program, InstructionData,
+    ToAccountMetas,
+};
+use anchor_spl::{associated_token::get_associated_token_address, token};
+use anchor_tic_tac_toe::{Game, Tile};
+
+use crate::{game_address, profile_address, vault_address};
+
+// Settings for a game between two known players, staked in SOL.
+// The default is a friendly, unrated 3x3 game without a turn clock.
+#[derive(Clone, Copy, Debug)]
+pub struct GameConfig {
+    pub wager: u64,
+    pub turn_timeout: i64,
+    pub board_size: u8,
+    pub win_length: u8,
+    // Both players need a profile before a rated game is set up
+    pub rated: bool,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            wager: 0,
+            turn_timeout: 0,
+            board_size: 3,
+            win_length: 3,
+            rated: false,
+        }
+    }
+}
+
+pub fn create_profile(player: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: anchor_tic_tac_toe::ID,
+        accounts: anchor_tic_tac_toe::accounts::CreateProfile {
+            profile: profile_address(player),
+            player: *player,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_tic_tac_toe::instruction::CreateProfile {}.data(),
+    }
+}
+
+// The game lands at game_address(player_one, player_two). A wagered or rated
+// game waits for player two's join_game before the first move.
+pub fn setup_game(player_one: &Pubkey, player_two: &Pubkey, config: &GameConfig) -> Instruction {
+    let game = game_address(player_one, player_two);
+    let profile = |player: &Pubkey| config.rated.then(|| profile_address(player));
+
+    Instruction {
+        program_id: anchor_tic_tac_toe::ID,
+        accounts: anchor_tic_tac_toe::accounts::SetupGame {
+            game,
+            player_one: *player_one,
+            vault: vault_address(&game),
+            stake_mint: None,
+            player_one_token_account: None,
+            vault_token_account: None,
+            player_one_profile: profile(player_one),
+            player_two_profile: profile(player_two),
+            token_program: None,
+            associated_token_program: None,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_tic_tac_toe::instruction::SetupGame {
+            player_two: *player_two,
+            wager: config.wager,
+            turn_timeout: config.turn_timeout,
+            board_size: config.board_size,
+            win_length: config.win_length,
+        }
+        .data(),
+    }
+}
+
+// Player two, or whoever takes an open seat, joins and matches the stake.
+// Token wagers here and in play are assumed to be on the SPL Token program
+pub fn join_game(address: &Pubkey, game: &Game, player: &Pubkey) -> Instruction {
+    let token_account = |owner: &Pubkey| {
+        game.stake_mint
+            .map(|mint| get_associated_token_address(owner, &mint))
+    };
+
+    Instruction {
+        program_id: anchor_tic_tac_toe::ID,
+        accounts: anchor_tic_tac_toe::accounts::JoinGame {
+            game: *address,
+            player_two: *player,
+            vault: vault_address(address),
+            stake_mint: game.stake_mint,
+            player_two_token_account: token_account(player),
+            vault_token_account: token_account(address),
+            player_two_profile: game.rated.then(|| profile_address(player)),
+            token_program: game.stake_mint.map(|_| token::ID),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_tic_tac_toe::instruction::JoinGame {}.data(),
+    }
+}
+
+// `game` is the account as last fetched, it decides which pot and profile
+// accounts go along. They are passed on every move, so the caller doesn't
+// have to know which move ends the game.
+pub fn play(address: &Pubkey, game: &Game, player: &Pubkey, tile: Tile) -> Instruction {
+    Instruction {
+        program_id: anchor_tic_tac_toe::ID,
+        accounts: play_accounts(address, game, player),
+        data: anchor_tic_tac_toe::instruction::Play { tile }.data(),
+    }
+}
+
+pub fn claim_timeout(address: &Pubkey, game: &Game, player: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: anchor_tic_tac_toe::ID,
+        accounts: play_accounts(address, game, player),
+        data: anchor_tic_tac_toe::instruction::ClaimTimeout {}.data(),
+    }
+}
+
+// `player` is the winner, or either player after a tie
+pub fn close_game(address: &Pubkey, game: &Game, player: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: anchor_tic_tac_toe::ID,
+        accounts: anchor_tic_tac_toe::accounts::CloseGame {
+            game: *address,
+            player: *player,
+            player_one: game.players[0],
+        }
+        .to_account_metas(None),
+        data: anchor_tic_tac_toe::instruction::CloseGame {}.data(),
+    }
+}
+
+fn play_accounts(
+    address: &Pubkey,
+    game: &Game,
+    player: &Pubkey,
+) -> Vec<anchor_lang::prelude::AccountMeta> {
+    let [player_one, player_two] = game.players;
+    let wagered = game.wager > 0;
+    let sol_pot = wagered && game.stake_mint.is_none();
+    let token_account = |owner: &Pubkey| {
+        game.stake_mint
+            .map(|mint| get_associated_token_address(owner, &mint))
+    };
+    let profile = |player: &Pubkey| game.rated.then(|| profile_address(player));
+
+    anchor_tic_tac_toe::accounts::Play {
+        game: *address,
+        player: *player,
+        player_one: wagered.then_some(player_one),
+        player_two: wagered.then_some(player_two),
+        vault: sol_pot.then(|| vault_address(address)),
+        stake_mint: game.stake_mint,
+        player_one_token_account: token_account(&player_one),
+        player_two_token_account: token_account(&player_two),
+        vault_token_account: token_account(address),
+        player_one_profile: profile(&player_one),
+        player_two_profile: profile(&player_two),
+        token_program: game.stake_mint.map(|_| token::ID),
+        system_program: sol_pot.then_some(system_program::ID),
+    }
+    .to_account_metas(None)
+}
@@ -0,0 +1,37 @@
+// Off-chain side of anchor-tic-tac-toe: builds its instructions, decodes its
+// accounts and picks moves, so games can be driven without a front end.
+
+pub mod bot;
+pub mod instructions;
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, Result};
+use anchor_tic_tac_toe::{Game, PlayerProfile};
+
+pub use anchor_tic_tac_toe::{GameState, Sign, Tile};
+pub use bot::Bot;
+pub use instructions::*;
+
+pub fn game_address(player_one: &Pubkey, seed: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"game", player_one.as_ref(), seed.as_ref()],
+        &anchor_tic_tac_toe::ID,
+    )
+    .0
+}
+
+pub fn vault_address(game: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", game.as_ref()], &anchor_tic_tac_toe::ID).0
+}
+
+pub fn profile_address(player: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"profile", player.as_ref()], &anchor_tic_tac_toe::ID).0
+}
+
+// Checks the discriminator, so any other account type is rejected
+pub fn decode_game(data: &[u8]) -> Result<Game> {
+    Game::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_profile(data: &[u8]) -> Result<PlayerProfile> {
+    PlayerProfile::try_deserialize(&mut &data[..])
+}
@@ -0,0 +1,102 @@
+use tic_tac_toe_client::Bot;
+use tic_tac_toe_core::{self as rules, Grid, Outcome, Tile};
+
+// Cells row by row, '.' empty
+fn board(rows: &[&str]) -> Vec<u8> {
+    rows.iter()
+        .flat_map(|row| row.chars())
+        .map(|cell| match cell {
+            'X' => 1,
+            'O' => 2,
+            _ => Grid::EMPTY,
+        })
+        .collect()
+}
+
+fn turn(cells: &[u8]) -> u8 {
+    cells.iter().filter(|cell| **cell != Grid::EMPTY).count() as u8 + 1
+}
+
+fn best_move(cells: &mut [u8], win_length: u8, bot: Bot) -> Option<Tile> {
+    let turn = turn(cells);
+    let size = (cells.len() as f64).sqrt() as u8;
+    bot.best_move(&Grid::new(cells, size, win_length).unwrap(), turn)
+}
+
+// Both sides play `bot` from the given position until the game ends
+fn play_out(cells: &mut [u8], win_length: u8, bot: Bot) -> Outcome {
+    let size = (cells.len() as f64).sqrt() as u8;
+    loop {
+        let turn = turn(cells);
+        let tile = best_move(cells, win_length, bot).unwrap();
+        let mut grid = Grid::new(cells, size, win_length).unwrap();
+        match rules::play(&mut grid, turn, tile).unwrap() {
+            Outcome::Active => continue,
+            outcome => return outcome,
+        }
+    }
+}
+
+#[test]
+fn takes_the_win() {
+    let mut cells = board(&["XX.", "OO.", "..."]);
+
+    assert_eq!(
+        best_move(&mut cells, 3, Bot::perfect()),
+        Some(Tile { row: 0, column: 2 })
+    );
+}
+
+#[test]
+fn blocks_the_opponent() {
+    let mut cells = board(&["X..", "OX.", "..."]);
+
+    // O has to take the last tile of X's diagonal
+    assert_eq!(
+        best_move(&mut cells, 3, Bot::perfect()),
+        Some(Tile { row: 2, column: 2 })
+    );
+}
+
+#[test]
+fn prefers_the_quicker_win() {
+    // X can win now on the top row, or set up a fork
+    let mut cells = board(&["XX.", "O..", "O.."]);
+
+    assert_eq!(
+        best_move(&mut cells, 3, Bot::perfect()),
+        Some(Tile { row: 0, column: 2 })
+    );
+}
+
+#[test]
+fn no_move_on_a_full_board() {
+    let mut cells = board(&["XOX", "XOO", "OXX"]);
+
+    assert_eq!(best_move(&mut cells, 3, Bot::perfect()), None);
+}
+
+#[test]
+fn perfect_play_is_a_tie() {
+    let mut cells = board(&["...", "...", "..."]);
+
+    assert_eq!(play_out(&mut cells, 3, Bot::perfect()), Outcome::Tie);
+}
+
+#[test]
+fn punishes_a_bad_opening() {
+    // O answered a center opening on an edge, X forces a win
+    let mut cells = board(&[".O.", ".X.", "..."]);
+
+    assert_eq!(
+        play_out(&mut cells, 3, Bot::perfect()),
+        Outcome::Won(rules::Sign::X)
+    );
+}
+
+#[test]
+fn depth_limited_bot_still_finishes_larger_boards() {
+    let mut cells = vec![Grid::EMPTY; 25];
+
+    assert_ne!(play_out(&mut cells, 4, Bot::with_depth(2)), Outcome::Active);
+}
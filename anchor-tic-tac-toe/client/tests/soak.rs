@@ -0,0 +1,192 @@
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::entrypoint::ProgramResult,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::Instruction, native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer,
+    system_instruction, transaction::Transaction,
+};
+use tic_tac_toe_client::{
+    decode_game, decode_profile, game_address, profile_address, Bot, GameConfig, GameState,
+};
+
+// Games per soak run, raise it with SOAK_GAMES=5000 cargo test --test soak
+const DEFAULT_GAMES: usize = 20;
+
+// Anchor's entrypoint ties the account slice to the `AccountInfo` lifetime,
+// which `processor!` can't express, so leak the slice for the test run.
+fn entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    anchor_tic_tac_toe::entry(program_id, accounts, data)
+}
+
+async fn start() -> ProgramTestContext {
+    ProgramTest::new(
+        "anchor_tic_tac_toe",
+        anchor_tic_tac_toe::ID,
+        processor!(entry),
+    )
+    .start_with_context()
+    .await
+}
+
+async fn send(context: &mut ProgramTestContext, ix: Instruction, signer: &Keypair) {
+    // Refresh the blockhash so identical moves don't dedupe as the same transaction
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, signer],
+        recent_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+async fn fund(context: &mut ProgramTestContext, player: &Pubkey) {
+    let payer = context.payer.insecure_clone();
+    send(
+        context,
+        system_instruction::transfer(&payer.pubkey(), player, 10 * LAMPORTS_PER_SOL),
+        &payer,
+    )
+    .await;
+}
+
+// Sets the game up, joins it if it's waiting on player two, and lets each
+// player's bot move in turn until it ends
+async fn play_game(
+    context: &mut ProgramTestContext,
+    players: [&Keypair; 2],
+    config: &GameConfig,
+    bots: [Bot; 2],
+) -> GameState {
+    let address = game_address(&players[0].pubkey(), &players[1].pubkey());
+    send(
+        context,
+        tic_tac_toe_client::setup_game(&players[0].pubkey(), &players[1].pubkey(), config),
+        players[0],
+    )
+    .await;
+
+    loop {
+        let account = context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        let game = decode_game(&account.data).unwrap();
+        if game.turn == 0 {
+            send(
+                context,
+                tic_tac_toe_client::join_game(&address, &game, &players[1].pubkey()),
+                players[1],
+            )
+            .await;
+            continue;
+        }
+
+        let Some(tile) = bots[(game.turn as usize + 1) % 2].next_move(&game) else {
+            return game.state;
+        };
+
+        let player = players[(game.turn as usize + 1) % 2];
+        send(
+            context,
+            tic_tac_toe_client::play(&address, &game, &player.pubkey(), tile),
+            player,
+        )
+        .await;
+    }
+}
+
+#[tokio::test]
+async fn perfect_bots_always_tie() {
+    let games = std::env::var("SOAK_GAMES")
+        .ok()
+        .and_then(|games| games.parse().ok())
+        .unwrap_or(DEFAULT_GAMES);
+    let mut context = start().await;
+    let player_one = context.payer.insecure_clone();
+
+    for _ in 0..games {
+        // A new opponent every game gives every game its own address
+        let player_two = Keypair::new();
+        let state = play_game(
+            &mut context,
+            [&player_one, &player_two],
+            &GameConfig::default(),
+            [Bot::perfect(); 2],
+        )
+        .await;
+
+        assert_eq!(state, GameState::Tie);
+    }
+}
+
+#[tokio::test]
+async fn deeper_search_wins_wagered_rated_games() {
+    let mut context = start().await;
+    let (player_one, player_two) = (Keypair::new(), Keypair::new());
+    for player in [&player_one, &player_two] {
+        fund(&mut context, &player.pubkey()).await;
+        send(
+            &mut context,
+            tic_tac_toe_client::create_profile(&player.pubkey()),
+            player,
+        )
+        .await;
+    }
+
+    // A bot that only looks at its own move can't see the fork coming
+    let config = GameConfig {
+        wager: LAMPORTS_PER_SOL,
+        rated: true,
+        ..GameConfig::default()
+    };
+    let state = play_game(
+        &mut context,
+        [&player_one, &player_two],
+        &config,
+        [Bot::perfect(), Bot::with_depth(1)],
+    )
+    .await;
+
+    assert_eq!(
+        state,
+        GameState::Won {
+            winner: player_one.pubkey()
+        }
+    );
+
+    let profile = context
+        .banks_client
+        .get_account(profile_address(&player_one.pubkey()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(decode_profile(&profile.data).unwrap().wins, 1);
+}
+
+#[tokio::test]
+async fn depth_limited_bots_finish_large_boards() {
+    let mut context = start().await;
+    let player_one = context.payer.insecure_clone();
+    let player_two = Keypair::new();
+
+    let config = GameConfig {
+        board_size: 6,
+        win_length: 4,
+        ..GameConfig::default()
+    };
+    let state = play_game(
+        &mut context,
+        [&player_one, &player_two],
+        &config,
+        [Bot::with_depth(2); 2],
+    )
+    .await;
+
+    assert_ne!(state, GameState::Active);
+}
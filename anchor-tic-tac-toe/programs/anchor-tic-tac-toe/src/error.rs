@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use tic_tac_toe_core::RuleError;
 
 #[error_code]
 pub enum TicTacToeError {
@@ -7,4 +8,28 @@ pub enum TicTacToeError {
     GameAlreadyOver,
     NotPlayersTurn,
     GameAlreadyStarted,
+    GameNotStarted,
+    InvalidPlayer,
+    WagerTooSmall,
+    WagerOverflow,
+    InvalidStakeMint,
+    MissingPotAccounts,
+    InvalidTurnTimeout,
+    TurnNotExpired,
+    InvalidBoardConfig,
+    MissingProfiles,
+    PlayerNotJoined,
+    StakeMintWithoutWager,
+    GameNotFinished,
+    TransferFeeMint,
+}
+
+impl From<RuleError> for TicTacToeError {
+    fn from(error: RuleError) -> Self {
+        match error {
+            RuleError::InvalidBoardConfig => TicTacToeError::InvalidBoardConfig,
+            RuleError::TileOutOfBounds => TicTacToeError::TileOutOfBounds,
+            RuleError::TileAlreadySet => TicTacToeError::TileAlreadySet,
+        }
+    }
 }
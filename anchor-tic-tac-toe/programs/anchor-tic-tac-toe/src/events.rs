@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{GameState, Sign};
+
+// Enough to replay any game from transaction logs alone
+
+#[event]
+pub struct GameCreated {
+    pub game: Pubkey,
+    // Player two is the default key until someone takes an open seat
+    pub players: [Pubkey; 2],
+    pub invited: Option<Pubkey>,
+    pub board_size: u8,
+    pub win_length: u8,
+    pub wager: u64,
+    pub stake_mint: Option<Pubkey>,
+    pub turn_timeout: i64,
+    pub rated: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlayerJoined {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MovePlayed {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub row: u8,
+    pub column: u8,
+    pub sign: Sign,
+    pub turn: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GameFinished {
+    pub game: Pubkey,
+    pub state: GameState,
+    pub winner: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GameCancelled {
+    pub game: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GameClosed {
+    pub game: Pubkey,
+    pub closed_by: Pubkey,
+    pub timestamp: i64,
+}
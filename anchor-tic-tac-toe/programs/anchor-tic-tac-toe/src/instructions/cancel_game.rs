@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{error::TicTacToeError, events::GameCancelled, pot, state::Game};
+
+// Player one takes their stake back if player two never joined,
+// or just closes an open game nobody took. A friendly game that already
+// started can be cancelled too once player two has let their first turn expire.
+#[derive(Accounts)]
+pub struct CancelGame<'info> {
+    #[account(
+        mut,
+        close = player_one,
+        seeds = [b"game", game.players[0].as_ref(), game.seed.as_ref()],
+        bump = game.bump,
+        constraint = game.players[0] == player_one.key() @ TicTacToeError::InvalidPlayer
+    )]
+    pub game: Account<'info, Game>,
+
+    #[account(mut)]
+    pub player_one: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", game.key().as_ref()],
+        bump = game.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(constraint = game.stake_mint == Some(stake_mint.key()) @ TicTacToeError::InvalidStakeMint)]
+    pub stake_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = player_one,
+        associated_token::token_program = token_program
+    )]
+    pub player_one_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = game,
+        associated_token::token_program = token_program
+    )]
+    pub vault_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CancelGame<'info> {
+    fn refund_stake(&self) -> Result<()> {
+        if !self.game.is_wagered() {
+            return Ok(());
+        }
+
+        if self.game.stake_mint.is_none() {
+            let game_key = self.game.key();
+            let vault_seeds: &[&[&[u8]]] =
+                &[&[b"vault", game_key.as_ref(), &[self.game.vault_bump]]];
+
+            return pot::withdraw_sol(
+                &self.system_program.to_account_info(),
+                &self.vault.to_account_info(),
+                &self.player_one.to_account_info(),
+                self.vault.lamports(),
+                vault_seeds,
+            );
+        }
+
+        let (
+            Some(stake_mint),
+            Some(player_one_token_account),
+            Some(vault_token_account),
+            Some(token_program),
+        ) = (
+            &self.stake_mint,
+            &self.player_one_token_account,
+            &self.vault_token_account,
+            &self.token_program,
+        )
+        else {
+            return err!(TicTacToeError::MissingPotAccounts);
+        };
+
+        let game_seeds: &[&[&[u8]]] = &[&[
+            b"game",
+            self.game.players[0].as_ref(),
+            self.game.seed.as_ref(),
+            &[self.game.bump],
+        ]];
+
+        pot::withdraw_tokens(
+            &token_program.to_account_info(),
+            &vault_token_account.to_account_info(),
+            stake_mint,
+            &player_one_token_account.to_account_info(),
+            &self.game.to_account_info(),
+            vault_token_account.amount,
+            game_seeds,
+        )?;
+
+        pot::close_token_vault(
+            &token_program.to_account_info(),
+            &vault_token_account.to_account_info(),
+            &self.player_one.to_account_info(),
+            &self.game.to_account_info(),
+            game_seeds,
+        )
+    }
+}
+
+pub fn handler(ctx: Context<CancelGame>) -> Result<()> {
+    let game = &ctx.accounts.game;
+    require!(
+        !game.is_started() || game.is_abandoned(Clock::get()?.unix_timestamp),
+        TicTacToeError::GameAlreadyStarted
+    );
+
+    ctx.accounts.refund_stake()?;
+
+    emit!(GameCancelled {
+        game: ctx.accounts.game.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::TicTacToeError, instructions::Play};
+
+// The waiting player wins if the current player lets the clock run out.
+// Takes the same accounts as play, `player` being the one claiming.
+pub fn handler(ctx: Context<Play>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+
+    require!(game.is_started(), TicTacToeError::GameNotStarted);
+    require!(game.is_active(), TicTacToeError::GameAlreadyOver);
+    require_keys_eq!(
+        game.waiting_player(),
+        ctx.accounts.player.key(),
+        TicTacToeError::InvalidPlayer
+    );
+    // Nobody wins by default against a player two who never took their seat
+    require!(
+        game.joined || game.waiting_player() == game.players[1],
+        TicTacToeError::PlayerNotJoined
+    );
+    require!(
+        game.is_turn_expired(Clock::get()?.unix_timestamp),
+        TicTacToeError::TurnNotExpired
+    );
+
+    game.forfeit()?;
+
+    ctx.accounts.finish_game()
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::TicTacToeError, events::GameClosed, state::Game};
+
+// Closes a finished game, handing its rent back to player one, who paid it.
+// That also frees the address, so the same pair can set up another game.
+// Only the winner, or either player after a tie, can close it, so a losing
+// player can't make the result disappear before a tournament records it.
+#[derive(Accounts)]
+pub struct CloseGame<'info> {
+    #[account(
+        mut,
+        close = player_one,
+        seeds = [b"game", game.players[0].as_ref(), game.seed.as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, Game>,
+
+    pub player: Signer<'info>,
+
+    /// CHECK: only receives the rent, checked against the game's first seat
+    #[account(
+        mut,
+        constraint = player_one.key() == game.players[0] @ TicTacToeError::InvalidPlayer
+    )]
+    pub player_one: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<CloseGame>) -> Result<()> {
+    let game = &ctx.accounts.game;
+    require!(!game.is_active(), TicTacToeError::GameNotFinished);
+
+    let player = ctx.accounts.player.key();
+    let allowed = match game.winner() {
+        Some(winner) => player == winner,
+        None => game.players.contains(&player),
+    };
+    require!(allowed, TicTacToeError::InvalidPlayer);
+
+    emit!(GameClosed {
+        game: game.key(),
+        closed_by: player,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
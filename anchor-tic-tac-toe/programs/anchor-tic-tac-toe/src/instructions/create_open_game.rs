@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::TicTacToeError,
+    events::GameCreated,
+    pot,
+    state::{Game, PlayerProfile},
+};
+
+// Like setup_game, but the second seat is left empty for join_game to fill.
+// The host picks any unused key as the seed, so they can have several open games.
+// Passing the host's profile makes it a rated game, whoever joins has to bring theirs.
+#[derive(Accounts)]
+#[instruction(seed: Pubkey, wager: u64, turn_timeout: i64, board_size: u8)]
+pub struct CreateOpenGame<'info> {
+    #[account(
+        init,
+        payer = host,
+        space = 8 + Game::maximum_size(board_size.min(Game::MAX_BOARD_SIZE)),
+        seeds = [b"game", host.key().as_ref(), seed.as_ref()],
+        bump
+    )]
+    pub game: Account<'info, Game>,
+
+    #[account(mut)]
+    pub host: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", game.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub stake_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = host,
+        associated_token::token_program = token_program
+    )]
+    pub host_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init,
+        payer = host,
+        associated_token::mint = stake_mint,
+        associated_token::authority = game,
+        associated_token::token_program = token_program
+    )]
+    pub vault_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [b"profile", host.key().as_ref()],
+        bump = host_profile.bump
+    )]
+    pub host_profile: Option<Account<'info, PlayerProfile>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateOpenGame<'info> {
+    fn deposit_stake(&self, wager: u64) -> Result<()> {
+        if self.stake_mint.is_none() {
+            // The first deposit has to make the vault rent exempt
+            require_gte!(
+                wager,
+                Rent::get()?.minimum_balance(0),
+                TicTacToeError::WagerTooSmall
+            );
+
+            return pot::deposit_sol(
+                &self.system_program.to_account_info(),
+                &self.host.to_account_info(),
+                &self.vault.to_account_info(),
+                wager,
+            );
+        }
+
+        let (
+            Some(stake_mint),
+            Some(host_token_account),
+            Some(vault_token_account),
+            Some(token_program),
+        ) = (
+            &self.stake_mint,
+            &self.host_token_account,
+            &self.vault_token_account,
+            &self.token_program,
+        )
+        else {
+            return err!(TicTacToeError::MissingPotAccounts);
+        };
+
+        pot::deposit_tokens(
+            &token_program.to_account_info(),
+            &host_token_account.to_account_info(),
+            stake_mint,
+            &vault_token_account.to_account_info(),
+            &self.host.to_account_info(),
+            wager,
+        )
+    }
+}
+
+pub fn handler(
+    ctx: Context<CreateOpenGame>,
+    seed: Pubkey,
+    wager: u64,
+    turn_timeout: i64,
+    board_size: u8,
+    win_length: u8,
+    invited: Option<Pubkey>,
+) -> Result<()> {
+    require_gte!(turn_timeout, 0, TicTacToeError::InvalidTurnTimeout);
+    // A stake mint only comes with a wager, otherwise its token vault would sit empty
+    require!(
+        wager > 0 || ctx.accounts.stake_mint.is_none(),
+        TicTacToeError::StakeMintWithoutWager
+    );
+    if let Some(stake_mint) = &ctx.accounts.stake_mint {
+        pot::check_stake_mint(stake_mint)?;
+    }
+
+    let host = ctx.accounts.host.key();
+    // An empty seat is marked by the default key, so it can't be invited either
+    if let Some(invited) = invited {
+        require!(
+            invited != host && invited != Pubkey::default(),
+            TicTacToeError::InvalidPlayer
+        );
+    }
+
+    let game = &mut ctx.accounts.game;
+    game.new_board(board_size, win_length)?;
+    game.bump = ctx.bumps.game;
+    game.vault_bump = ctx.bumps.vault;
+    game.wager = wager;
+    game.stake_mint = ctx.accounts.stake_mint.as_ref().map(|mint| mint.key());
+    game.turn_timeout = turn_timeout;
+    game.seed = seed;
+    game.invited = invited;
+    game.rated = ctx.accounts.host_profile.is_some();
+    game.players = [host, Pubkey::default()];
+
+    emit!(GameCreated {
+        game: game.key(),
+        players: game.players,
+        invited,
+        board_size,
+        win_length,
+        wager,
+        stake_mint: game.stake_mint,
+        turn_timeout,
+        rated: game.rated,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    if wager > 0 {
+        ctx.accounts.deposit_stake(wager)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::state::PlayerProfile;
+
+#[derive(Accounts)]
+pub struct CreateProfile<'info> {
+    #[account(
+        init,
+        payer = player,
+        space = 8 + PlayerProfile::MAXIMUM_SIZE,
+        seeds = [b"profile", player.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateProfile>) -> Result<()> {
+    let player = ctx.accounts.player.key();
+    ctx.accounts.profile.init(player, ctx.bumps.profile);
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    error::TicTacToeError,
+    events::PlayerJoined,
+    pot,
+    state::{Game, PlayerProfile},
+};
+
+// Player two takes their seat, matching player one's stake if there is one,
+// which starts the game. Anyone can take an open seat that isn't invite-only.
+#[derive(Accounts)]
+pub struct JoinGame<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", game.players[0].as_ref(), game.seed.as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, Game>,
+
+    #[account(mut)]
+    pub player_two: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", game.key().as_ref()],
+        bump = game.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(constraint = game.stake_mint == Some(stake_mint.key()) @ TicTacToeError::InvalidStakeMint)]
+    pub stake_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = player_two,
+        associated_token::token_program = token_program
+    )]
+    pub player_two_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = game,
+        associated_token::token_program = token_program
+    )]
+    pub vault_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // Rated games need to know who they're rating
+    #[account(
+        seeds = [b"profile", player_two.key().as_ref()],
+        bump = player_two_profile.bump
+    )]
+    pub player_two_profile: Option<Account<'info, PlayerProfile>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> JoinGame<'info> {
+    fn deposit_stake(&self) -> Result<()> {
+        let wager = self.game.wager;
+
+        if self.game.stake_mint.is_none() {
+            return pot::deposit_sol(
+                &self.system_program.to_account_info(),
+                &self.player_two.to_account_info(),
+                &self.vault.to_account_info(),
+                wager,
+            );
+        }
+
+        let (
+            Some(stake_mint),
+            Some(player_two_token_account),
+            Some(vault_token_account),
+            Some(token_program),
+        ) = (
+            &self.stake_mint,
+            &self.player_two_token_account,
+            &self.vault_token_account,
+            &self.token_program,
+        )
+        else {
+            return err!(TicTacToeError::MissingPotAccounts);
+        };
+
+        pot::deposit_tokens(
+            &token_program.to_account_info(),
+            &player_two_token_account.to_account_info(),
+            stake_mint,
+            &vault_token_account.to_account_info(),
+            &self.player_two.to_account_info(),
+            wager,
+        )
+    }
+}
+
+pub fn handler(ctx: Context<JoinGame>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        !ctx.accounts.game.rated || ctx.accounts.player_two_profile.is_some(),
+        TicTacToeError::MissingProfiles
+    );
+
+    let game = &mut ctx.accounts.game;
+    game.join(ctx.accounts.player_two.key())?;
+    game.last_move_at = now;
+
+    if game.is_wagered() {
+        ctx.accounts.deposit_stake()?;
+    }
+
+    emit!(PlayerJoined {
+        game: ctx.accounts.game.key(),
+        player: ctx.accounts.player_two.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,18 @@
+#![allow(ambiguous_glob_reexports)]
+
+pub mod cancel_game;
+pub mod claim_timeout;
+pub mod close_game;
+pub mod create_open_game;
+pub mod create_profile;
+pub mod join_game;
+pub mod play;
+pub mod setup_game;
+
+pub use cancel_game::*;
+pub use close_game::*;
+pub use create_open_game::*;
+pub use create_profile::*;
+pub use join_game::*;
+pub use play::*;
+pub use setup_game::*;
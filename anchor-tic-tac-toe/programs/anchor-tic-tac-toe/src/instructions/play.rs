@@ -0,0 +1,227 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    error::TicTacToeError,
+    events::{GameFinished, MovePlayed},
+    pot,
+    state::{Game, PlayerProfile, Tile},
+};
+
+// The pot and profile accounts are only read by the move that ends a wagered
+// or rated game, every other move can leave them out.
+// claim_timeout reuses these accounts.
+#[derive(Accounts)]
+pub struct Play<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", game.players[0].as_ref(), game.seed.as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, Game>,
+
+    pub player: Signer<'info>,
+
+    #[account(mut, address = game.players[0] @ TicTacToeError::InvalidPlayer)]
+    pub player_one: Option<SystemAccount<'info>>,
+    #[account(mut, address = game.players[1] @ TicTacToeError::InvalidPlayer)]
+    pub player_two: Option<SystemAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [b"vault", game.key().as_ref()],
+        bump = game.vault_bump
+    )]
+    pub vault: Option<SystemAccount<'info>>,
+
+    #[account(constraint = game.stake_mint == Some(stake_mint.key()) @ TicTacToeError::InvalidStakeMint)]
+    pub stake_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = game.players[0],
+        associated_token::token_program = token_program
+    )]
+    pub player_one_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = game.players[1],
+        associated_token::token_program = token_program
+    )]
+    pub player_two_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = game,
+        associated_token::token_program = token_program
+    )]
+    pub vault_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", game.players[0].as_ref()],
+        bump = player_one_profile.bump
+    )]
+    pub player_one_profile: Option<Account<'info, PlayerProfile>>,
+    #[account(
+        mut,
+        seeds = [b"profile", game.players[1].as_ref()],
+        bump = player_two_profile.bump
+    )]
+    pub player_two_profile: Option<Account<'info, PlayerProfile>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+impl<'info> Play<'info> {
+    // Settles everything riding on the game once update_state has ended it
+    pub fn finish_game(&mut self) -> Result<()> {
+        emit!(GameFinished {
+            game: self.game.key(),
+            state: self.game.state.clone(),
+            winner: self.game.winner(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        if self.game.is_wagered() {
+            self.pay_out()?;
+        }
+
+        if self.game.rated {
+            self.record_results()?;
+        }
+
+        Ok(())
+    }
+
+    fn record_results(&mut self) -> Result<()> {
+        let (Some(player_one_profile), Some(player_two_profile), Some(player_one_score)) = (
+            &mut self.player_one_profile,
+            &mut self.player_two_profile,
+            self.game.player_one_score(),
+        ) else {
+            return err!(TicTacToeError::MissingProfiles);
+        };
+
+        PlayerProfile::record_game(player_one_profile, player_two_profile, player_one_score);
+        Ok(())
+    }
+
+    fn pay_out(&self) -> Result<()> {
+        let shares = self.game.pot_shares()?;
+
+        let Some(player_one) = &self.player_one else {
+            return err!(TicTacToeError::MissingPotAccounts);
+        };
+
+        if self.game.stake_mint.is_none() {
+            let (Some(player_two), Some(vault), Some(system_program)) =
+                (&self.player_two, &self.vault, &self.system_program)
+            else {
+                return err!(TicTacToeError::MissingPotAccounts);
+            };
+
+            let game_key = self.game.key();
+            let vault_seeds: &[&[&[u8]]] =
+                &[&[b"vault", game_key.as_ref(), &[self.game.vault_bump]]];
+
+            for (recipient, share) in [player_one, player_two].into_iter().zip(shares) {
+                if share > 0 {
+                    pot::withdraw_sol(
+                        &system_program.to_account_info(),
+                        &vault.to_account_info(),
+                        &recipient.to_account_info(),
+                        share,
+                        vault_seeds,
+                    )?;
+                }
+            }
+
+            return Ok(());
+        }
+
+        let (
+            Some(stake_mint),
+            Some(player_one_token_account),
+            Some(player_two_token_account),
+            Some(vault_token_account),
+            Some(token_program),
+        ) = (
+            &self.stake_mint,
+            &self.player_one_token_account,
+            &self.player_two_token_account,
+            &self.vault_token_account,
+            &self.token_program,
+        )
+        else {
+            return err!(TicTacToeError::MissingPotAccounts);
+        };
+
+        let game_seeds: &[&[&[u8]]] = &[&[
+            b"game",
+            self.game.players[0].as_ref(),
+            self.game.seed.as_ref(),
+            &[self.game.bump],
+        ]];
+
+        for (recipient, share) in [player_one_token_account, player_two_token_account]
+            .into_iter()
+            .zip(shares)
+        {
+            if share > 0 {
+                pot::withdraw_tokens(
+                    &token_program.to_account_info(),
+                    &vault_token_account.to_account_info(),
+                    stake_mint,
+                    &recipient.to_account_info(),
+                    &self.game.to_account_info(),
+                    share,
+                    game_seeds,
+                )?;
+            }
+        }
+
+        pot::close_token_vault(
+            &token_program.to_account_info(),
+            &vault_token_account.to_account_info(),
+            &player_one.to_account_info(),
+            &self.game.to_account_info(),
+            game_seeds,
+        )
+    }
+}
+
+pub fn handler(ctx: Context<Play>, tile: Tile) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+
+    require!(game.is_started(), TicTacToeError::GameNotStarted);
+    require!(game.is_active(), TicTacToeError::GameAlreadyOver);
+    require_keys_eq!(
+        game.current_player(),
+        ctx.accounts.player.key(),
+        TicTacToeError::NotPlayersTurn
+    );
+
+    let (sign, turn) = (game.current_sign(), game.turn);
+    let now = Clock::get()?.unix_timestamp;
+
+    game.play(&tile)?;
+    game.last_move_at = now;
+
+    emit!(MovePlayed {
+        game: game.key(),
+        player: ctx.accounts.player.key(),
+        row: tile.row,
+        column: tile.column,
+        sign,
+        turn,
+        timestamp: now,
+    });
+
+    if !game.is_active() {
+        ctx.accounts.finish_game()?;
+    }
+
+    Ok(())
+}
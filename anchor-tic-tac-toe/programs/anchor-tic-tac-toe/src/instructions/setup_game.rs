@@ -0,0 +1,190 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::TicTacToeError,
+    events::GameCreated,
+    pot,
+    state::{Game, PlayerProfile},
+};
+
+// One game per pair of players, player one pays for the account,
+// sized for the board it's played on.
+// Pass the stake mint and token accounts to wager SPL tokens instead of SOL,
+// and both players' profiles to make it a rated game.
+#[derive(Accounts)]
+#[instruction(player_two: Pubkey, wager: u64, turn_timeout: i64, board_size: u8)]
+pub struct SetupGame<'info> {
+    #[account(
+        init,
+        payer = player_one,
+        // Oversized boards are rejected by the handler with a proper error
+        space = 8 + Game::maximum_size(board_size.min(Game::MAX_BOARD_SIZE)),
+        seeds = [b"game", player_one.key().as_ref(), player_two.as_ref()],
+        bump
+    )]
+    pub game: Account<'info, Game>,
+
+    #[account(mut)]
+    pub player_one: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", game.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub stake_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = player_one,
+        associated_token::token_program = token_program
+    )]
+    pub player_one_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init,
+        payer = player_one,
+        associated_token::mint = stake_mint,
+        associated_token::authority = game,
+        associated_token::token_program = token_program
+    )]
+    pub vault_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [b"profile", player_one.key().as_ref()],
+        bump = player_one_profile.bump
+    )]
+    pub player_one_profile: Option<Account<'info, PlayerProfile>>,
+    #[account(
+        seeds = [b"profile", player_two.as_ref()],
+        bump = player_two_profile.bump
+    )]
+    pub player_two_profile: Option<Account<'info, PlayerProfile>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetupGame<'info> {
+    fn deposit_stake(&self, wager: u64) -> Result<()> {
+        if self.stake_mint.is_none() {
+            // The first deposit has to make the vault rent exempt
+            require_gte!(
+                wager,
+                Rent::get()?.minimum_balance(0),
+                TicTacToeError::WagerTooSmall
+            );
+
+            return pot::deposit_sol(
+                &self.system_program.to_account_info(),
+                &self.player_one.to_account_info(),
+                &self.vault.to_account_info(),
+                wager,
+            );
+        }
+
+        let (
+            Some(stake_mint),
+            Some(player_one_token_account),
+            Some(vault_token_account),
+            Some(token_program),
+        ) = (
+            &self.stake_mint,
+            &self.player_one_token_account,
+            &self.vault_token_account,
+            &self.token_program,
+        )
+        else {
+            return err!(TicTacToeError::MissingPotAccounts);
+        };
+
+        pot::deposit_tokens(
+            &token_program.to_account_info(),
+            &player_one_token_account.to_account_info(),
+            stake_mint,
+            &vault_token_account.to_account_info(),
+            &self.player_one.to_account_info(),
+            wager,
+        )
+    }
+}
+
+pub fn handler(
+    ctx: Context<SetupGame>,
+    player_two: Pubkey,
+    wager: u64,
+    turn_timeout: i64,
+    board_size: u8,
+    win_length: u8,
+) -> Result<()> {
+    require_gte!(turn_timeout, 0, TicTacToeError::InvalidTurnTimeout);
+    // A stake mint only comes with a wager, otherwise its token vault would sit empty
+    require!(
+        wager > 0 || ctx.accounts.stake_mint.is_none(),
+        TicTacToeError::StakeMintWithoutWager
+    );
+    if let Some(stake_mint) = &ctx.accounts.stake_mint {
+        pot::check_stake_mint(stake_mint)?;
+    }
+    // Player two has to be someone else, and someone who could sign to play
+    require_keys_neq!(player_two, Pubkey::default(), TicTacToeError::InvalidPlayer);
+    require_keys_neq!(
+        player_two,
+        ctx.accounts.player_one.key(),
+        TicTacToeError::InvalidPlayer
+    );
+
+    let game = &mut ctx.accounts.game;
+    game.new_board(board_size, win_length)?;
+    game.bump = ctx.bumps.game;
+    game.vault_bump = ctx.bumps.vault;
+    game.wager = wager;
+    game.stake_mint = ctx.accounts.stake_mint.as_ref().map(|mint| mint.key());
+    game.turn_timeout = turn_timeout;
+    game.seed = player_two;
+
+    let players = [ctx.accounts.player_one.key(), player_two];
+
+    game.rated = match (
+        &ctx.accounts.player_one_profile,
+        &ctx.accounts.player_two_profile,
+    ) {
+        (Some(_), Some(_)) => true,
+        (None, None) => false,
+        _ => return err!(TicTacToeError::MissingProfiles),
+    };
+
+    let now = Clock::get()?.unix_timestamp;
+
+    emit!(GameCreated {
+        game: game.key(),
+        players,
+        invited: None,
+        board_size,
+        win_length,
+        wager,
+        stake_mint: game.stake_mint,
+        turn_timeout,
+        rated: game.rated,
+        timestamp: now,
+    });
+
+    // Friendly games start right away. Wagered ones wait for player two to match
+    // the stake, rated ones for player two to agree to put their rating on it.
+    if wager == 0 && !game.rated {
+        game.last_move_at = now;
+        return game.start(players);
+    }
+
+    game.players = players;
+    if wager == 0 {
+        return Ok(());
+    }
+    ctx.accounts.deposit_stake(wager)
+}
@@ -1,12 +1,13 @@
 pub mod error;
+pub mod events;
+pub mod instructions;
+pub mod pot;
+pub mod state;
 
 use anchor_lang::prelude::*;
 
-use num_derive::*;
-use num_traits::*;
-use std::usize;
-
-use crate::error::*;
+pub use instructions::*;
+pub use state::*;
 
 declare_id!("mBuuWhoYBjXk2vqCUL25CU9zPkn4GE9QdvBfTkDcudj");
 
@@ -18,129 +19,62 @@ pub mod anchor_tic_tac_toe {
         msg!("Greetings from: {:?}", ctx.program_id);
         Ok(())
     }
-}
-
-#[derive(Accounts)]
-pub struct Initialize {}
-
-// State Accounts
-#[account]
-pub struct Game {
-    pub players: [Pubkey; 2],          // 32*2
-    pub turn: u8,                      // 1
-    pub board: [[Option<Sign>; 3]; 3], //  9 * (1+1) = 18
-    pub state: GameState,              // 32 + 1
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum GameState {
-    Active,
-    Tie,
-    Won { winner: Pubkey },
-}
-
-#[derive(
-    AnchorSerialize, AnchorDeserialize, FromPrimitive, ToPrimitive, Copy, Clone, PartialEq, Eq,
-)]
-pub enum Sign {
-    X,
-    O,
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize)]
-pub struct Tile {
-    row: u8,
-    column: u8,
-}
 
-// Game Logic
-impl Game {
-    pub const MAXIMUM_SIZE: usize = (32 * 2) + 1 + (9 * (1 + 1)) + 32 + 1;
-    pub fn start(&mut self, players: [Pubkey; 2]) -> Result<()> {
-        require_eq!(self.turn, 0, TicTacToeError::GameAlreadyStarted);
-        self.players = players;
-        self.turn = 1;
-        Ok(())
+    pub fn create_profile(ctx: Context<CreateProfile>) -> Result<()> {
+        create_profile::handler(ctx)
     }
 
-    pub fn is_active(&self) -> bool {
-        self.state == GameState::Active
+    pub fn setup_game(
+        ctx: Context<SetupGame>,
+        player_two: Pubkey,
+        wager: u64,
+        turn_timeout: i64,
+        board_size: u8,
+        win_length: u8,
+    ) -> Result<()> {
+        setup_game::handler(ctx, player_two, wager, turn_timeout, board_size, win_length)
     }
 
-    pub fn current_player_index(&self) -> usize {
-        ((self.turn - 1) % 2) as usize
+    pub fn create_open_game(
+        ctx: Context<CreateOpenGame>,
+        seed: Pubkey,
+        wager: u64,
+        turn_timeout: i64,
+        board_size: u8,
+        win_length: u8,
+        invited: Option<Pubkey>,
+    ) -> Result<()> {
+        create_open_game::handler(
+            ctx,
+            seed,
+            wager,
+            turn_timeout,
+            board_size,
+            win_length,
+            invited,
+        )
     }
 
-    pub fn current_player(&self) -> Pubkey {
-        self.players[self.current_player_index()]
+    pub fn join_game(ctx: Context<JoinGame>) -> Result<()> {
+        join_game::handler(ctx)
     }
 
-    pub fn play(&mut self, tile: &Tile) -> Result<()> {
-        require!(self.is_active(), TicTacToeError::GameAlreadyOver);
-
-        match tile {
-            tile @ Tile {
-                row: 0..=2,
-                column: 0..=2,
-            } => match self.board[tile.row as usize][tile.column as usize] {
-                Some(_) => return Err(TicTacToeError::TileAlreadySet.into()),
-                None => {
-                    self.board[tile.row as usize][tile.column as usize] =
-                        Some(Sign::from_usize(self.current_player_index()).unwrap());
-                }
-            },
-            _ => return Err(TicTacToeError::TileOutOfBounds.into()),
-        }
-
-        self.update_state();
-
-        if GameState::Active == self.state {
-            self.turn += 1;
-        }
-        Ok(())
+    pub fn play(ctx: Context<Play>, tile: Tile) -> Result<()> {
+        play::handler(ctx, tile)
     }
 
-    fn is_winning_trio(&self, trio: [(usize, usize); 3]) -> bool {
-        let [first, second, third] = trio;
-        self.board[first.0][first.1].is_some()
-            && self.board[first.0][first.1] == self.board[second.0][second.1]
-            && self.board[first.0][first.1] == self.board[third.0][third.1]
+    pub fn cancel_game(ctx: Context<CancelGame>) -> Result<()> {
+        cancel_game::handler(ctx)
     }
 
-    fn update_state(&mut self) {
-        for i in 0..=2 {
-            if self.is_winning_trio([(i, 0), (i, 1), (i, 2)]) {
-                self.state = GameState::Won {
-                    winner: self.current_player(),
-                };
-                return;
-            }
-
-            if self.is_winning_trio([(0, i), (1, i), (2, i)]) {
-                self.state = GameState::Won {
-                    winner: self.current_player(),
-                };
-                return;
-            }
-        }
-
-        if self.is_winning_trio([(0, 0), (1, 1), (2, 2)])
-            || self.is_winning_trio([(0, 2), (1, 1), (2, 0)])
-        {
-            self.state = GameState::Won {
-                winner: self.current_player(),
-            };
-            return;
-        }
-
-        for row in 0..=2 {
-            for column in 0..=2 {
-                if self.board[row][column].is_none() {
-                    return;
-                }
-            }
-        }
+    pub fn claim_timeout(ctx: Context<Play>) -> Result<()> {
+        claim_timeout::handler(ctx)
+    }
 
-        self.state = GameState::Tie;
+    pub fn close_game(ctx: Context<CloseGame>) -> Result<()> {
+        close_game::handler(ctx)
     }
 }
+
+#[derive(Accounts)]
+pub struct Initialize {}
@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions,
+        },
+    },
+    token_interface::{close_account, transfer_checked, CloseAccount, Mint, TransferChecked},
+};
+
+use crate::error::TicTacToeError;
+
+// Helpers for moving stakes in and out of a game's pot.
+// SOL stakes sit in a system-owned vault PDA seeded by [b"vault", game],
+// SPL stakes sit in the game PDA's associated token account for the stake mint.
+
+// Payouts assume the token vault holds exactly what both players staked.
+// A Token-2022 transfer fee would leave it short and lock the pot.
+pub fn check_stake_mint(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let info = mint.to_account_info();
+    if *info.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let data = info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    require!(
+        mint.get_extension::<TransferFeeConfig>().is_err(),
+        TicTacToeError::TransferFeeMint
+    );
+    Ok(())
+}
+
+pub fn deposit_sol<'info>(
+    system_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    transfer(
+        CpiContext::new(
+            system_program.clone(),
+            Transfer {
+                from: from.clone(),
+                to: vault.clone(),
+            },
+        ),
+        amount,
+    )
+}
+
+pub fn withdraw_sol<'info>(
+    system_program: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+    vault_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    transfer(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Transfer {
+                from: vault.clone(),
+                to: to.clone(),
+            },
+            vault_seeds,
+        ),
+        amount,
+    )
+}
+
+pub fn deposit_tokens<'info>(
+    token_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    vault: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    transfer_checked(
+        CpiContext::new(
+            token_program.clone(),
+            TransferChecked {
+                from: from.clone(),
+                mint: mint.to_account_info(),
+                to: vault.clone(),
+                authority: authority.clone(),
+            },
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+pub fn withdraw_tokens<'info>(
+    token_program: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &AccountInfo<'info>,
+    game: &AccountInfo<'info>,
+    amount: u64,
+    game_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            TransferChecked {
+                from: vault.clone(),
+                mint: mint.to_account_info(),
+                to: to.clone(),
+                authority: game.clone(),
+            },
+            game_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+// Returns the token vault's rent to whoever paid for it, player one
+pub fn close_token_vault<'info>(
+    token_program: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    game: &AccountInfo<'info>,
+    game_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    close_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        CloseAccount {
+            account: vault.clone(),
+            destination: destination.clone(),
+            authority: game.clone(),
+        },
+        game_seeds,
+    ))
+}
@@ -0,0 +1,288 @@
+use anchor_lang::prelude::*;
+
+use num_derive::*;
+use num_traits::*;
+
+use tic_tac_toe_core::{self as rules, Outcome};
+
+use crate::{error::*, state::PlayerProfile};
+
+// State Accounts
+#[account]
+pub struct Game {
+    pub players: [Pubkey; 2],       // 32*2
+    pub turn: u8,                   // 1
+    pub board_size: u8,             // 1
+    pub win_length: u8,             // 1
+    pub board: Vec<Option<Sign>>,   // 4 + board_size^2 * (1+1), row by row
+    pub state: GameState,           // 32 + 1
+    pub bump: u8,                   // 1
+    pub wager: u64,                 // 8, stake per player, 0 for a friendly game
+    pub stake_mint: Option<Pubkey>, // 1 + 32, None when staking SOL
+    pub vault_bump: u8,             // 1
+    pub turn_timeout: i64,          // 8, seconds a player has to move, 0 disables claims
+    pub last_move_at: i64,          // 8
+    pub rated: bool,                // 1, both players' profiles are updated when it ends
+    pub seed: Pubkey,               // 32, player two, or any key the host picked for an open game
+    pub invited: Option<Pubkey>,    // 1 + 32, the only wallet allowed to take an open seat
+    pub joined: bool,               // 1, player two has signed for the game, by joining or moving
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum GameState {
+    Active,
+    Tie,
+    Won { winner: Pubkey },
+}
+
+#[derive(
+    AnchorSerialize,
+    AnchorDeserialize,
+    FromPrimitive,
+    ToPrimitive,
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+pub enum Sign {
+    X,
+    O,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Tile {
+    pub row: u8,
+    pub column: u8,
+}
+
+// Game Logic
+impl Game {
+    pub const MIN_BOARD_SIZE: u8 = rules::MIN_BOARD_SIZE;
+    pub const MAX_BOARD_SIZE: u8 = rules::MAX_BOARD_SIZE;
+
+    pub fn maximum_size(board_size: u8) -> usize {
+        let tiles = board_size as usize * board_size as usize;
+        (32 * 2)
+            + 1
+            + 1
+            + 1
+            + (4 + tiles * (1 + 1))
+            + 32
+            + 1
+            + 1
+            + 8
+            + (1 + 32)
+            + 1
+            + 8
+            + 8
+            + 1
+            + 32
+            + (1 + 32)
+            + 1
+    }
+
+    pub fn validate_board(board_size: u8, win_length: u8) -> Result<()> {
+        rules::validate_board(board_size, win_length).map_err(TicTacToeError::from)?;
+        Ok(())
+    }
+
+    // Lays out an empty board_size x board_size board, won by win_length in a row
+    pub fn new_board(&mut self, board_size: u8, win_length: u8) -> Result<()> {
+        Self::validate_board(board_size, win_length)?;
+
+        self.board_size = board_size;
+        self.win_length = win_length;
+        self.board = vec![None; board_size as usize * board_size as usize];
+        Ok(())
+    }
+
+    pub fn tile(&self, row: u8, column: u8) -> Option<Sign> {
+        self.board[self.index(row, column)]
+    }
+
+    fn index(&self, row: u8, column: u8) -> usize {
+        row as usize * self.board_size as usize + column as usize
+    }
+
+    pub fn start(&mut self, players: [Pubkey; 2]) -> Result<()> {
+        require_eq!(self.turn, 0, TicTacToeError::GameAlreadyStarted);
+        self.players = players;
+        self.turn = 1;
+        Ok(())
+    }
+
+    // Takes the second seat and starts the game.
+    // Open games seat whoever joins first, unless the host invited someone.
+    pub fn join(&mut self, player: Pubkey) -> Result<()> {
+        require!(!self.is_started(), TicTacToeError::GameAlreadyStarted);
+
+        if self.has_open_seat() {
+            require_keys_neq!(player, self.players[0], TicTacToeError::InvalidPlayer);
+            if let Some(invited) = self.invited {
+                require_keys_eq!(player, invited, TicTacToeError::InvalidPlayer);
+            }
+        } else {
+            require_keys_eq!(player, self.players[1], TicTacToeError::InvalidPlayer);
+        }
+
+        self.joined = true;
+        self.start([self.players[0], player])
+    }
+
+    pub fn has_open_seat(&self) -> bool {
+        self.players[1] == Pubkey::default()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.state == GameState::Active
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.turn > 0
+    }
+
+    pub fn is_wagered(&self) -> bool {
+        self.wager > 0
+    }
+
+    // What each player takes out of the pot: everything to the winner, half each on a tie
+    pub fn pot_shares(&self) -> Result<[u64; 2]> {
+        let pot = self
+            .wager
+            .checked_mul(2)
+            .ok_or(TicTacToeError::WagerOverflow)?;
+
+        Ok(match self.state {
+            GameState::Active => [0, 0],
+            GameState::Tie => [self.wager, self.wager],
+            GameState::Won { winner } if winner == self.players[0] => [pot, 0],
+            GameState::Won { .. } => [0, pot],
+        })
+    }
+
+    pub fn winner(&self) -> Option<Pubkey> {
+        match self.state {
+            GameState::Won { winner } => Some(winner),
+            _ => None,
+        }
+    }
+
+    // Player one's score for rating purposes, None while the game is still going
+    pub fn player_one_score(&self) -> Option<i64> {
+        match self.state {
+            GameState::Active => None,
+            GameState::Tie => Some(PlayerProfile::TIE),
+            GameState::Won { winner } if winner == self.players[0] => Some(PlayerProfile::WIN),
+            GameState::Won { .. } => Some(PlayerProfile::LOSS),
+        }
+    }
+
+    pub fn current_player_index(&self) -> usize {
+        ((self.turn - 1) % 2) as usize
+    }
+
+    pub fn current_player(&self) -> Pubkey {
+        self.players[self.current_player_index()]
+    }
+
+    pub fn current_sign(&self) -> Sign {
+        Sign::from_usize(self.current_player_index()).unwrap()
+    }
+
+    pub fn waiting_player(&self) -> Pubkey {
+        self.players[1 - self.current_player_index()]
+    }
+
+    pub fn is_turn_expired(&self, now: i64) -> bool {
+        self.turn_timeout > 0 && now.saturating_sub(self.last_move_at) >= self.turn_timeout
+    }
+
+    // A friendly game whose player two let their first turn run out without
+    // ever taking their seat. Nobody can win it, so player one may cancel it.
+    pub fn is_abandoned(&self, now: i64) -> bool {
+        self.is_started()
+            && self.is_active()
+            && !self.joined
+            && self.current_player_index() == 1
+            && self.is_turn_expired(now)
+    }
+
+    // The player who ran out the clock loses
+    pub fn forfeit(&mut self) -> Result<()> {
+        require!(self.is_active(), TicTacToeError::GameAlreadyOver);
+
+        self.state = GameState::Won {
+            winner: self.waiting_player(),
+        };
+        Ok(())
+    }
+
+    pub fn play(&mut self, tile: &Tile) -> Result<()> {
+        require!(self.is_active(), TicTacToeError::GameAlreadyOver);
+
+        // Friendly games start without player two, their first move is when they join
+        if self.current_player_index() == 1 {
+            self.joined = true;
+        }
+
+        // The rules themselves live in tic-tac-toe-core, shared with the native and Pinocchio ports
+        match rules::play(self, self.turn, (*tile).into()).map_err(TicTacToeError::from)? {
+            Outcome::Active => self.turn += 1,
+            Outcome::Tie => self.state = GameState::Tie,
+            Outcome::Won(sign) => {
+                self.state = GameState::Won {
+                    winner: self.players[sign.player_index()],
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl rules::Board for Game {
+    fn board_size(&self) -> u8 {
+        self.board_size
+    }
+
+    fn win_length(&self) -> u8 {
+        self.win_length
+    }
+
+    fn tile(&self, row: u8, column: u8) -> Option<rules::Sign> {
+        Game::tile(self, row, column).map(Into::into)
+    }
+
+    fn set_tile(&mut self, row: u8, column: u8, sign: rules::Sign) {
+        let index = self.index(row, column);
+        self.board[index] = Some(sign.into());
+    }
+}
+
+impl From<rules::Sign> for Sign {
+    fn from(sign: rules::Sign) -> Self {
+        match sign {
+            rules::Sign::X => Sign::X,
+            rules::Sign::O => Sign::O,
+        }
+    }
+}
+
+impl From<Sign> for rules::Sign {
+    fn from(sign: Sign) -> Self {
+        match sign {
+            Sign::X => rules::Sign::X,
+            Sign::O => rules::Sign::O,
+        }
+    }
+}
+
+impl From<Tile> for rules::Tile {
+    fn from(tile: Tile) -> Self {
+        rules::Tile {
+            row: tile.row,
+            column: tile.column,
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod game;
+pub mod profile;
+
+pub use game::*;
+pub use profile::*;
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+// One per wallet, updated whenever a rated game finishes
+#[account]
+pub struct PlayerProfile {
+    pub player: Pubkey,    // 32
+    pub games_played: u32, // 4
+    pub wins: u32,         // 4
+    pub losses: u32,       // 4
+    pub ties: u32,         // 4
+    pub rating: u32,       // 4
+    pub bump: u8,          // 1
+}
+
+impl PlayerProfile {
+    pub const MAXIMUM_SIZE: usize = 32 + (4 * 5) + 1;
+    pub const STARTING_RATING: u32 = 1200;
+    pub const K_FACTOR: i64 = 32;
+
+    // Scores in basis points
+    pub const WIN: i64 = 10_000;
+    pub const TIE: i64 = 5_000;
+    pub const LOSS: i64 = 0;
+
+    // Expected score against an opponent rated 0, 50, 100 ... 800 points higher,
+    // 1 / (1 + 10^(diff / 400)) in basis points, so ratings stay integer math
+    const EXPECTED_SCORES: [i64; 17] = [
+        5000, 4285, 3599, 2966, 2403, 1917, 1510, 1177, 909, 698, 532, 405, 307, 232, 175, 132, 99,
+    ];
+    const EXPECTED_SCORE_STEP: i64 = 50;
+
+    pub fn init(&mut self, player: Pubkey, bump: u8) {
+        self.player = player;
+        self.rating = Self::STARTING_RATING;
+        self.bump = bump;
+    }
+
+    // Expected score in basis points, interpolated between table entries.
+    // Gaps past 800 points are treated as 800.
+    pub fn expected_score(rating: u32, opponent_rating: u32) -> i64 {
+        let max_diff = Self::EXPECTED_SCORE_STEP * (Self::EXPECTED_SCORES.len() as i64 - 1);
+        let diff = (opponent_rating as i64 - rating as i64).clamp(-max_diff, max_diff);
+
+        let underdog_score = |diff: i64| {
+            let step = (diff / Self::EXPECTED_SCORE_STEP) as usize;
+            let offset = diff % Self::EXPECTED_SCORE_STEP;
+            let low = Self::EXPECTED_SCORES[step];
+            let high = Self::EXPECTED_SCORES[(step + 1).min(Self::EXPECTED_SCORES.len() - 1)];
+            low - (low - high) * offset / Self::EXPECTED_SCORE_STEP
+        };
+
+        if diff >= 0 {
+            underdog_score(diff)
+        } else {
+            Self::WIN - underdog_score(-diff)
+        }
+    }
+
+    // K * (score - expected), rounded to the nearest point
+    pub fn rating_change(rating: u32, opponent_rating: u32, score: i64) -> i64 {
+        let delta = Self::K_FACTOR * (score - Self::expected_score(rating, opponent_rating));
+        (delta + delta.signum() * Self::WIN / 2) / Self::WIN
+    }
+
+    // Records a finished game for both players, whatever one gains the other loses
+    pub fn record_game(player_one: &mut Self, player_two: &mut Self, player_one_score: i64) {
+        let change = Self::rating_change(player_one.rating, player_two.rating, player_one_score);
+
+        player_one.record(player_one_score, change);
+        player_two.record(Self::WIN - player_one_score, -change);
+    }
+
+    fn record(&mut self, score: i64, rating_change: i64) {
+        self.games_played += 1;
+        match score {
+            Self::WIN => self.wins += 1,
+            Self::LOSS => self.losses += 1,
+            _ => self.ties += 1,
+        }
+        self.rating = (self.rating as i64 + rating_change).max(0) as u32;
+    }
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use anchor_tic_tac_toe::{error::TicTacToeError, Game, GameState};
+use common::{assert_error, TestGame};
+use solana_sdk::signer::Signer;
+
+#[tokio::test]
+async fn game_account_is_sized_for_the_board() {
+    let mut test = TestGame::with_board(15, 5).await;
+
+    let game = test.game().await;
+    assert_eq!(game.board_size, 15);
+    assert_eq!(game.win_length, 5);
+    assert_eq!(game.board.len(), 15 * 15);
+
+    let account = test.account(test.game).await.unwrap();
+    assert_eq!(account.data.len(), 8 + Game::maximum_size(15));
+}
+
+#[tokio::test]
+async fn five_by_five_needs_four_in_a_row() {
+    let mut test = TestGame::with_board(5, 4).await;
+
+    // Three on the diagonal isn't enough on this board
+    test.play_moves(&[(0, 0), (4, 0), (1, 1), (4, 1), (2, 2), (4, 2)])
+        .await;
+    assert_eq!(test.game().await.state, GameState::Active);
+
+    test.play_moves(&[(3, 3)]).await;
+    assert_eq!(
+        test.game().await.state,
+        GameState::Won {
+            winner: test.player_one.pubkey()
+        }
+    );
+}
+
+#[tokio::test]
+async fn gomoku_line_completed_in_the_middle() {
+    let mut test = TestGame::with_board(15, 5).await;
+
+    // X fills in the gap of X X . X X on row 7
+    test.play_moves(&[
+        (7, 3),
+        (0, 0),
+        (7, 4),
+        (0, 1),
+        (7, 6),
+        (0, 2),
+        (7, 7),
+        (0, 3),
+    ])
+    .await;
+    assert_eq!(test.game().await.state, GameState::Active);
+
+    test.play_moves(&[(7, 5)]).await;
+    assert_eq!(
+        test.game().await.state,
+        GameState::Won {
+            winner: test.player_one.pubkey()
+        }
+    );
+}
+
+#[tokio::test]
+async fn anti_diagonal_on_a_larger_board() {
+    let mut test = TestGame::with_board(4, 3).await;
+
+    test.play_moves(&[(0, 3), (3, 3), (1, 2), (0, 0), (2, 1)])
+        .await;
+
+    assert_eq!(
+        test.game().await.state,
+        GameState::Won {
+            winner: test.player_one.pubkey()
+        }
+    );
+}
+
+#[tokio::test]
+async fn bounds_follow_the_board_size() {
+    let mut test = TestGame::with_board(5, 4).await;
+    let player_one = test.player_one.insecure_clone();
+
+    assert_error(
+        test.play(&player_one, 5, 0).await,
+        TicTacToeError::TileOutOfBounds,
+    );
+    assert_error(
+        test.play(&player_one, 0, 5).await,
+        TicTacToeError::TileOutOfBounds,
+    );
+
+    test.play(&player_one, 4, 4).await.unwrap();
+    assert_eq!(test.game().await.turn, 2);
+}
+
+#[tokio::test]
+async fn rejects_invalid_boards() {
+    for (board_size, win_length) in [(2, 2), (3, 2), (4, 5), (16, 5)] {
+        let mut test = TestGame::create(None).await;
+        test.board_size = board_size;
+        test.win_length = win_length;
+
+        assert_error(test.setup_game(0).await, TicTacToeError::InvalidBoardConfig);
+    }
+}
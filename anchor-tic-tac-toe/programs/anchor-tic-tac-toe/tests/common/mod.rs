@@ -0,0 +1,700 @@
+#![allow(dead_code)]
+
+use std::sync::Once;
+
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::{
+        entrypoint::ProgramResult,
+        program_option::COption,
+        program_pack::Pack,
+        program_stubs::{self, SyscallStubs},
+    },
+    AccountDeserialize, AnchorDeserialize, Discriminator, Event, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address_with_program_id},
+    token::{self, spl_token},
+    token_2022::{
+        self,
+        spl_token_2022::{
+            self,
+            extension::{
+                transfer_fee::TransferFeeConfig, BaseStateWithExtensionsMut, ExtensionType,
+                StateWithExtensionsMut,
+            },
+        },
+    },
+};
+use anchor_tic_tac_toe::{error::TicTacToeError, Game, PlayerProfile, Tile};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+pub const STARTING_LAMPORTS: u64 = 10 * LAMPORTS_PER_SOL;
+pub const STARTING_TOKENS: u64 = 1_000_000_000;
+pub const TURN_TIMEOUT: i64 = 60;
+
+// Anchor's entrypoint ties the account slice to the `AccountInfo` lifetime,
+// which `processor!` can't express, so leak the slice for the test run.
+fn entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    anchor_tic_tac_toe::entry(program_id, accounts, data)
+}
+
+pub struct TestGame {
+    pub context: ProgramTestContext,
+    pub player_one: Keypair,
+    pub player_two: Keypair,
+    pub game: Pubkey,
+    pub vault: Pubkey,
+    pub stake_mint: Option<Pubkey>,
+    pub token_program: Pubkey,
+    pub wager: u64,
+    pub turn_timeout: i64,
+    pub board_size: u8,
+    pub win_length: u8,
+    pub rated: bool,
+    // Log messages of every transaction sent so far
+    pub logs: Vec<String>,
+}
+
+impl TestGame {
+    // A friendly game, ready for player one's first move
+    pub async fn new() -> Self {
+        let mut test_game = Self::create(None).await;
+        test_game.setup_game(0).await.unwrap();
+        test_game
+    }
+
+    // A friendly game on a board_size x board_size board, won by win_length in a row
+    pub async fn with_board(board_size: u8, win_length: u8) -> Self {
+        let mut test_game = Self::create(None).await;
+        test_game.board_size = board_size;
+        test_game.win_length = win_length;
+        test_game.setup_game(0).await.unwrap();
+        test_game
+    }
+
+    // A SOL wagered game waiting for player two to join
+    pub async fn with_wager(wager: u64) -> Self {
+        let mut test_game = Self::create(None).await;
+        test_game.setup_game(wager).await.unwrap();
+        test_game
+    }
+
+    // An SPL wagered game waiting for player two to join
+    pub async fn with_token_wager(wager: u64) -> Self {
+        let mut test_game = Self::create(Some(Pubkey::new_unique())).await;
+        test_game.setup_game(wager).await.unwrap();
+        test_game
+    }
+
+    // An open game hosted by player one, waiting for anyone, or just the invited wallet, to join
+    pub async fn open(invited: Option<Pubkey>, wager: u64) -> Self {
+        let mut test_game = Self::create(None).await;
+        test_game.create_open_game(invited, wager).await.unwrap();
+        test_game
+    }
+
+    // Funds both players, and gives them token accounts when staking a mint
+    pub async fn create(stake_mint: Option<Pubkey>) -> Self {
+        Self::create_with(stake_mint, None).await
+    }
+
+    // Like create, but with a Token-2022 stake mint that charges
+    // transfer_fee_bps on every transfer
+    pub async fn with_transfer_fee_mint(transfer_fee_bps: u16) -> Self {
+        Self::create_with(Some(Pubkey::new_unique()), Some(transfer_fee_bps)).await
+    }
+
+    async fn create_with(stake_mint: Option<Pubkey>, transfer_fee_bps: Option<u16>) -> Self {
+        let player_one = Keypair::new();
+        let player_two = Keypair::new();
+
+        let mut program_test = ProgramTest::new(
+            "anchor_tic_tac_toe",
+            anchor_tic_tac_toe::ID,
+            processor!(entry),
+        );
+
+        for player in [&player_one, &player_two] {
+            program_test.add_account(
+                player.pubkey(),
+                Account::new(STARTING_LAMPORTS, 0, &system_program::ID),
+            );
+        }
+
+        let token_program = match transfer_fee_bps {
+            Some(_) => token_2022::ID,
+            None => token::ID,
+        };
+        if let Some(mint) = stake_mint {
+            match transfer_fee_bps {
+                Some(bps) => add_transfer_fee_mint(&mut program_test, mint, bps),
+                None => add_mint(&mut program_test, mint),
+            }
+            for player in [&player_one, &player_two] {
+                add_token_account(
+                    &mut program_test,
+                    mint,
+                    token_program,
+                    player.pubkey(),
+                    STARTING_TOKENS,
+                );
+            }
+        }
+
+        let context = program_test.start_with_context().await;
+        capture_log_data();
+
+        let (game, _) = Pubkey::find_program_address(
+            &[
+                b"game",
+                player_one.pubkey().as_ref(),
+                player_two.pubkey().as_ref(),
+            ],
+            &anchor_tic_tac_toe::ID,
+        );
+        let (vault, _) =
+            Pubkey::find_program_address(&[b"vault", game.as_ref()], &anchor_tic_tac_toe::ID);
+
+        Self {
+            context,
+            player_one,
+            player_two,
+            game,
+            vault,
+            stake_mint,
+            token_program,
+            wager: 0,
+            turn_timeout: TURN_TIMEOUT,
+            board_size: 3,
+            win_length: 3,
+            rated: false,
+            logs: Vec::new(),
+        }
+    }
+
+    // A friendly rated game between two fresh profiles, player two has joined
+    pub async fn rated() -> Self {
+        let mut test_game = Self::create(None).await;
+        test_game.create_profiles().await;
+        test_game.rated = true;
+        test_game.setup_game(0).await.unwrap();
+        let player_two = test_game.player_two.insecure_clone();
+        test_game.join_game(&player_two).await.unwrap();
+        test_game
+    }
+
+    pub async fn create_profiles(&mut self) {
+        for player in [
+            self.player_one.insecure_clone(),
+            self.player_two.insecure_clone(),
+        ] {
+            self.create_profile(&player).await.unwrap();
+        }
+    }
+
+    pub async fn create_profile(&mut self, player: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_tic_tac_toe::ID,
+            accounts: anchor_tic_tac_toe::accounts::CreateProfile {
+                profile: profile_address(player.pubkey()),
+                player: player.pubkey(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_tic_tac_toe::instruction::CreateProfile {}.data(),
+        };
+
+        self.send(ix, player).await
+    }
+
+    pub async fn setup_game(&mut self, wager: u64) -> Result<(), BanksClientError> {
+        self.setup_game_against(self.player_two.pubkey(), wager)
+            .await
+    }
+
+    // Sets the game up with any key as player two, at that pair's address
+    pub async fn setup_game_against(
+        &mut self,
+        player_two: Pubkey,
+        wager: u64,
+    ) -> Result<(), BanksClientError> {
+        self.wager = wager;
+        self.game = Pubkey::find_program_address(
+            &[
+                b"game",
+                self.player_one.pubkey().as_ref(),
+                player_two.as_ref(),
+            ],
+            &anchor_tic_tac_toe::ID,
+        )
+        .0;
+        self.vault =
+            Pubkey::find_program_address(&[b"vault", self.game.as_ref()], &anchor_tic_tac_toe::ID)
+                .0;
+
+        let ix = Instruction {
+            program_id: anchor_tic_tac_toe::ID,
+            accounts: anchor_tic_tac_toe::accounts::SetupGame {
+                game: self.game,
+                player_one: self.player_one.pubkey(),
+                vault: self.vault,
+                stake_mint: self.stake_mint,
+                player_one_token_account: self.token_account(self.player_one.pubkey()),
+                vault_token_account: self.token_account(self.game),
+                player_one_profile: self.profile(self.player_one.pubkey()),
+                player_two_profile: self.profile(player_two),
+                token_program: self.stake_mint.map(|_| self.token_program),
+                associated_token_program: self.stake_mint.map(|_| associated_token::ID),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_tic_tac_toe::instruction::SetupGame {
+                player_two,
+                wager,
+                turn_timeout: self.turn_timeout,
+                board_size: self.board_size,
+                win_length: self.win_length,
+            }
+            .data(),
+        };
+
+        let player_one = self.player_one.insecure_clone();
+        self.send(ix, &player_one).await
+    }
+
+    // Hosts the game under a fresh seed, player two is whoever joins
+    pub async fn create_open_game(
+        &mut self,
+        invited: Option<Pubkey>,
+        wager: u64,
+    ) -> Result<(), BanksClientError> {
+        let seed = Pubkey::new_unique();
+        self.wager = wager;
+        self.game = Pubkey::find_program_address(
+            &[b"game", self.player_one.pubkey().as_ref(), seed.as_ref()],
+            &anchor_tic_tac_toe::ID,
+        )
+        .0;
+        self.vault =
+            Pubkey::find_program_address(&[b"vault", self.game.as_ref()], &anchor_tic_tac_toe::ID)
+                .0;
+
+        let ix = Instruction {
+            program_id: anchor_tic_tac_toe::ID,
+            accounts: anchor_tic_tac_toe::accounts::CreateOpenGame {
+                game: self.game,
+                host: self.player_one.pubkey(),
+                vault: self.vault,
+                stake_mint: self.stake_mint,
+                host_token_account: self.token_account(self.player_one.pubkey()),
+                vault_token_account: self.token_account(self.game),
+                host_profile: self.profile(self.player_one.pubkey()),
+                token_program: self.stake_mint.map(|_| self.token_program),
+                associated_token_program: self.stake_mint.map(|_| associated_token::ID),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_tic_tac_toe::instruction::CreateOpenGame {
+                seed,
+                wager,
+                turn_timeout: self.turn_timeout,
+                board_size: self.board_size,
+                win_length: self.win_length,
+                invited,
+            }
+            .data(),
+        };
+
+        let player_one = self.player_one.insecure_clone();
+        self.send(ix, &player_one).await
+    }
+
+    pub async fn join_game(&mut self, player: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_tic_tac_toe::ID,
+            accounts: anchor_tic_tac_toe::accounts::JoinGame {
+                game: self.game,
+                player_two: player.pubkey(),
+                vault: self.vault,
+                stake_mint: self.stake_mint,
+                player_two_token_account: self.token_account(player.pubkey()),
+                vault_token_account: self.token_account(self.game),
+                player_two_profile: self.profile(player.pubkey()),
+                token_program: self.stake_mint.map(|_| self.token_program),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_tic_tac_toe::instruction::JoinGame {}.data(),
+        };
+
+        self.send(ix, player).await
+    }
+
+    pub async fn cancel_game(&mut self) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_tic_tac_toe::ID,
+            accounts: anchor_tic_tac_toe::accounts::CancelGame {
+                game: self.game,
+                player_one: self.player_one.pubkey(),
+                vault: self.vault,
+                stake_mint: self.stake_mint,
+                player_one_token_account: self.token_account(self.player_one.pubkey()),
+                vault_token_account: self.token_account(self.game),
+                token_program: self.stake_mint.map(|_| self.token_program),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_tic_tac_toe::instruction::CancelGame {}.data(),
+        };
+
+        let player_one = self.player_one.insecure_clone();
+        self.send(ix, &player_one).await
+    }
+
+    pub async fn close_game(&mut self, player: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_tic_tac_toe::ID,
+            accounts: anchor_tic_tac_toe::accounts::CloseGame {
+                game: self.game,
+                player: player.pubkey(),
+                player_one: self.player_one.pubkey(),
+            }
+            .to_account_metas(None),
+            data: anchor_tic_tac_toe::instruction::CloseGame {}.data(),
+        };
+
+        self.send(ix, player).await
+    }
+
+    pub async fn play(
+        &mut self,
+        player: &Keypair,
+        row: u8,
+        column: u8,
+    ) -> Result<(), BanksClientError> {
+        let data = anchor_tic_tac_toe::instruction::Play {
+            tile: Tile { row, column },
+        }
+        .data();
+
+        self.send_play(player, data).await
+    }
+
+    pub async fn claim_timeout(&mut self, player: &Keypair) -> Result<(), BanksClientError> {
+        let data = anchor_tic_tac_toe::instruction::ClaimTimeout {}.data();
+
+        self.send_play(player, data).await
+    }
+
+    // play and claim_timeout share their accounts
+    async fn send_play(&mut self, player: &Keypair, data: Vec<u8>) -> Result<(), BanksClientError> {
+        // Friendly games leave the pot accounts out
+        let wagered = self.wager > 0;
+        let sol_pot = wagered && self.stake_mint.is_none();
+
+        let ix = Instruction {
+            program_id: anchor_tic_tac_toe::ID,
+            accounts: anchor_tic_tac_toe::accounts::Play {
+                game: self.game,
+                player: player.pubkey(),
+                player_one: wagered.then(|| self.player_one.pubkey()),
+                player_two: wagered.then(|| self.player_two.pubkey()),
+                vault: sol_pot.then_some(self.vault),
+                stake_mint: self.stake_mint,
+                player_one_token_account: self.token_account(self.player_one.pubkey()),
+                player_two_token_account: self.token_account(self.player_two.pubkey()),
+                vault_token_account: self.token_account(self.game),
+                player_one_profile: self.profile(self.player_one.pubkey()),
+                player_two_profile: self.profile(self.player_two.pubkey()),
+                token_program: self.stake_mint.map(|_| self.token_program),
+                system_program: sol_pot.then_some(system_program::ID),
+            }
+            .to_account_metas(None),
+            data,
+        };
+
+        self.send(ix, player).await
+    }
+
+    // Alternates between the two players, starting with player one
+    pub async fn play_moves(&mut self, moves: &[(u8, u8)]) {
+        for (i, (row, column)) in moves.iter().enumerate() {
+            let player = if i % 2 == 0 {
+                self.player_one.insecure_clone()
+            } else {
+                self.player_two.insecure_clone()
+            };
+            self.play(&player, *row, *column).await.unwrap();
+        }
+    }
+
+    pub async fn send(
+        &mut self,
+        ix: Instruction,
+        signer: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        // Refresh the blockhash so identical moves don't dedupe as the same transaction
+        let recent_blockhash = self.context.get_new_latest_blockhash().await?;
+
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&self.context.payer.pubkey()),
+            &[&self.context.payer, signer],
+            recent_blockhash,
+        );
+        let result = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(tx)
+            .await?;
+
+        if let Some(metadata) = result.metadata {
+            self.logs.extend(metadata.log_messages);
+        }
+        result.result.map_err(BanksClientError::TransactionError)
+    }
+
+    // Every event of type T emitted so far, in order
+    pub fn events<T: Event + Discriminator + AnchorDeserialize>(&self) -> Vec<T> {
+        self.logs
+            .iter()
+            .filter_map(|log| log.split_once("Program data: "))
+            .filter_map(|(_, data)| STANDARD.decode(data).ok())
+            .filter(|data| data.starts_with(&T::DISCRIMINATOR))
+            .map(|data| T::try_from_slice(&data[8..]).unwrap())
+            .collect()
+    }
+
+    pub async fn advance_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn game(&mut self) -> Game {
+        let account = self.account(self.game).await.unwrap();
+        Game::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn account(&mut self, address: Pubkey) -> Option<Account> {
+        self.context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.account(address)
+            .await
+            .map_or(0, |account| account.lamports)
+    }
+
+    pub async fn token_balance(&mut self, owner: Pubkey) -> u64 {
+        let address = self.token_account(owner).unwrap();
+        let account = self.account(address).await.unwrap();
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    pub async fn player_profile(&mut self, player: Pubkey) -> PlayerProfile {
+        let account = self.account(profile_address(player)).await.unwrap();
+        PlayerProfile::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    // Profiles are only passed along for rated games
+    pub fn profile(&self, player: Pubkey) -> Option<Pubkey> {
+        self.rated.then(|| profile_address(player))
+    }
+
+    pub fn token_account(&self, owner: Pubkey) -> Option<Pubkey> {
+        self.stake_mint.map(|mint| {
+            get_associated_token_address_with_program_id(&owner, &mint, &self.token_program)
+        })
+    }
+}
+
+pub fn profile_address(player: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"profile", player.as_ref()], &anchor_tic_tac_toe::ID).0
+}
+
+fn add_mint(program_test: &mut ProgramTest, mint: Pubkey) {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint::pack(
+        spl_token::state::Mint {
+            mint_authority: COption::None,
+            supply: 2 * STARTING_TOKENS,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        },
+        &mut data,
+    )
+    .unwrap();
+
+    program_test.add_account(
+        mint,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: token::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+fn add_transfer_fee_mint(program_test: &mut ProgramTest, mint: Pubkey, transfer_fee_bps: u16) {
+    let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+        ExtensionType::TransferFeeConfig,
+    ])
+    .unwrap();
+    let mut data = vec![0; space];
+    let mut state =
+        StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data)
+            .unwrap();
+    let fee = state.init_extension::<TransferFeeConfig>(true).unwrap();
+    fee.newer_transfer_fee.transfer_fee_basis_points = transfer_fee_bps.into();
+    fee.newer_transfer_fee.maximum_fee = u64::MAX.into();
+    fee.older_transfer_fee = fee.newer_transfer_fee;
+    state.base = spl_token_2022::state::Mint {
+        mint_authority: COption::None,
+        supply: 2 * STARTING_TOKENS,
+        decimals: 6,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+
+    program_test.add_account(
+        mint,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: token_2022::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+fn add_token_account(
+    program_test: &mut ProgramTest,
+    mint: Pubkey,
+    token_program: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+) {
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account::pack(
+        spl_token::state::Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        },
+        &mut data,
+    )
+    .unwrap();
+
+    program_test.add_account(
+        get_associated_token_address_with_program_id(&owner, &mint, &token_program),
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: token_program,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+pub fn assert_error(result: Result<(), BanksClientError>, error: TicTacToeError) {
+    let err = result.unwrap_err().unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(0, InstructionError::Custom(error.into()))
+    );
+}
+
+// Natively run programs print `sol_log_data` to stdout instead of the transaction
+// logs, so route it through `sol_log` the way the runtime would write it.
+struct LogDataStubs(Box<dyn SyscallStubs>);
+
+impl SyscallStubs for LogDataStubs {
+    fn sol_log(&self, message: &str) {
+        self.0.sol_log(message)
+    }
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+        self.0
+            .sol_log(&format!("Program data: {}", fields.join(" ")))
+    }
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.0
+            .sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_clock_sysvar(var_addr)
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_fees_sysvar(var_addr)
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_rent_sysvar(var_addr)
+    }
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_last_restart_slot(var_addr)
+    }
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.0.sol_get_return_data()
+    }
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.0.sol_set_return_data(data)
+    }
+    fn sol_get_stack_height(&self) -> u64 {
+        self.0.sol_get_stack_height()
+    }
+}
+
+// Has to run after ProgramTest installed its own stubs, which it does on first start,
+// and before any transaction is sent, since the stubs are briefly swapped out
+fn capture_log_data() {
+    struct Placeholder;
+    impl SyscallStubs for Placeholder {}
+
+    static ONCE: Once = Once::new();
+
+    ONCE.call_once(|| {
+        let stubs = program_stubs::set_syscall_stubs(Box::new(Placeholder));
+        program_stubs::set_syscall_stubs(Box::new(LogDataStubs(stubs)));
+    });
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use anchor_tic_tac_toe::{
+    events::{GameCancelled, GameCreated, GameFinished, MovePlayed, PlayerJoined},
+    GameState, Sign,
+};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signer::Signer};
+
+const WAGER: u64 = LAMPORTS_PER_SOL;
+
+// X X X
+// O O .
+// . . .
+const PLAYER_ONE_WINS: [(u8, u8); 5] = [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)];
+
+// X O X
+// X O O
+// O X X
+const TIE: [(u8, u8); 9] = [
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (1, 1),
+    (1, 0),
+    (1, 2),
+    (2, 1),
+    (2, 0),
+    (2, 2),
+];
+
+#[tokio::test]
+async fn setup_game_emits_game_created() {
+    let test_game = TestGame::with_board(5, 4).await;
+
+    let events = test_game.events::<GameCreated>();
+    assert_eq!(events.len(), 1);
+
+    let created = &events[0];
+    assert_eq!(created.game, test_game.game);
+    assert_eq!(
+        created.players,
+        [test_game.player_one.pubkey(), test_game.player_two.pubkey()]
+    );
+    assert_eq!((created.board_size, created.win_length), (5, 4));
+    assert_eq!(created.wager, 0);
+    assert_eq!(created.stake_mint, None);
+    assert_eq!(created.turn_timeout, TURN_TIMEOUT);
+    assert!(!created.rated);
+}
+
+#[tokio::test]
+async fn join_game_emits_player_joined() {
+    let mut test_game = TestGame::with_wager(WAGER).await;
+    let player_two = test_game.player_two.insecure_clone();
+    test_game.join_game(&player_two).await.unwrap();
+
+    let events = test_game.events::<PlayerJoined>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].game, test_game.game);
+    assert_eq!(events[0].player, player_two.pubkey());
+}
+
+#[tokio::test]
+async fn play_emits_move_played() {
+    let mut test_game = TestGame::new().await;
+    let player_one = test_game.player_one.insecure_clone();
+    let player_two = test_game.player_two.insecure_clone();
+
+    test_game.play(&player_one, 1, 1).await.unwrap();
+    test_game.play(&player_two, 0, 2).await.unwrap();
+
+    let moves = test_game.events::<MovePlayed>();
+    assert_eq!(moves.len(), 2);
+
+    assert_eq!(moves[0].player, player_one.pubkey());
+    assert_eq!((moves[0].row, moves[0].column), (1, 1));
+    assert_eq!(moves[0].sign, Sign::X);
+    assert_eq!(moves[0].turn, 1);
+
+    assert_eq!(moves[1].player, player_two.pubkey());
+    assert_eq!((moves[1].row, moves[1].column), (0, 2));
+    assert_eq!(moves[1].sign, Sign::O);
+    assert_eq!(moves[1].turn, 2);
+
+    assert!(test_game.events::<GameFinished>().is_empty());
+}
+
+#[tokio::test]
+async fn winning_move_emits_game_finished() {
+    let mut test_game = TestGame::new().await;
+    let player_one = test_game.player_one.insecure_clone();
+    let player_two = test_game.player_two.insecure_clone();
+
+    test_game.play_moves(&PLAYER_ONE_WINS).await;
+
+    let events = test_game.events::<GameFinished>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].game, test_game.game);
+    assert_eq!(
+        events[0].state,
+        GameState::Won {
+            winner: player_one.pubkey()
+        }
+    );
+    assert_eq!(events[0].winner, Some(player_one.pubkey()));
+
+    // A rejected move after the end leaves nothing new behind
+    assert!(test_game.play(&player_two, 2, 2).await.is_err());
+    assert_eq!(test_game.events::<MovePlayed>().len(), 5);
+}
+
+#[tokio::test]
+async fn tie_emits_game_finished_without_winner() {
+    let mut test_game = TestGame::new().await;
+
+    test_game.play_moves(&TIE).await;
+
+    let events = test_game.events::<GameFinished>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].state, GameState::Tie);
+    assert_eq!(events[0].winner, None);
+}
+
+#[tokio::test]
+async fn claim_timeout_emits_game_finished() {
+    let mut test_game = TestGame::new().await;
+    let player_two = test_game.player_two.insecure_clone();
+
+    // Player one never makes the first move
+    test_game.advance_clock(TURN_TIMEOUT).await;
+    test_game.claim_timeout(&player_two).await.unwrap();
+
+    let events = test_game.events::<GameFinished>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].winner, Some(player_two.pubkey()));
+}
+
+#[tokio::test]
+async fn cancel_game_emits_game_cancelled() {
+    let mut test_game = TestGame::with_wager(WAGER).await;
+    test_game.cancel_game().await.unwrap();
+
+    let events = test_game.events::<GameCancelled>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].game, test_game.game);
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_tic_tac_toe::{error::TicTacToeError, GameState};
+use common::{assert_error, TestGame, STARTING_LAMPORTS};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+const WAGER: u64 = LAMPORTS_PER_SOL;
+
+#[tokio::test]
+async fn open_game_waits_for_a_player() {
+    let mut test = TestGame::open(None, 0).await;
+
+    let game = test.game().await;
+    assert_eq!(game.players, [test.player_one.pubkey(), Pubkey::default()]);
+    assert!(game.has_open_seat());
+    assert!(!game.is_started());
+
+    let player_one = test.player_one.insecure_clone();
+    assert_error(
+        test.play(&player_one, 0, 0).await,
+        TicTacToeError::GameNotStarted,
+    );
+}
+
+#[tokio::test]
+async fn anyone_can_take_an_open_seat() {
+    let mut test = TestGame::open(None, 0).await;
+    let player_one = test.player_one.insecure_clone();
+    let player_two = test.player_two.insecure_clone();
+
+    test.join_game(&player_two).await.unwrap();
+
+    let game = test.game().await;
+    assert_eq!(game.players, [player_one.pubkey(), player_two.pubkey()]);
+    assert_eq!(game.turn, 1);
+
+    test.play_moves(&[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)])
+        .await;
+    assert_eq!(
+        test.game().await.state,
+        GameState::Won {
+            winner: player_one.pubkey()
+        }
+    );
+}
+
+#[tokio::test]
+async fn filled_seat_cannot_be_taken() {
+    let mut test = TestGame::open(None, 0).await;
+    let player_two = test.player_two.insecure_clone();
+    test.join_game(&player_two).await.unwrap();
+
+    assert_error(
+        test.join_game(&Keypair::new()).await,
+        TicTacToeError::GameAlreadyStarted,
+    );
+    assert_eq!(test.game().await.players[1], player_two.pubkey());
+}
+
+#[tokio::test]
+async fn host_cannot_join_their_own_game() {
+    let mut test = TestGame::open(None, 0).await;
+    let player_one = test.player_one.insecure_clone();
+
+    assert_error(
+        test.join_game(&player_one).await,
+        TicTacToeError::InvalidPlayer,
+    );
+}
+
+#[tokio::test]
+async fn invite_only_game_seats_the_invited_player() {
+    let mut test = TestGame::create(None).await;
+    let player_two = test.player_two.insecure_clone();
+    test.create_open_game(Some(player_two.pubkey()), 0)
+        .await
+        .unwrap();
+
+    assert_error(
+        test.join_game(&Keypair::new()).await,
+        TicTacToeError::InvalidPlayer,
+    );
+
+    test.join_game(&player_two).await.unwrap();
+    assert_eq!(test.game().await.players[1], player_two.pubkey());
+}
+
+#[tokio::test]
+async fn host_cannot_invite_themselves() {
+    let mut test = TestGame::create(None).await;
+    let player_one = test.player_one.pubkey();
+
+    assert_error(
+        test.create_open_game(Some(player_one), 0).await,
+        TicTacToeError::InvalidPlayer,
+    );
+}
+
+#[tokio::test]
+async fn host_can_have_several_open_games() {
+    let mut test = TestGame::open(None, 0).await;
+    let first = test.game;
+
+    test.create_open_game(None, 0).await.unwrap();
+
+    assert_ne!(test.game, first);
+    assert!(test.game().await.has_open_seat());
+}
+
+#[tokio::test]
+async fn wagered_open_game_pays_the_winner() {
+    let mut test = TestGame::open(None, WAGER).await;
+    let player_two = test.player_two.insecure_clone();
+    assert_eq!(test.lamports(test.vault).await, WAGER);
+
+    test.join_game(&player_two).await.unwrap();
+    assert_eq!(test.lamports(test.vault).await, 2 * WAGER);
+
+    // X X .
+    // O O O
+    // X . .
+    test.play_moves(&[(0, 0), (1, 0), (0, 1), (1, 1), (2, 0), (1, 2)])
+        .await;
+
+    assert_eq!(test.lamports(test.vault).await, 0);
+    assert_eq!(
+        test.lamports(player_two.pubkey()).await,
+        STARTING_LAMPORTS + WAGER
+    );
+}
+
+#[tokio::test]
+async fn host_can_cancel_an_untaken_game() {
+    let mut test = TestGame::open(None, 0).await;
+
+    test.cancel_game().await.unwrap();
+    assert!(test.account(test.game).await.is_none());
+}
+
+#[tokio::test]
+async fn rated_open_game_needs_the_joiners_profile() {
+    let mut test = TestGame::create(None).await;
+    let player_one = test.player_one.insecure_clone();
+    let player_two = test.player_two.insecure_clone();
+    test.create_profile(&player_one).await.unwrap();
+    test.rated = true;
+    test.create_open_game(None, 0).await.unwrap();
+    assert!(test.game().await.rated);
+
+    test.create_profile(&player_two).await.unwrap();
+
+    // Leave player two's profile out of the join
+    test.rated = false;
+    assert_error(
+        test.join_game(&player_two).await,
+        TicTacToeError::MissingProfiles,
+    );
+
+    test.rated = true;
+    test.join_game(&player_two).await.unwrap();
+
+    test.play_moves(&[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)])
+        .await;
+    assert_eq!(test.player_profile(player_one.pubkey()).await.wins, 1);
+    assert_eq!(test.player_profile(player_two.pubkey()).await.losses, 1);
+}
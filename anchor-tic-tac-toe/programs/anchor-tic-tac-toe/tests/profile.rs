@@ -0,0 +1,160 @@
+mod common;
+
+use anchor_tic_tac_toe::{error::TicTacToeError, PlayerProfile};
+use common::{assert_error, TestGame, TURN_TIMEOUT};
+use solana_sdk::signer::Signer;
+
+// X X X
+// O O .
+// . . .
+const PLAYER_ONE_WINS: [(u8, u8); 5] = [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)];
+
+#[tokio::test]
+async fn new_profiles_start_at_the_base_rating() {
+    let mut test = TestGame::create(None).await;
+    test.create_profiles().await;
+
+    let profile = test.player_profile(test.player_one.pubkey()).await;
+    assert_eq!(profile.player, test.player_one.pubkey());
+    assert_eq!(profile.rating, PlayerProfile::STARTING_RATING);
+    assert_eq!(profile.games_played, 0);
+
+    // One profile per wallet
+    let player_one = test.player_one.insecure_clone();
+    assert!(test.create_profile(&player_one).await.is_err());
+}
+
+#[tokio::test]
+async fn win_updates_both_profiles() {
+    let mut test = TestGame::rated().await;
+    assert!(test.game().await.rated);
+
+    test.play_moves(&PLAYER_ONE_WINS).await;
+
+    let winner = test.player_profile(test.player_one.pubkey()).await;
+    let loser = test.player_profile(test.player_two.pubkey()).await;
+
+    assert_eq!((winner.games_played, winner.wins, winner.losses), (1, 1, 0));
+    assert_eq!((loser.games_played, loser.wins, loser.losses), (1, 0, 1));
+    assert_eq!(winner.rating, PlayerProfile::STARTING_RATING + 16);
+    assert_eq!(loser.rating, PlayerProfile::STARTING_RATING - 16);
+}
+
+#[tokio::test]
+async fn tie_between_equals_keeps_ratings() {
+    let mut test = TestGame::rated().await;
+
+    test.play_moves(&[
+        (0, 0),
+        (0, 1),
+        (0, 2),
+        (1, 1),
+        (1, 0),
+        (1, 2),
+        (2, 1),
+        (2, 0),
+        (2, 2),
+    ])
+    .await;
+
+    for player in [test.player_one.pubkey(), test.player_two.pubkey()] {
+        let profile = test.player_profile(player).await;
+        assert_eq!((profile.games_played, profile.ties), (1, 1));
+        assert_eq!(profile.rating, PlayerProfile::STARTING_RATING);
+    }
+}
+
+#[tokio::test]
+async fn forfeit_counts_as_a_loss() {
+    let mut test = TestGame::rated().await;
+    let player_two = test.player_two.insecure_clone();
+
+    test.advance_clock(TURN_TIMEOUT).await;
+    test.claim_timeout(&player_two).await.unwrap();
+
+    let profile = test.player_profile(test.player_one.pubkey()).await;
+    assert_eq!(profile.losses, 1);
+    assert!(profile.rating < PlayerProfile::STARTING_RATING);
+}
+
+#[tokio::test]
+async fn final_move_needs_the_profiles() {
+    let mut test = TestGame::rated().await;
+    test.play_moves(&PLAYER_ONE_WINS[..4]).await;
+
+    // Leave the profiles out of the winning move
+    test.rated = false;
+    let player_one = test.player_one.insecure_clone();
+    let (row, column) = PLAYER_ONE_WINS[4];
+    assert_error(
+        test.play(&player_one, row, column).await,
+        TicTacToeError::MissingProfiles,
+    );
+}
+
+#[tokio::test]
+async fn rated_games_need_both_profiles() {
+    let mut test = TestGame::create(None).await;
+    let player_one = test.player_one.insecure_clone();
+    test.create_profile(&player_one).await.unwrap();
+    test.rated = true;
+
+    // Player two never made a profile
+    assert!(test.setup_game(0).await.is_err());
+}
+
+#[tokio::test]
+async fn rated_games_wait_for_player_two() {
+    let mut test = TestGame::create(None).await;
+    test.create_profiles().await;
+    test.rated = true;
+    test.setup_game(0).await.unwrap();
+
+    // Player one can't put player two's rating on the line without them
+    let player_one = test.player_one.insecure_clone();
+    assert_error(
+        test.play(&player_one, 1, 1).await,
+        TicTacToeError::GameNotStarted,
+    );
+
+    let player_two = test.player_two.insecure_clone();
+    test.join_game(&player_two).await.unwrap();
+    test.play_moves(&PLAYER_ONE_WINS).await;
+
+    let profile = test.player_profile(test.player_two.pubkey()).await;
+    assert_eq!(profile.losses, 1);
+}
+
+#[tokio::test]
+async fn unrated_games_leave_profiles_alone() {
+    let mut test = TestGame::create(None).await;
+    test.create_profiles().await;
+    test.setup_game(0).await.unwrap();
+
+    test.play_moves(&PLAYER_ONE_WINS).await;
+
+    let profile = test.player_profile(test.player_one.pubkey()).await;
+    assert_eq!(profile.games_played, 0);
+    assert_eq!(profile.rating, PlayerProfile::STARTING_RATING);
+}
+
+#[test]
+fn upsets_move_ratings_more() {
+    let favourite = 1400;
+    let underdog = 1200;
+
+    assert_eq!(PlayerProfile::expected_score(1200, 1200), 5000);
+    assert_eq!(PlayerProfile::expected_score(underdog, favourite), 2403);
+    assert_eq!(PlayerProfile::expected_score(favourite, underdog), 7597);
+
+    let expected_win = PlayerProfile::rating_change(favourite, underdog, PlayerProfile::WIN);
+    let upset = PlayerProfile::rating_change(underdog, favourite, PlayerProfile::WIN);
+    assert_eq!(expected_win, 8);
+    assert_eq!(upset, 24);
+
+    // Past 800 points the expected score stops moving
+    assert_eq!(
+        PlayerProfile::expected_score(1000, 3000),
+        PlayerProfile::expected_score(1000, 1800)
+    );
+}
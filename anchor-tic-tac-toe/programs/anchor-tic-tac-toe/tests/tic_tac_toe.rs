@@ -0,0 +1,190 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_tic_tac_toe::{error::TicTacToeError, GameState, Sign};
+use common::{assert_error, TestGame, STARTING_LAMPORTS};
+use solana_sdk::signer::Signer;
+
+// X X X
+// O O .
+// . . .
+const PLAYER_ONE_WINS: [(u8, u8); 5] = [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)];
+
+// X O X
+// X O O
+// O X X
+const TIE: [(u8, u8); 9] = [
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (1, 1),
+    (1, 0),
+    (1, 2),
+    (2, 1),
+    (2, 0),
+    (2, 2),
+];
+
+#[tokio::test]
+async fn setup_game() {
+    let mut test = TestGame::new().await;
+    let game = test.game().await;
+
+    assert_eq!(
+        game.players,
+        [test.player_one.pubkey(), test.player_two.pubkey()]
+    );
+    assert_eq!(game.turn, 1);
+    assert_eq!(game.state, GameState::Active);
+    assert_eq!(game.current_player(), test.player_one.pubkey());
+}
+
+#[tokio::test]
+async fn player_one_wins() {
+    let mut test = TestGame::new().await;
+
+    test.play_moves(&PLAYER_ONE_WINS).await;
+
+    let game = test.game().await;
+    assert_eq!(
+        game.state,
+        GameState::Won {
+            winner: test.player_one.pubkey()
+        }
+    );
+    assert_eq!(game.turn, 5);
+    assert!((0..3).all(|column| game.tile(0, column) == Some(Sign::X)));
+
+    let player_two = test.player_two.insecure_clone();
+    assert_error(
+        test.play(&player_two, 2, 2).await,
+        TicTacToeError::GameAlreadyOver,
+    );
+}
+
+#[tokio::test]
+async fn player_two_wins_on_diagonal() {
+    let mut test = TestGame::new().await;
+
+    // X X O
+    // X O .
+    // O . .
+    test.play_moves(&[(0, 0), (0, 2), (0, 1), (1, 1), (1, 0), (2, 0)])
+        .await;
+
+    let game = test.game().await;
+    assert_eq!(
+        game.state,
+        GameState::Won {
+            winner: test.player_two.pubkey()
+        }
+    );
+}
+
+#[tokio::test]
+async fn tie() {
+    let mut test = TestGame::new().await;
+
+    test.play_moves(&TIE).await;
+
+    let game = test.game().await;
+    assert_eq!(game.state, GameState::Tie);
+    assert!(game.board.iter().all(Option::is_some));
+}
+
+#[tokio::test]
+async fn illegal_moves() {
+    let mut test = TestGame::new().await;
+    let player_one = test.player_one.insecure_clone();
+    let player_two = test.player_two.insecure_clone();
+
+    assert_error(
+        test.play(&player_two, 1, 1).await,
+        TicTacToeError::NotPlayersTurn,
+    );
+    assert_error(
+        test.play(&player_one, 3, 0).await,
+        TicTacToeError::TileOutOfBounds,
+    );
+
+    test.play(&player_one, 1, 1).await.unwrap();
+
+    assert_error(
+        test.play(&player_one, 0, 0).await,
+        TicTacToeError::NotPlayersTurn,
+    );
+    assert_error(
+        test.play(&player_two, 1, 1).await,
+        TicTacToeError::TileAlreadySet,
+    );
+
+    // Rejected moves leave the board untouched
+    let game = test.game().await;
+    assert_eq!(game.turn, 2);
+    assert_eq!(game.tile(1, 1), Some(Sign::X));
+    assert_eq!(game.board.iter().flatten().count(), 1);
+}
+
+#[tokio::test]
+async fn setup_game_twice_fails() {
+    let mut test = TestGame::new().await;
+
+    assert!(test.setup_game(0).await.is_err());
+}
+
+#[tokio::test]
+async fn a_pair_plays_two_games_in_a_row() {
+    let mut test = TestGame::new().await;
+    let player_one = test.player_one.insecure_clone();
+    let player_two = test.player_two.insecure_clone();
+
+    // An unfinished game stays put
+    assert_error(
+        test.close_game(&player_one).await,
+        TicTacToeError::GameNotFinished,
+    );
+
+    test.play_moves(&PLAYER_ONE_WINS).await;
+    assert!(test.setup_game(0).await.is_err());
+
+    // The loser can't make the result disappear, the winner can close it
+    assert_error(
+        test.close_game(&player_two).await,
+        TicTacToeError::InvalidPlayer,
+    );
+    test.close_game(&player_one).await.unwrap();
+    assert!(test.account(test.game).await.is_none());
+    assert_eq!(test.lamports(player_one.pubkey()).await, STARTING_LAMPORTS);
+
+    test.setup_game(0).await.unwrap();
+    let game = test.game().await;
+    assert_eq!(game.turn, 1);
+    assert_eq!(game.state, GameState::Active);
+    test.play_moves(&PLAYER_ONE_WINS).await;
+}
+
+#[tokio::test]
+async fn either_player_closes_a_tie() {
+    let mut test = TestGame::new().await;
+    let player_two = test.player_two.insecure_clone();
+
+    test.play_moves(&TIE).await;
+    test.close_game(&player_two).await.unwrap();
+
+    assert!(test.account(test.game).await.is_none());
+}
+
+#[tokio::test]
+async fn player_two_must_be_someone_else() {
+    let mut test = TestGame::create(None).await;
+    let player_one = test.player_one.pubkey();
+
+    assert_error(
+        test.setup_game_against(player_one, 0).await,
+        TicTacToeError::InvalidPlayer,
+    );
+    assert_error(
+        test.setup_game_against(Pubkey::default(), 0).await,
+        TicTacToeError::InvalidPlayer,
+    );
+}
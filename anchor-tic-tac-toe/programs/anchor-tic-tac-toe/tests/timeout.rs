@@ -0,0 +1,157 @@
+mod common;
+
+use anchor_tic_tac_toe::{error::TicTacToeError, GameState};
+use common::{assert_error, TestGame, STARTING_LAMPORTS, TURN_TIMEOUT};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn moves_reset_the_clock() {
+    let mut test = TestGame::new().await;
+    let started_at = test.game().await.last_move_at;
+    assert_eq!(test.game().await.turn_timeout, TURN_TIMEOUT);
+
+    test.advance_clock(TURN_TIMEOUT - 1).await;
+    test.play_moves(&[(1, 1)]).await;
+
+    assert_eq!(
+        test.game().await.last_move_at,
+        started_at + TURN_TIMEOUT - 1
+    );
+}
+
+#[tokio::test]
+async fn waiting_player_wins_after_timeout() {
+    let mut test = TestGame::new().await;
+    let player_one = test.player_one.insecure_clone();
+    test.play_moves(&[(1, 1), (0, 0), (2, 2)]).await;
+
+    // Player two is on the clock, so player one is the one waiting
+    test.advance_clock(TURN_TIMEOUT).await;
+    test.claim_timeout(&player_one).await.unwrap();
+
+    let game = test.game().await;
+    assert_eq!(
+        game.state,
+        GameState::Won {
+            winner: test.player_one.pubkey()
+        }
+    );
+
+    let player_two = test.player_two.insecure_clone();
+    assert_error(
+        test.play(&player_two, 0, 0).await,
+        TicTacToeError::GameAlreadyOver,
+    );
+}
+
+#[tokio::test]
+async fn cannot_claim_before_timeout() {
+    let mut test = TestGame::new().await;
+    let player_two = test.player_two.insecure_clone();
+
+    test.advance_clock(TURN_TIMEOUT - 1).await;
+    assert_error(
+        test.claim_timeout(&player_two).await,
+        TicTacToeError::TurnNotExpired,
+    );
+
+    // A move resets the clock for the other player
+    test.advance_clock(1).await;
+    test.play_moves(&[(1, 1), (0, 0), (2, 2)]).await;
+    test.advance_clock(TURN_TIMEOUT - 1).await;
+
+    let player_one = test.player_one.insecure_clone();
+    assert_error(
+        test.claim_timeout(&player_one).await,
+        TicTacToeError::TurnNotExpired,
+    );
+}
+
+#[tokio::test]
+async fn cannot_claim_before_player_two_joins() {
+    let mut test = TestGame::new().await;
+    let player_one = test.player_one.insecure_clone();
+
+    // A friendly game starts on its own, player two has only been invited
+    test.play_moves(&[(1, 1)]).await;
+    test.advance_clock(TURN_TIMEOUT).await;
+    assert_error(
+        test.claim_timeout(&player_one).await,
+        TicTacToeError::PlayerNotJoined,
+    );
+}
+
+#[tokio::test]
+async fn player_one_cancels_a_friendly_game_player_two_never_joined() {
+    let mut test = TestGame::new().await;
+    test.play_moves(&[(1, 1)]).await;
+
+    // Player two still has time to take their seat
+    test.advance_clock(TURN_TIMEOUT - 1).await;
+    assert_error(test.cancel_game().await, TicTacToeError::GameAlreadyStarted);
+
+    test.advance_clock(1).await;
+    test.cancel_game().await.unwrap();
+    assert!(test.account(test.game).await.is_none());
+    assert_eq!(
+        test.lamports(test.player_one.pubkey()).await,
+        STARTING_LAMPORTS
+    );
+}
+
+#[tokio::test]
+async fn cannot_cancel_once_player_two_has_moved() {
+    let mut test = TestGame::new().await;
+    test.play_moves(&[(1, 1), (0, 0), (2, 2)]).await;
+    test.advance_clock(TURN_TIMEOUT).await;
+
+    assert_error(test.cancel_game().await, TicTacToeError::GameAlreadyStarted);
+}
+
+#[tokio::test]
+async fn only_the_waiting_player_can_claim() {
+    let mut test = TestGame::new().await;
+    let player_one = test.player_one.insecure_clone();
+    let stranger = Keypair::new();
+    test.advance_clock(TURN_TIMEOUT).await;
+
+    assert_error(
+        test.claim_timeout(&player_one).await,
+        TicTacToeError::InvalidPlayer,
+    );
+    assert_error(
+        test.claim_timeout(&stranger).await,
+        TicTacToeError::InvalidPlayer,
+    );
+}
+
+#[tokio::test]
+async fn timeouts_can_be_disabled() {
+    let mut test = TestGame::create(None).await;
+    let player_two = test.player_two.insecure_clone();
+    test.turn_timeout = 0;
+    test.setup_game(0).await.unwrap();
+
+    test.advance_clock(365 * 24 * 60 * 60).await;
+    assert_error(
+        test.claim_timeout(&player_two).await,
+        TicTacToeError::TurnNotExpired,
+    );
+}
+
+#[tokio::test]
+async fn forfeit_pays_out_the_pot() {
+    let wager = LAMPORTS_PER_SOL;
+    let mut test = TestGame::with_wager(wager).await;
+    let player_two = test.player_two.insecure_clone();
+    test.join_game(&player_two).await.unwrap();
+
+    test.advance_clock(TURN_TIMEOUT).await;
+    test.claim_timeout(&player_two).await.unwrap();
+
+    assert_eq!(test.lamports(test.vault).await, 0);
+    assert_eq!(
+        test.lamports(test.player_two.pubkey()).await,
+        STARTING_LAMPORTS + wager
+    );
+}
@@ -0,0 +1,267 @@
+mod common;
+
+use anchor_tic_tac_toe::{error::TicTacToeError, Game};
+use common::{assert_error, TestGame, STARTING_LAMPORTS, STARTING_TOKENS};
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair, signer::Signer,
+};
+
+const WAGER: u64 = LAMPORTS_PER_SOL;
+const TOKEN_WAGER: u64 = 250_000_000;
+
+// X X O
+// X O .
+// O . .
+const PLAYER_TWO_WINS: [(u8, u8); 6] = [(0, 0), (0, 2), (0, 1), (1, 1), (1, 0), (2, 0)];
+
+// X O X
+// X O O
+// O X X
+const TIE: [(u8, u8); 9] = [
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (1, 1),
+    (1, 0),
+    (1, 2),
+    (2, 1),
+    (2, 0),
+    (2, 2),
+];
+
+fn game_rent() -> u64 {
+    Rent::default().minimum_balance(8 + Game::maximum_size(3))
+}
+
+#[tokio::test]
+async fn wagered_game_waits_for_player_two() {
+    let mut test = TestGame::with_wager(WAGER).await;
+    let player_one = test.player_one.insecure_clone();
+
+    let game = test.game().await;
+    assert_eq!(game.turn, 0);
+    assert_eq!(game.wager, WAGER);
+    assert_eq!(game.stake_mint, None);
+    assert_eq!(test.lamports(test.vault).await, WAGER);
+
+    assert_error(
+        test.play(&player_one, 1, 1).await,
+        TicTacToeError::GameNotStarted,
+    );
+}
+
+#[tokio::test]
+async fn sol_wager_must_cover_vault_rent() {
+    let mut test = TestGame::create(None).await;
+
+    assert_error(test.setup_game(1).await, TicTacToeError::WagerTooSmall);
+}
+
+#[tokio::test]
+async fn only_player_two_can_join() {
+    let mut test = TestGame::with_wager(WAGER).await;
+    let stranger = Keypair::new();
+    let player_two = test.player_two.insecure_clone();
+
+    assert_error(
+        test.join_game(&stranger).await,
+        TicTacToeError::InvalidPlayer,
+    );
+
+    test.join_game(&player_two).await.unwrap();
+    assert_error(
+        test.join_game(&player_two).await,
+        TicTacToeError::GameAlreadyStarted,
+    );
+
+    assert_eq!(test.game().await.turn, 1);
+    assert_eq!(test.lamports(test.vault).await, 2 * WAGER);
+}
+
+#[tokio::test]
+async fn sol_winner_takes_the_pot() {
+    let mut test = TestGame::with_wager(WAGER).await;
+    let player_two = test.player_two.insecure_clone();
+    test.join_game(&player_two).await.unwrap();
+
+    test.play_moves(&PLAYER_TWO_WINS).await;
+
+    assert_eq!(test.lamports(test.vault).await, 0);
+    assert_eq!(
+        test.lamports(test.player_two.pubkey()).await,
+        STARTING_LAMPORTS + WAGER
+    );
+    assert_eq!(
+        test.lamports(test.player_one.pubkey()).await,
+        STARTING_LAMPORTS - WAGER - game_rent()
+    );
+}
+
+#[tokio::test]
+async fn sol_tie_splits_the_pot() {
+    let mut test = TestGame::with_wager(WAGER).await;
+    let player_two = test.player_two.insecure_clone();
+    test.join_game(&player_two).await.unwrap();
+
+    test.play_moves(&TIE).await;
+
+    assert_eq!(test.lamports(test.vault).await, 0);
+    assert_eq!(
+        test.lamports(test.player_one.pubkey()).await,
+        STARTING_LAMPORTS - game_rent()
+    );
+    assert_eq!(
+        test.lamports(test.player_two.pubkey()).await,
+        STARTING_LAMPORTS
+    );
+}
+
+#[tokio::test]
+async fn final_move_needs_the_pot_accounts() {
+    let mut test = TestGame::with_wager(WAGER).await;
+    let player_two = test.player_two.insecure_clone();
+    test.join_game(&player_two).await.unwrap();
+
+    test.play_moves(&PLAYER_TWO_WINS[..5]).await;
+
+    // Leave the pot accounts out of the winning move
+    test.wager = 0;
+    let (row, column) = PLAYER_TWO_WINS[5];
+    assert_error(
+        test.play(&player_two, row, column).await,
+        TicTacToeError::MissingPotAccounts,
+    );
+
+    test.wager = WAGER;
+    test.play(&player_two, row, column).await.unwrap();
+    assert_eq!(
+        test.lamports(test.player_two.pubkey()).await,
+        STARTING_LAMPORTS + WAGER
+    );
+}
+
+#[tokio::test]
+async fn creator_reclaims_stake_if_nobody_joins() {
+    let mut test = TestGame::with_wager(WAGER).await;
+
+    test.cancel_game().await.unwrap();
+
+    assert!(test.account(test.game).await.is_none());
+    assert_eq!(test.lamports(test.vault).await, 0);
+    assert_eq!(
+        test.lamports(test.player_one.pubkey()).await,
+        STARTING_LAMPORTS
+    );
+}
+
+#[tokio::test]
+async fn cannot_cancel_a_started_game() {
+    let mut test = TestGame::with_wager(WAGER).await;
+    let player_two = test.player_two.insecure_clone();
+    test.join_game(&player_two).await.unwrap();
+
+    assert_error(test.cancel_game().await, TicTacToeError::GameAlreadyStarted);
+}
+
+#[tokio::test]
+async fn token_winner_takes_the_pot() {
+    let mut test = TestGame::with_token_wager(TOKEN_WAGER).await;
+    let player_two = test.player_two.insecure_clone();
+
+    let game = test.game().await;
+    assert_eq!(game.stake_mint, test.stake_mint);
+    assert_eq!(test.token_balance(test.game).await, TOKEN_WAGER);
+
+    test.join_game(&player_two).await.unwrap();
+    assert_eq!(test.token_balance(test.game).await, 2 * TOKEN_WAGER);
+
+    test.play_moves(&PLAYER_TWO_WINS).await;
+
+    assert_eq!(
+        test.token_balance(test.player_one.pubkey()).await,
+        STARTING_TOKENS - TOKEN_WAGER
+    );
+    assert_eq!(
+        test.token_balance(test.player_two.pubkey()).await,
+        STARTING_TOKENS + TOKEN_WAGER
+    );
+
+    // The token vault is closed and its rent goes back to player one
+    let vault_token_account = test.token_account(test.game).unwrap();
+    assert!(test.account(vault_token_account).await.is_none());
+    assert_eq!(
+        test.lamports(test.player_one.pubkey()).await,
+        STARTING_LAMPORTS - game_rent()
+    );
+}
+
+#[tokio::test]
+async fn token_tie_splits_the_pot() {
+    let mut test = TestGame::with_token_wager(TOKEN_WAGER).await;
+    let player_two = test.player_two.insecure_clone();
+    test.join_game(&player_two).await.unwrap();
+
+    test.play_moves(&TIE).await;
+
+    assert_eq!(
+        test.token_balance(test.player_one.pubkey()).await,
+        STARTING_TOKENS
+    );
+    assert_eq!(
+        test.token_balance(test.player_two.pubkey()).await,
+        STARTING_TOKENS
+    );
+}
+
+#[tokio::test]
+async fn token_stake_is_refunded_on_cancel() {
+    let mut test = TestGame::with_token_wager(TOKEN_WAGER).await;
+
+    test.cancel_game().await.unwrap();
+
+    assert_eq!(
+        test.token_balance(test.player_one.pubkey()).await,
+        STARTING_TOKENS
+    );
+    assert_eq!(
+        test.lamports(test.player_one.pubkey()).await,
+        STARTING_LAMPORTS
+    );
+}
+
+#[tokio::test]
+async fn token_stake_needs_a_wager() {
+    let mut test = TestGame::create(Some(Pubkey::new_unique())).await;
+
+    assert_error(
+        test.setup_game(0).await,
+        TicTacToeError::StakeMintWithoutWager,
+    );
+    assert_error(
+        test.create_open_game(None, 0).await,
+        TicTacToeError::StakeMintWithoutWager,
+    );
+
+    // No token vault is left behind for player one to pay rent on
+    let vault_token_account = test.token_account(test.game).unwrap();
+    assert!(test.account(vault_token_account).await.is_none());
+}
+
+#[tokio::test]
+async fn transfer_fee_mints_cannot_be_staked() {
+    // The vault would receive less than the wager, and the pot couldn't pay out
+    let mut test = TestGame::with_transfer_fee_mint(100).await;
+
+    assert_error(
+        test.setup_game(TOKEN_WAGER).await,
+        TicTacToeError::TransferFeeMint,
+    );
+    assert_error(
+        test.create_open_game(None, TOKEN_WAGER).await,
+        TicTacToeError::TransferFeeMint,
+    );
+    assert_eq!(
+        test.token_balance(test.player_one.pubkey()).await,
+        STARTING_TOKENS
+    );
+}
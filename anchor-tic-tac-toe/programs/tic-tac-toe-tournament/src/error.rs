@@ -0,0 +1,24 @@
+use anchor_lang::error_code;
+
+#[error_code]
+pub enum TournamentError {
+    InvalidPlayerCount,
+    EntryFeeTooSmall,
+    InvalidTurnTimeout,
+    TournamentFull,
+    AlreadyRegistered,
+    TournamentNotStarted,
+    TournamentOver,
+    InvalidPairing,
+    NotHost,
+    PairingDecided,
+    GameInProgress,
+    GameNotOver,
+    NotChampion,
+    PrizeAlreadyClaimed,
+    InvalidRegistrationDeadline,
+    RegistrationClosed,
+    NotRegistered,
+    NoShowNotExpired,
+    GameNotClosed,
+}
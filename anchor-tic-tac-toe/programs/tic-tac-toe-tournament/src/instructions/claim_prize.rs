@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_tic_tac_toe::pot;
+
+use crate::{error::TournamentError, state::Tournament};
+
+// The champion takes the whole prize pool
+#[derive(Accounts)]
+pub struct ClaimPrize<'info> {
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.organizer.as_ref(), &tournament.id.to_le_bytes()],
+        bump = tournament.bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        mut,
+        seeds = [b"prize_pool", tournament.key().as_ref()],
+        bump = tournament.prize_pool_bump
+    )]
+    pub prize_pool: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub champion: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ClaimPrize>) -> Result<()> {
+    let tournament = &mut ctx.accounts.tournament;
+    require!(
+        tournament.champion == Some(ctx.accounts.champion.key()),
+        TournamentError::NotChampion
+    );
+    require!(
+        !tournament.prize_claimed,
+        TournamentError::PrizeAlreadyClaimed
+    );
+    tournament.prize_claimed = true;
+
+    let prize = ctx.accounts.prize_pool.lamports();
+    if prize == 0 {
+        return Ok(());
+    }
+
+    let tournament_key = tournament.key();
+    let prize_pool_seeds: &[&[&[u8]]] = &[&[
+        b"prize_pool",
+        tournament_key.as_ref(),
+        &[tournament.prize_pool_bump],
+    ]];
+
+    pot::withdraw_sol(
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.prize_pool.to_account_info(),
+        &ctx.accounts.champion.to_account_info(),
+        prize,
+        prize_pool_seeds,
+    )
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::TournamentError, state::Pairing};
+
+// Anyone can free a pairing up once its game is gone, e.g. after the host
+// cancelled it on anchor-tic-tac-toe, so the host can open another
+#[derive(Accounts)]
+pub struct ClearGame<'info> {
+    #[account(
+        mut,
+        seeds = [b"pairing", pairing.tournament.as_ref(), &[pairing.round], &[pairing.index]],
+        bump = pairing.bump,
+        constraint = pairing.game == Some(game.key()) @ TournamentError::InvalidPairing
+    )]
+    pub pairing: Account<'info, Pairing>,
+
+    /// CHECK: only checked to be closed
+    pub game: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<ClearGame>) -> Result<()> {
+    let game = &ctx.accounts.game;
+    require!(
+        game.data_is_empty() && game.lamports() == 0,
+        TournamentError::GameNotClosed
+    );
+
+    // Nothing was played, the next game takes the closed one's seed
+    ctx.accounts.pairing.game = None;
+    Ok(())
+}
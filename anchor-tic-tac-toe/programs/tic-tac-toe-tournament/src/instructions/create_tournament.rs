@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_tic_tac_toe::Game;
+
+use crate::{error::TournamentError, state::Tournament};
+
+#[derive(Accounts)]
+#[instruction(id: u64, max_players: u8)]
+pub struct CreateTournament<'info> {
+    #[account(
+        init,
+        payer = organizer,
+        // Oversized brackets are rejected by the handler with a proper error
+        space = 8 + Tournament::maximum_size(max_players.min(Tournament::MAX_PLAYERS)),
+        seeds = [b"tournament", organizer.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        seeds = [b"prize_pool", tournament.key().as_ref()],
+        bump
+    )]
+    pub prize_pool: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handler(
+    ctx: Context<CreateTournament>,
+    id: u64,
+    max_players: u8,
+    entry_fee: u64,
+    board_size: u8,
+    win_length: u8,
+    turn_timeout: i64,
+    registration_ends_at: i64,
+) -> Result<()> {
+    Tournament::validate_player_count(max_players)?;
+    Game::validate_board(board_size, win_length)?;
+    // A game nobody can claim a timeout on could hold up the whole bracket
+    require_gt!(turn_timeout, 0, TournamentError::InvalidTurnTimeout);
+    require!(
+        registration_ends_at == 0 || registration_ends_at > Clock::get()?.unix_timestamp,
+        TournamentError::InvalidRegistrationDeadline
+    );
+    // The first entry fee has to make the prize pool rent exempt
+    require!(
+        entry_fee == 0 || entry_fee >= Rent::get()?.minimum_balance(0),
+        TournamentError::EntryFeeTooSmall
+    );
+
+    let tournament = &mut ctx.accounts.tournament;
+    tournament.organizer = ctx.accounts.organizer.key();
+    tournament.id = id;
+    tournament.max_players = max_players;
+    tournament.entry_fee = entry_fee;
+    tournament.board_size = board_size;
+    tournament.win_length = win_length;
+    tournament.turn_timeout = turn_timeout;
+    tournament.registration_ends_at = registration_ends_at;
+    tournament.bump = ctx.bumps.tournament;
+    tournament.prize_pool_bump = ctx.bumps.prize_pool;
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_tic_tac_toe::Game;
+
+use crate::{
+    error::TournamentError,
+    state::{Pairing, Tournament},
+};
+
+// Anyone can knock out a player who doesn't show up, so one absent entrant
+// can't hold up the bracket. The host has turn_timeout from the start of the
+// round, or from a tie, to open the match, and the guest has as long again to
+// join it. Once both are playing, anchor-tic-tac-toe's claim_timeout takes over.
+#[derive(Accounts)]
+#[instruction(round: u8, index: u8)]
+pub struct ForfeitNoShow<'info> {
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.organizer.as_ref(), &tournament.id.to_le_bytes()],
+        bump = tournament.bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    // The host may never have opened the pairing
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Pairing::MAXIMUM_SIZE,
+        seeds = [b"pairing", tournament.key().as_ref(), &[round], &[index]],
+        bump
+    )]
+    pub pairing: Account<'info, Pairing>,
+
+    // Only needed when the pairing has a game open
+    #[account(constraint = pairing.game == Some(game.key()) @ TournamentError::InvalidPairing)]
+    pub game: Option<Account<'info, Game>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ForfeitNoShow>, round: u8, index: u8) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let tournament = &mut ctx.accounts.tournament;
+    let players = tournament.pairing(index)?;
+    require_eq!(round, tournament.round, TournamentError::InvalidPairing);
+
+    let pairing = &mut ctx.accounts.pairing;
+    pairing.init(tournament.key(), round, index, players, ctx.bumps.pairing);
+    require!(pairing.winner.is_none(), TournamentError::PairingDecided);
+
+    let (winner, waiting_since) = match (pairing.game, &ctx.accounts.game) {
+        // The host never opened the match
+        (None, _) => (
+            players[1],
+            pairing.waiting_since.max(tournament.round_started_at),
+        ),
+        // The guest never joined it
+        (Some(_), Some(game)) => {
+            require!(!game.is_started(), TournamentError::GameInProgress);
+            (players[0], pairing.waiting_since)
+        }
+        (Some(_), None) => return err!(TournamentError::InvalidPairing),
+    };
+
+    require!(
+        now.saturating_sub(waiting_since) >= tournament.turn_timeout,
+        TournamentError::NoShowNotExpired
+    );
+
+    pairing.winner = Some(winner);
+    tournament.record_winner(index, winner, now)
+}
@@ -0,0 +1,19 @@
+#![allow(ambiguous_glob_reexports)]
+
+pub mod claim_prize;
+pub mod clear_game;
+pub mod create_tournament;
+pub mod forfeit_no_show;
+pub mod record_result;
+pub mod register;
+pub mod start_match;
+pub mod unregister;
+
+pub use claim_prize::*;
+pub use clear_game::*;
+pub use create_tournament::*;
+pub use forfeit_no_show::*;
+pub use record_result::*;
+pub use register::*;
+pub use start_match::*;
+pub use unregister::*;
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use anchor_tic_tac_toe::{Game, GameState};
+
+use crate::{
+    error::TournamentError,
+    state::{Pairing, Tournament},
+};
+
+// Anyone can report a finished game: the winner moves on, a tie frees the
+// pairing up for a rematch.
+#[derive(Accounts)]
+pub struct RecordResult<'info> {
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.organizer.as_ref(), &tournament.id.to_le_bytes()],
+        bump = tournament.bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        mut,
+        seeds = [b"pairing", tournament.key().as_ref(), &[pairing.round], &[pairing.index]],
+        bump = pairing.bump,
+        constraint = pairing.game == Some(game.key()) @ TournamentError::InvalidPairing
+    )]
+    pub pairing: Account<'info, Pairing>,
+
+    pub game: Account<'info, Game>,
+}
+
+pub fn handler(ctx: Context<RecordResult>) -> Result<()> {
+    let pairing = &mut ctx.accounts.pairing;
+    require!(pairing.winner.is_none(), TournamentError::PairingDecided);
+
+    match ctx.accounts.game.state {
+        GameState::Active => err!(TournamentError::GameNotOver),
+        GameState::Tie => {
+            pairing.game = None;
+            pairing.games_played += 1;
+            // The host has as long to open the rematch as for any other match
+            pairing.waiting_since = Clock::get()?.unix_timestamp;
+            Ok(())
+        }
+        GameState::Won { winner } => {
+            pairing.winner = Some(winner);
+            pairing.games_played += 1;
+            ctx.accounts.tournament.record_winner(
+                pairing.index,
+                winner,
+                Clock::get()?.unix_timestamp,
+            )
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use anchor_tic_tac_toe::pot;
+
+use crate::state::Tournament;
+
+#[derive(Accounts)]
+pub struct Register<'info> {
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.organizer.as_ref(), &tournament.id.to_le_bytes()],
+        bump = tournament.bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        mut,
+        seeds = [b"prize_pool", tournament.key().as_ref()],
+        bump = tournament.prize_pool_bump
+    )]
+    pub prize_pool: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<Register>) -> Result<()> {
+    ctx.accounts
+        .tournament
+        .register(ctx.accounts.player.key(), Clock::get()?.unix_timestamp)?;
+
+    let entry_fee = ctx.accounts.tournament.entry_fee;
+    if entry_fee > 0 {
+        pot::deposit_sol(
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.player.to_account_info(),
+            &ctx.accounts.prize_pool.to_account_info(),
+            entry_fee,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, program::invoke},
+    InstructionData,
+};
+use anchor_tic_tac_toe::program::AnchorTicTacToe;
+
+use crate::{
+    error::TournamentError,
+    state::{Pairing, Tournament},
+};
+
+// Player one of a pairing hosts its game, inviting player two, who joins it
+// through anchor-tic-tac-toe. After a tie the host starts the rematch the same way.
+#[derive(Accounts)]
+#[instruction(round: u8, index: u8)]
+pub struct StartMatch<'info> {
+    #[account(
+        seeds = [b"tournament", tournament.organizer.as_ref(), &tournament.id.to_le_bytes()],
+        bump = tournament.bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        init_if_needed,
+        payer = host,
+        space = 8 + Pairing::MAXIMUM_SIZE,
+        seeds = [b"pairing", tournament.key().as_ref(), &[round], &[index]],
+        bump
+    )]
+    pub pairing: Account<'info, Pairing>,
+
+    #[account(mut)]
+    pub host: Signer<'info>,
+
+    /// CHECK: created by anchor-tic-tac-toe, which checks its seeds
+    #[account(mut)]
+    pub game: UncheckedAccount<'info>,
+    /// CHECK: the new game's SOL vault, checked by anchor-tic-tac-toe
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+
+    pub tic_tac_toe_program: Program<'info, AnchorTicTacToe>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<StartMatch>, round: u8, index: u8) -> Result<()> {
+    let tournament = &ctx.accounts.tournament;
+    let players = tournament.pairing(index)?;
+    require_eq!(round, tournament.round, TournamentError::InvalidPairing);
+    require_keys_eq!(
+        ctx.accounts.host.key(),
+        players[0],
+        TournamentError::NotHost
+    );
+
+    let pairing = &mut ctx.accounts.pairing;
+    pairing.init(tournament.key(), round, index, players, ctx.bumps.pairing);
+
+    require!(pairing.winner.is_none(), TournamentError::PairingDecided);
+    require!(pairing.game.is_none(), TournamentError::GameInProgress);
+
+    let seed = Pairing::game_seed(&pairing.key(), pairing.games_played);
+    pairing.game = Some(ctx.accounts.game.key());
+    // Starts the guest's clock to join
+    pairing.waiting_since = Clock::get()?.unix_timestamp;
+
+    // A friendly game, the stakes are in the prize pool
+    let ix = Instruction {
+        program_id: anchor_tic_tac_toe::ID,
+        accounts: anchor_tic_tac_toe::accounts::CreateOpenGame {
+            game: ctx.accounts.game.key(),
+            host: ctx.accounts.host.key(),
+            vault: ctx.accounts.vault.key(),
+            stake_mint: None,
+            host_token_account: None,
+            vault_token_account: None,
+            host_profile: None,
+            token_program: None,
+            associated_token_program: None,
+            system_program: ctx.accounts.system_program.key(),
+        }
+        .to_account_metas(None),
+        data: anchor_tic_tac_toe::instruction::CreateOpenGame {
+            seed,
+            wager: 0,
+            turn_timeout: tournament.turn_timeout,
+            board_size: tournament.board_size,
+            win_length: tournament.win_length,
+            invited: Some(players[1]),
+        }
+        .data(),
+    };
+
+    invoke(
+        &ix,
+        &[
+            ctx.accounts.game.to_account_info(),
+            ctx.accounts.host.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.tic_tac_toe_program.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )
+    .map_err(Into::into)
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use anchor_tic_tac_toe::pot;
+
+use crate::state::Tournament;
+
+// Takes a player out of a tournament that hasn't filled up yet and refunds
+// their entry fee
+#[derive(Accounts)]
+pub struct Unregister<'info> {
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.organizer.as_ref(), &tournament.id.to_le_bytes()],
+        bump = tournament.bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        mut,
+        seeds = [b"prize_pool", tournament.key().as_ref()],
+        bump = tournament.prize_pool_bump
+    )]
+    pub prize_pool: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<Unregister>) -> Result<()> {
+    let tournament = &mut ctx.accounts.tournament;
+    tournament.unregister(ctx.accounts.player.key())?;
+
+    if tournament.entry_fee == 0 {
+        return Ok(());
+    }
+
+    let tournament_key = tournament.key();
+    let prize_pool_seeds: &[&[&[u8]]] = &[&[
+        b"prize_pool",
+        tournament_key.as_ref(),
+        &[tournament.prize_pool_bump],
+    ]];
+
+    pot::withdraw_sol(
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.prize_pool.to_account_info(),
+        &ctx.accounts.player.to_account_info(),
+        tournament.entry_fee,
+        prize_pool_seeds,
+    )
+}
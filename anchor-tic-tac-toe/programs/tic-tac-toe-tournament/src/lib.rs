@@ -0,0 +1,68 @@
+pub mod error;
+pub mod instructions;
+pub mod state;
+
+use anchor_lang::prelude::*;
+
+pub use instructions::*;
+pub use state::*;
+
+declare_id!("G2RrDCiRzS1ywidrxgFdE6vGHPX7YZ2pTE2WDgsWuvYL");
+
+// Brackets are played out on anchor-tic-tac-toe games, this program only
+// registers players, opens each pairing's game and moves the winners along.
+#[program]
+pub mod tic_tac_toe_tournament {
+    use super::*;
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_tournament(
+        ctx: Context<CreateTournament>,
+        id: u64,
+        max_players: u8,
+        entry_fee: u64,
+        board_size: u8,
+        win_length: u8,
+        turn_timeout: i64,
+        registration_ends_at: i64,
+    ) -> Result<()> {
+        create_tournament::handler(
+            ctx,
+            id,
+            max_players,
+            entry_fee,
+            board_size,
+            win_length,
+            turn_timeout,
+            registration_ends_at,
+        )
+    }
+
+    pub fn register(ctx: Context<Register>) -> Result<()> {
+        register::handler(ctx)
+    }
+
+    pub fn unregister(ctx: Context<Unregister>) -> Result<()> {
+        unregister::handler(ctx)
+    }
+
+    pub fn start_match(ctx: Context<StartMatch>, round: u8, index: u8) -> Result<()> {
+        start_match::handler(ctx, round, index)
+    }
+
+    pub fn record_result(ctx: Context<RecordResult>) -> Result<()> {
+        record_result::handler(ctx)
+    }
+
+    pub fn forfeit_no_show(ctx: Context<ForfeitNoShow>, round: u8, index: u8) -> Result<()> {
+        forfeit_no_show::handler(ctx, round, index)
+    }
+
+    pub fn clear_game(ctx: Context<ClearGame>) -> Result<()> {
+        clear_game::handler(ctx)
+    }
+
+    pub fn claim_prize(ctx: Context<ClaimPrize>) -> Result<()> {
+        claim_prize::handler(ctx)
+    }
+}
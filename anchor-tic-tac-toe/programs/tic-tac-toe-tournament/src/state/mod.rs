@@ -0,0 +1,5 @@
+pub mod pairing;
+pub mod tournament;
+
+pub use pairing::*;
+pub use tournament::*;
@@ -0,0 +1,47 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+// Two entrants meeting in one round of a tournament
+#[account]
+pub struct Pairing {
+    pub tournament: Pubkey,     // 32
+    pub round: u8,              // 1
+    pub index: u8,              // 1
+    pub players: [Pubkey; 2],   // 32*2, player one hosts every game
+    pub game: Option<Pubkey>, // 1 + 32, None until the first game and between a tie and its rematch
+    pub games_played: u8,     // 1
+    pub winner: Option<Pubkey>, // 1 + 32
+    pub bump: u8,             // 1
+    pub waiting_since: i64,   // 8, when the current game was opened or the last tie recorded
+}
+
+impl Pairing {
+    pub const MAXIMUM_SIZE: usize = 32 + 1 + 1 + (32 * 2) + (1 + 32) + 1 + (1 + 32) + 1 + 8;
+
+    // Game PDAs are never reused, so every rematch needs a seed of its own
+    pub fn game_seed(pairing: &Pubkey, games_played: u8) -> Pubkey {
+        Pubkey::new_from_array(hashv(&[pairing.as_ref(), &[games_played]]).to_bytes())
+    }
+
+    pub fn is_new(&self) -> bool {
+        self.tournament == Pubkey::default()
+    }
+
+    // Fills in a pairing account the first time it's used
+    pub fn init(
+        &mut self,
+        tournament: Pubkey,
+        round: u8,
+        index: u8,
+        players: [Pubkey; 2],
+        bump: u8,
+    ) {
+        if !self.is_new() {
+            return;
+        }
+        self.tournament = tournament;
+        self.round = round;
+        self.index = index;
+        self.players = players;
+        self.bump = bump;
+    }
+}
@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+
+use crate::error::TournamentError;
+
+#[account]
+pub struct Tournament {
+    pub organizer: Pubkey,            // 32
+    pub id: u64,                      // 8, lets an organizer run several tournaments
+    pub max_players: u8,              // 1
+    pub entry_fee: u64,               // 8, lamports paid into the prize pool on registration
+    pub board_size: u8,               // 1
+    pub win_length: u8,               // 1
+    pub turn_timeout: i64,            // 8, also how long each side has to show up for a match
+    pub registration_ends_at: i64,    // 8, 0 keeps registration open until the bracket is full
+    pub round: u8,                    // 1
+    pub round_started_at: i64,        // 8
+    pub entrants: Vec<Pubkey>,        // 4 + 32 * max_players, everyone still in, in bracket order
+    pub winners: Vec<Option<Pubkey>>, // 4 + 33 * max_players / 2, one per pairing this round
+    pub champion: Option<Pubkey>,     // 1 + 32
+    pub prize_claimed: bool,          // 1
+    pub bump: u8,                     // 1
+    pub prize_pool_bump: u8,          // 1
+}
+
+impl Tournament {
+    // Keeps the bracket to four rounds
+    pub const MAX_PLAYERS: u8 = 16;
+
+    pub fn maximum_size(max_players: u8) -> usize {
+        let players = max_players as usize;
+        32 + 8
+            + 1
+            + 8
+            + 1
+            + 1
+            + 8
+            + 8
+            + 1
+            + 8
+            + (4 + players * 32)
+            + (4 + players / 2 * 33)
+            + (1 + 32)
+            + 1
+            + 1
+            + 1
+    }
+
+    // Every round has to pair everyone off, so the bracket is a power of two
+    pub fn validate_player_count(max_players: u8) -> Result<()> {
+        require!(
+            (2..=Self::MAX_PLAYERS).contains(&max_players) && max_players.is_power_of_two(),
+            TournamentError::InvalidPlayerCount
+        );
+        Ok(())
+    }
+
+    // The bracket is drawn as soon as the last seat is taken
+    pub fn is_started(&self) -> bool {
+        !self.winners.is_empty() || self.champion.is_some()
+    }
+
+    pub fn is_registration_open(&self, now: i64) -> bool {
+        self.registration_ends_at == 0 || now < self.registration_ends_at
+    }
+
+    pub fn register(&mut self, player: Pubkey, now: i64) -> Result<()> {
+        require!(!self.is_started(), TournamentError::TournamentFull);
+        require!(
+            self.is_registration_open(now),
+            TournamentError::RegistrationClosed
+        );
+        require!(
+            !self.entrants.contains(&player),
+            TournamentError::AlreadyRegistered
+        );
+
+        self.entrants.push(player);
+
+        if self.entrants.len() == self.max_players as usize {
+            self.winners = vec![None; self.entrants.len() / 2];
+            self.round_started_at = now;
+        }
+        Ok(())
+    }
+
+    // Anyone can drop out until the bracket is drawn, which is also how
+    // everyone gets their entry fee back from a tournament that never fills
+    pub fn unregister(&mut self, player: Pubkey) -> Result<()> {
+        require!(!self.is_started(), TournamentError::TournamentFull);
+
+        let position = self
+            .entrants
+            .iter()
+            .position(|entrant| *entrant == player)
+            .ok_or(TournamentError::NotRegistered)?;
+        self.entrants.remove(position);
+        Ok(())
+    }
+
+    // Pairing i of the current round puts entrants 2i and 2i + 1 against each other
+    pub fn pairing(&self, index: u8) -> Result<[Pubkey; 2]> {
+        require!(self.is_started(), TournamentError::TournamentNotStarted);
+        require!(self.champion.is_none(), TournamentError::TournamentOver);
+
+        let index = index as usize;
+        require!(index < self.winners.len(), TournamentError::InvalidPairing);
+
+        Ok([self.entrants[2 * index], self.entrants[2 * index + 1]])
+    }
+
+    // Moves on to the next round once every pairing in this one is decided
+    pub fn record_winner(&mut self, index: u8, winner: Pubkey, now: i64) -> Result<()> {
+        let slot = self
+            .winners
+            .get_mut(index as usize)
+            .ok_or(TournamentError::InvalidPairing)?;
+        require!(slot.is_none(), TournamentError::PairingDecided);
+        *slot = Some(winner);
+
+        if self.winners.iter().any(Option::is_none) {
+            return Ok(());
+        }
+
+        self.entrants = self.winners.iter().flatten().copied().collect();
+
+        if self.entrants.len() == 1 {
+            self.champion = Some(self.entrants[0]);
+            self.winners.clear();
+        } else {
+            self.round += 1;
+            self.round_started_at = now;
+            self.winners = vec![None; self.entrants.len() / 2];
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,532 @@
+#![allow(dead_code)]
+
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::entrypoint::ProgramResult,
+    AccountDeserialize, InstructionData, ToAccountMetas,
+};
+use anchor_tic_tac_toe::{Game, Tile};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+use tic_tac_toe_tournament::{error::TournamentError, Pairing, Tournament};
+
+pub const STARTING_LAMPORTS: u64 = 10 * LAMPORTS_PER_SOL;
+pub const TURN_TIMEOUT: i64 = 60;
+
+// X X X
+// O O .
+// . . .
+pub const HOST_WINS: [(u8, u8); 5] = [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)];
+
+// X X .
+// O O O
+// . . X
+pub const GUEST_WINS: [(u8, u8); 6] = [(0, 0), (1, 0), (0, 1), (1, 1), (2, 2), (1, 2)];
+
+// X O X
+// X O O
+// O X X
+pub const TIE: [(u8, u8); 9] = [
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (1, 1),
+    (1, 0),
+    (1, 2),
+    (2, 1),
+    (2, 0),
+    (2, 2),
+];
+
+// Anchor's entrypoint ties the account slice to the `AccountInfo` lifetime,
+// which `processor!` can't express, so leak the slice for the test run.
+fn tournament_entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    tic_tac_toe_tournament::entry(program_id, accounts, data)
+}
+
+fn tic_tac_toe_entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    anchor_tic_tac_toe::entry(program_id, accounts, data)
+}
+
+pub struct TestTournament {
+    pub context: ProgramTestContext,
+    pub organizer: Keypair,
+    pub players: Vec<Keypair>,
+    pub tournament: Pubkey,
+    pub prize_pool: Pubkey,
+    // 0 keeps registration open until the bracket is full
+    pub registration_ends_at: i64,
+}
+
+impl TestTournament {
+    // An open tournament nobody has registered for yet
+    pub async fn new(max_players: u8, entry_fee: u64) -> Self {
+        let mut test = Self::create(max_players).await;
+        test.create_tournament(max_players, entry_fee, 3, 3, TURN_TIMEOUT)
+            .await
+            .unwrap();
+        test
+    }
+
+    // A tournament with every seat taken, so the first round is drawn
+    pub async fn full(max_players: u8, entry_fee: u64) -> Self {
+        let mut test = Self::new(max_players, entry_fee).await;
+        for player in test
+            .players
+            .iter()
+            .map(|p| p.insecure_clone())
+            .collect::<Vec<_>>()
+        {
+            test.register(&player).await.unwrap();
+        }
+        test
+    }
+
+    // Funds the organizer and max_players players
+    pub async fn create(max_players: u8) -> Self {
+        let mut program_test = ProgramTest::new(
+            "tic_tac_toe_tournament",
+            tic_tac_toe_tournament::ID,
+            processor!(tournament_entry),
+        );
+        program_test.add_program(
+            "anchor_tic_tac_toe",
+            anchor_tic_tac_toe::ID,
+            processor!(tic_tac_toe_entry),
+        );
+
+        let organizer = Keypair::new();
+        let players: Vec<Keypair> = (0..max_players).map(|_| Keypair::new()).collect();
+
+        for wallet in std::iter::once(&organizer).chain(&players) {
+            program_test.add_account(
+                wallet.pubkey(),
+                Account::new(STARTING_LAMPORTS, 0, &system_program::ID),
+            );
+        }
+
+        let context = program_test.start_with_context().await;
+
+        let (tournament, _) = Pubkey::find_program_address(
+            &[
+                b"tournament",
+                organizer.pubkey().as_ref(),
+                &0u64.to_le_bytes(),
+            ],
+            &tic_tac_toe_tournament::ID,
+        );
+        let (prize_pool, _) = Pubkey::find_program_address(
+            &[b"prize_pool", tournament.as_ref()],
+            &tic_tac_toe_tournament::ID,
+        );
+
+        Self {
+            context,
+            organizer,
+            players,
+            tournament,
+            prize_pool,
+            registration_ends_at: 0,
+        }
+    }
+
+    pub async fn create_tournament(
+        &mut self,
+        max_players: u8,
+        entry_fee: u64,
+        board_size: u8,
+        win_length: u8,
+        turn_timeout: i64,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: tic_tac_toe_tournament::ID,
+            accounts: tic_tac_toe_tournament::accounts::CreateTournament {
+                tournament: self.tournament,
+                prize_pool: self.prize_pool,
+                organizer: self.organizer.pubkey(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: tic_tac_toe_tournament::instruction::CreateTournament {
+                id: 0,
+                max_players,
+                entry_fee,
+                board_size,
+                win_length,
+                turn_timeout,
+                registration_ends_at: self.registration_ends_at,
+            }
+            .data(),
+        };
+
+        let organizer = self.organizer.insecure_clone();
+        self.send(ix, &organizer).await
+    }
+
+    pub async fn register(&mut self, player: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: tic_tac_toe_tournament::ID,
+            accounts: tic_tac_toe_tournament::accounts::Register {
+                tournament: self.tournament,
+                prize_pool: self.prize_pool,
+                player: player.pubkey(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: tic_tac_toe_tournament::instruction::Register {}.data(),
+        };
+
+        self.send(ix, player).await
+    }
+
+    pub async fn unregister(&mut self, player: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: tic_tac_toe_tournament::ID,
+            accounts: tic_tac_toe_tournament::accounts::Unregister {
+                tournament: self.tournament,
+                prize_pool: self.prize_pool,
+                player: player.pubkey(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: tic_tac_toe_tournament::instruction::Unregister {}.data(),
+        };
+
+        self.send(ix, player).await
+    }
+
+    // Opens the pairing's next game, hosted by `host`
+    pub async fn start_match(
+        &mut self,
+        host: &Keypair,
+        round: u8,
+        index: u8,
+    ) -> Result<Pubkey, BanksClientError> {
+        let pairing = self.pairing_address(round, index);
+        let games_played = match self.account(pairing).await {
+            Some(_) => self.pairing(round, index).await.games_played,
+            None => 0,
+        };
+        let seed = Pairing::game_seed(&pairing, games_played);
+        let game = game_address(host.pubkey(), seed);
+
+        let ix = Instruction {
+            program_id: tic_tac_toe_tournament::ID,
+            accounts: tic_tac_toe_tournament::accounts::StartMatch {
+                tournament: self.tournament,
+                pairing,
+                host: host.pubkey(),
+                game,
+                vault: vault_address(game),
+                tic_tac_toe_program: anchor_tic_tac_toe::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: tic_tac_toe_tournament::instruction::StartMatch { round, index }.data(),
+        };
+
+        self.send(ix, host).await?;
+        Ok(game)
+    }
+
+    pub async fn join_game(
+        &mut self,
+        game: Pubkey,
+        player: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_tic_tac_toe::ID,
+            accounts: anchor_tic_tac_toe::accounts::JoinGame {
+                game,
+                player_two: player.pubkey(),
+                vault: vault_address(game),
+                stake_mint: None,
+                player_two_token_account: None,
+                vault_token_account: None,
+                player_two_profile: None,
+                token_program: None,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_tic_tac_toe::instruction::JoinGame {}.data(),
+        };
+
+        self.send(ix, player).await
+    }
+
+    pub async fn play(
+        &mut self,
+        game: Pubkey,
+        player: &Keypair,
+        row: u8,
+        column: u8,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_tic_tac_toe::ID,
+            accounts: anchor_tic_tac_toe::accounts::Play {
+                game,
+                player: player.pubkey(),
+                player_one: None,
+                player_two: None,
+                vault: None,
+                stake_mint: None,
+                player_one_token_account: None,
+                player_two_token_account: None,
+                vault_token_account: None,
+                player_one_profile: None,
+                player_two_profile: None,
+                token_program: None,
+                system_program: None,
+            }
+            .to_account_metas(None),
+            data: anchor_tic_tac_toe::instruction::Play {
+                tile: Tile { row, column },
+            }
+            .data(),
+        };
+
+        self.send(ix, player).await
+    }
+
+    pub async fn record_result(
+        &mut self,
+        round: u8,
+        index: u8,
+        game: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: tic_tac_toe_tournament::ID,
+            accounts: tic_tac_toe_tournament::accounts::RecordResult {
+                tournament: self.tournament,
+                pairing: self.pairing_address(round, index),
+                game,
+            }
+            .to_account_metas(None),
+            data: tic_tac_toe_tournament::instruction::RecordResult {}.data(),
+        };
+
+        // Anyone can report a result, the fee payer is enough
+        let payer = self.context.payer.insecure_clone();
+        self.send(ix, &payer).await
+    }
+
+    // Cranked by the fee payer, `game` is the pairing's open game if it has one
+    pub async fn forfeit_no_show(
+        &mut self,
+        round: u8,
+        index: u8,
+        game: Option<Pubkey>,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: tic_tac_toe_tournament::ID,
+            accounts: tic_tac_toe_tournament::accounts::ForfeitNoShow {
+                tournament: self.tournament,
+                pairing: self.pairing_address(round, index),
+                game,
+                payer: self.context.payer.pubkey(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: tic_tac_toe_tournament::instruction::ForfeitNoShow { round, index }.data(),
+        };
+
+        let payer = self.context.payer.insecure_clone();
+        self.send(ix, &payer).await
+    }
+
+    pub async fn clear_game(
+        &mut self,
+        round: u8,
+        index: u8,
+        game: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: tic_tac_toe_tournament::ID,
+            accounts: tic_tac_toe_tournament::accounts::ClearGame {
+                pairing: self.pairing_address(round, index),
+                game,
+            }
+            .to_account_metas(None),
+            data: tic_tac_toe_tournament::instruction::ClearGame {}.data(),
+        };
+
+        let payer = self.context.payer.insecure_clone();
+        self.send(ix, &payer).await
+    }
+
+    // The host closes a game nobody joined through anchor-tic-tac-toe
+    pub async fn cancel_game(
+        &mut self,
+        game: Pubkey,
+        host: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: anchor_tic_tac_toe::ID,
+            accounts: anchor_tic_tac_toe::accounts::CancelGame {
+                game,
+                player_one: host.pubkey(),
+                vault: vault_address(game),
+                stake_mint: None,
+                player_one_token_account: None,
+                vault_token_account: None,
+                token_program: None,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_tic_tac_toe::instruction::CancelGame {}.data(),
+        };
+
+        self.send(ix, host).await
+    }
+
+    pub async fn claim_prize(&mut self, player: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: tic_tac_toe_tournament::ID,
+            accounts: tic_tac_toe_tournament::accounts::ClaimPrize {
+                tournament: self.tournament,
+                prize_pool: self.prize_pool,
+                champion: player.pubkey(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: tic_tac_toe_tournament::instruction::ClaimPrize {}.data(),
+        };
+
+        self.send(ix, player).await
+    }
+
+    // Starts, joins and plays out one game of a pairing, returning the game.
+    // Moves alternate between the host and their opponent.
+    pub async fn play_game(&mut self, round: u8, index: u8, moves: &[(u8, u8)]) -> Pubkey {
+        let [host, guest] = self.pairing_players(index).await;
+
+        let game = self.start_match(&host, round, index).await.unwrap();
+        self.join_game(game, &guest).await.unwrap();
+
+        for (i, (row, column)) in moves.iter().enumerate() {
+            let player = if i % 2 == 0 { &host } else { &guest };
+            self.play(game, player, *row, *column).await.unwrap();
+        }
+        game
+    }
+
+    // Plays a pairing to a result and reports it
+    pub async fn decide(&mut self, round: u8, index: u8, moves: &[(u8, u8)]) {
+        let game = self.play_game(round, index, moves).await;
+        self.record_result(round, index, game).await.unwrap();
+    }
+
+    // The keypairs of the entrants a pairing of the current round puts together
+    pub async fn pairing_players(&mut self, index: u8) -> [Keypair; 2] {
+        let entrants = self.tournament().await.entrants;
+        [0, 1].map(|seat| self.keypair(entrants[2 * index as usize + seat]))
+    }
+
+    pub fn keypair(&self, player: Pubkey) -> Keypair {
+        self.players
+            .iter()
+            .find(|keypair| keypair.pubkey() == player)
+            .unwrap()
+            .insecure_clone()
+    }
+
+    pub async fn send(
+        &mut self,
+        ix: Instruction,
+        signer: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        // Refresh the blockhash so identical instructions don't dedupe as the same transaction
+        let recent_blockhash = self.context.get_new_latest_blockhash().await?;
+
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&self.context.payer.pubkey()),
+            &[&self.context.payer, signer],
+            recent_blockhash,
+        );
+        self.context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn advance_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp
+    }
+
+    pub async fn tournament(&mut self) -> Tournament {
+        let account = self.account(self.tournament).await.unwrap();
+        Tournament::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn pairing(&mut self, round: u8, index: u8) -> Pairing {
+        let account = self
+            .account(self.pairing_address(round, index))
+            .await
+            .unwrap();
+        Pairing::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn game(&mut self, game: Pubkey) -> Game {
+        let account = self.account(game).await.unwrap();
+        Game::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn account(&mut self, address: Pubkey) -> Option<Account> {
+        self.context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.account(address)
+            .await
+            .map(|account| account.lamports)
+            .unwrap_or(0)
+    }
+
+    pub fn pairing_address(&self, round: u8, index: u8) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"pairing", self.tournament.as_ref(), &[round], &[index]],
+            &tic_tac_toe_tournament::ID,
+        )
+        .0
+    }
+}
+
+pub fn game_address(host: Pubkey, seed: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"game", host.as_ref(), seed.as_ref()],
+        &anchor_tic_tac_toe::ID,
+    )
+    .0
+}
+
+pub fn vault_address(game: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", game.as_ref()], &anchor_tic_tac_toe::ID).0
+}
+
+pub fn assert_error(result: Result<(), BanksClientError>, error: TournamentError) {
+    let err = result.unwrap_err().unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(0, InstructionError::Custom(error.into()))
+    );
+}
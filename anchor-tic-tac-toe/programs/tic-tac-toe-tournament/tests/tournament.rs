@@ -0,0 +1,390 @@
+mod common;
+
+use anchor_tic_tac_toe::GameState;
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+use tic_tac_toe_tournament::error::TournamentError;
+
+const ENTRY_FEE: u64 = LAMPORTS_PER_SOL / 10;
+
+#[tokio::test]
+async fn registration_draws_the_bracket_once_full() {
+    let mut test = TestTournament::new(4, ENTRY_FEE).await;
+    let players: Vec<Keypair> = test.players.iter().map(|p| p.insecure_clone()).collect();
+
+    for player in &players[..3] {
+        test.register(player).await.unwrap();
+    }
+    let tournament = test.tournament().await;
+    assert_eq!(tournament.entrants.len(), 3);
+    assert!(!tournament.is_started());
+
+    test.register(&players[3]).await.unwrap();
+    let tournament = test.tournament().await;
+    assert_eq!(
+        tournament.entrants,
+        players.iter().map(|p| p.pubkey()).collect::<Vec<_>>()
+    );
+    assert_eq!(tournament.winners, vec![None, None]);
+    assert_eq!(test.lamports(test.prize_pool).await, 4 * ENTRY_FEE);
+    assert_eq!(
+        test.lamports(players[0].pubkey()).await,
+        STARTING_LAMPORTS - ENTRY_FEE
+    );
+}
+
+#[tokio::test]
+async fn rejects_invalid_tournaments() {
+    let mut test = TestTournament::create(4).await;
+
+    for max_players in [0, 1, 3, 6, 32] {
+        assert_error(
+            test.create_tournament(max_players, 0, 3, 3, TURN_TIMEOUT)
+                .await,
+            TournamentError::InvalidPlayerCount,
+        );
+    }
+    assert_error(
+        test.create_tournament(4, 1, 3, 3, TURN_TIMEOUT).await,
+        TournamentError::EntryFeeTooSmall,
+    );
+    assert_error(
+        test.create_tournament(4, 0, 3, 3, 0).await,
+        TournamentError::InvalidTurnTimeout,
+    );
+    // Board rules are anchor-tic-tac-toe's
+    assert!(test
+        .create_tournament(4, 0, 3, 4, TURN_TIMEOUT)
+        .await
+        .is_err());
+
+    test.create_tournament(4, 0, 3, 3, TURN_TIMEOUT)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn registration_is_once_per_player_until_full() {
+    let mut test = TestTournament::new(2, 0).await;
+    let player = test.players[0].insecure_clone();
+
+    test.register(&player).await.unwrap();
+    assert_error(
+        test.register(&player).await,
+        TournamentError::AlreadyRegistered,
+    );
+
+    let player = test.players[1].insecure_clone();
+    test.register(&player).await.unwrap();
+    assert_error(
+        test.register(&Keypair::new()).await,
+        TournamentError::TournamentFull,
+    );
+}
+
+#[tokio::test]
+async fn pairing_games_follow_the_tournament_settings() {
+    let mut test = TestTournament::create(2).await;
+    test.create_tournament(2, 0, 5, 4, TURN_TIMEOUT)
+        .await
+        .unwrap();
+    for player in test
+        .players
+        .iter()
+        .map(|p| p.insecure_clone())
+        .collect::<Vec<_>>()
+    {
+        test.register(&player).await.unwrap();
+    }
+    let [host, guest] = test.pairing_players(0).await;
+
+    let game = test.start_match(&host, 0, 0).await.unwrap();
+
+    let game = test.game(game).await;
+    assert_eq!(game.players[0], host.pubkey());
+    assert_eq!(game.invited, Some(guest.pubkey()));
+    assert_eq!((game.board_size, game.win_length), (5, 4));
+    assert_eq!(game.turn_timeout, TURN_TIMEOUT);
+    assert_eq!(game.wager, 0);
+}
+
+#[tokio::test]
+async fn only_the_host_starts_a_match() {
+    let mut test = TestTournament::full(2, 0).await;
+    let [_, guest] = test.pairing_players(0).await;
+
+    assert_error(
+        test.start_match(&guest, 0, 0).await.map(|_| ()),
+        TournamentError::NotHost,
+    );
+}
+
+#[tokio::test]
+async fn bracket_needs_a_full_field() {
+    let mut test = TestTournament::new(2, 0).await;
+    let player = test.players[0].insecure_clone();
+    test.register(&player).await.unwrap();
+
+    assert_error(
+        test.start_match(&player, 0, 0).await.map(|_| ()),
+        TournamentError::TournamentNotStarted,
+    );
+}
+
+#[tokio::test]
+async fn one_game_at_a_time_per_pairing() {
+    let mut test = TestTournament::full(2, 0).await;
+    let [host, _] = test.pairing_players(0).await;
+
+    test.start_match(&host, 0, 0).await.unwrap();
+    assert_error(
+        test.start_match(&host, 0, 0).await.map(|_| ()),
+        TournamentError::GameInProgress,
+    );
+}
+
+#[tokio::test]
+async fn unfinished_games_cannot_be_recorded() {
+    let mut test = TestTournament::full(2, 0).await;
+    let game = test.play_game(0, 0, &HOST_WINS[..3]).await;
+
+    assert_error(
+        test.record_result(0, 0, game).await,
+        TournamentError::GameNotOver,
+    );
+}
+
+#[tokio::test]
+async fn tie_leads_to_a_rematch() {
+    let mut test = TestTournament::full(2, 0).await;
+
+    let first = test.play_game(0, 0, &TIE).await;
+    assert_eq!(test.game(first).await.state, GameState::Tie);
+    test.record_result(0, 0, first).await.unwrap();
+
+    let pairing = test.pairing(0, 0).await;
+    assert_eq!(pairing.game, None);
+    assert_eq!(pairing.games_played, 1);
+    assert_eq!(test.tournament().await.winners, vec![None]);
+
+    // The tie can't be counted twice
+    assert_error(
+        test.record_result(0, 0, first).await,
+        TournamentError::InvalidPairing,
+    );
+
+    let rematch = test.play_game(0, 0, &GUEST_WINS).await;
+    assert_ne!(rematch, first);
+    test.record_result(0, 0, rematch).await.unwrap();
+
+    let [_, guest] = test.pairing(0, 0).await.players;
+    assert_eq!(test.tournament().await.champion, Some(guest));
+}
+
+#[tokio::test]
+async fn results_are_recorded_once() {
+    let mut test = TestTournament::full(4, 0).await;
+    let game = test.play_game(0, 0, &HOST_WINS).await;
+
+    test.record_result(0, 0, game).await.unwrap();
+    assert_error(
+        test.record_result(0, 0, game).await,
+        TournamentError::PairingDecided,
+    );
+}
+
+#[tokio::test]
+async fn champion_takes_the_prize_pool() {
+    let mut test = TestTournament::full(4, ENTRY_FEE).await;
+    let players: Vec<_> = test.players.iter().map(|p| p.pubkey()).collect();
+
+    test.decide(0, 0, &HOST_WINS).await;
+    let tournament = test.tournament().await;
+    assert_eq!(tournament.round, 0);
+    assert_eq!(tournament.winners, vec![Some(players[0]), None]);
+
+    test.decide(0, 1, &GUEST_WINS).await;
+    let tournament = test.tournament().await;
+    assert_eq!(tournament.round, 1);
+    assert_eq!(tournament.entrants, vec![players[0], players[3]]);
+
+    test.decide(1, 0, &GUEST_WINS).await;
+    let tournament = test.tournament().await;
+    assert_eq!(tournament.champion, Some(players[3]));
+
+    let loser = test.keypair(players[0]);
+    assert_error(test.claim_prize(&loser).await, TournamentError::NotChampion);
+
+    let champion = test.keypair(players[3]);
+    let before = test.lamports(players[3]).await;
+    test.claim_prize(&champion).await.unwrap();
+    assert_eq!(test.lamports(players[3]).await, before + 4 * ENTRY_FEE);
+    assert_eq!(test.lamports(test.prize_pool).await, 0);
+
+    assert_error(
+        test.claim_prize(&champion).await,
+        TournamentError::PrizeAlreadyClaimed,
+    );
+}
+
+#[tokio::test]
+async fn finished_tournament_opens_no_more_matches() {
+    let mut test = TestTournament::full(2, 0).await;
+    let [host, _] = test.pairing_players(0).await;
+    test.decide(0, 0, &HOST_WINS).await;
+
+    assert_error(
+        test.start_match(&host, 1, 0).await.map(|_| ()),
+        TournamentError::TournamentOver,
+    );
+}
+
+#[tokio::test]
+async fn entrants_can_leave_until_the_bracket_is_drawn() {
+    let mut test = TestTournament::new(4, ENTRY_FEE).await;
+    let players: Vec<Keypair> = test.players.iter().map(|p| p.insecure_clone()).collect();
+    for player in &players[..2] {
+        test.register(player).await.unwrap();
+    }
+
+    test.unregister(&players[0]).await.unwrap();
+    assert_eq!(test.tournament().await.entrants, vec![players[1].pubkey()]);
+    assert_eq!(test.lamports(players[0].pubkey()).await, STARTING_LAMPORTS);
+    assert_eq!(test.lamports(test.prize_pool).await, ENTRY_FEE);
+
+    assert_error(
+        test.unregister(&players[0]).await,
+        TournamentError::NotRegistered,
+    );
+
+    // Once full, everyone is in for the bracket
+    for player in [&players[0], &players[2], &players[3]] {
+        test.register(player).await.unwrap();
+    }
+    assert_error(
+        test.unregister(&players[0]).await,
+        TournamentError::TournamentFull,
+    );
+}
+
+#[tokio::test]
+async fn expired_registration_refunds_everyone() {
+    let mut test = TestTournament::create(4).await;
+    test.registration_ends_at = test.now().await + TURN_TIMEOUT;
+    test.create_tournament(4, ENTRY_FEE, 3, 3, TURN_TIMEOUT)
+        .await
+        .unwrap();
+    let players: Vec<Keypair> = test.players.iter().map(|p| p.insecure_clone()).collect();
+    for player in &players[..3] {
+        test.register(player).await.unwrap();
+    }
+
+    test.advance_clock(TURN_TIMEOUT).await;
+    assert_error(
+        test.register(&players[3]).await,
+        TournamentError::RegistrationClosed,
+    );
+
+    for player in &players[..3] {
+        test.unregister(player).await.unwrap();
+        assert_eq!(test.lamports(player.pubkey()).await, STARTING_LAMPORTS);
+    }
+    assert_eq!(test.lamports(test.prize_pool).await, 0);
+}
+
+#[tokio::test]
+async fn registration_deadline_must_be_in_the_future() {
+    let mut test = TestTournament::create(4).await;
+    test.registration_ends_at = test.now().await;
+
+    assert_error(
+        test.create_tournament(4, 0, 3, 3, TURN_TIMEOUT).await,
+        TournamentError::InvalidRegistrationDeadline,
+    );
+}
+
+#[tokio::test]
+async fn host_who_never_opens_the_match_forfeits() {
+    let mut test = TestTournament::full(4, 0).await;
+    let [_, guest] = test.pairing_players(0).await;
+
+    test.advance_clock(TURN_TIMEOUT - 1).await;
+    assert_error(
+        test.forfeit_no_show(0, 0, None).await,
+        TournamentError::NoShowNotExpired,
+    );
+
+    test.advance_clock(1).await;
+    test.forfeit_no_show(0, 0, None).await.unwrap();
+
+    assert_eq!(test.pairing(0, 0).await.winner, Some(guest.pubkey()));
+    assert_eq!(
+        test.tournament().await.winners,
+        vec![Some(guest.pubkey()), None]
+    );
+    assert_error(
+        test.forfeit_no_show(0, 0, None).await,
+        TournamentError::PairingDecided,
+    );
+}
+
+#[tokio::test]
+async fn guest_who_never_joins_forfeits() {
+    let mut test = TestTournament::full(2, 0).await;
+    let [host, guest] = test.pairing_players(0).await;
+
+    // Opening late still gives the guest the whole window to join
+    test.advance_clock(TURN_TIMEOUT - 1).await;
+    let game = test.start_match(&host, 0, 0).await.unwrap();
+    test.advance_clock(TURN_TIMEOUT - 1).await;
+    assert_error(
+        test.forfeit_no_show(0, 0, Some(game)).await,
+        TournamentError::NoShowNotExpired,
+    );
+
+    test.advance_clock(1).await;
+    test.forfeit_no_show(0, 0, Some(game)).await.unwrap();
+    assert_eq!(test.tournament().await.champion, Some(host.pubkey()));
+
+    // Joining late changes nothing
+    test.join_game(game, &guest).await.unwrap();
+    assert_error(
+        test.forfeit_no_show(0, 0, Some(game)).await,
+        TournamentError::TournamentOver,
+    );
+}
+
+#[tokio::test]
+async fn games_in_progress_are_left_to_claim_timeout() {
+    let mut test = TestTournament::full(2, 0).await;
+    let game = test.play_game(0, 0, &HOST_WINS[..1]).await;
+
+    test.advance_clock(TURN_TIMEOUT).await;
+    assert_error(
+        test.forfeit_no_show(0, 0, Some(game)).await,
+        TournamentError::GameInProgress,
+    );
+    assert_error(
+        test.forfeit_no_show(0, 0, None).await,
+        TournamentError::InvalidPairing,
+    );
+}
+
+#[tokio::test]
+async fn cancelled_games_free_the_pairing() {
+    let mut test = TestTournament::full(2, 0).await;
+    let [host, guest] = test.pairing_players(0).await;
+    let game = test.start_match(&host, 0, 0).await.unwrap();
+
+    assert_error(
+        test.clear_game(0, 0, game).await,
+        TournamentError::GameNotClosed,
+    );
+
+    test.cancel_game(game, &host).await.unwrap();
+    test.clear_game(0, 0, game).await.unwrap();
+    assert_eq!(test.pairing(0, 0).await.game, None);
+
+    let game = test.start_match(&host, 0, 0).await.unwrap();
+    test.join_game(game, &guest).await.unwrap();
+}
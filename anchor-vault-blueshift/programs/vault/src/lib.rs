@@ -1,6 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::{transfer, Transfer};
 
+pub mod config;
+pub mod limit;
+
+pub use config::*;
+pub use limit::*;
+
 declare_id!("22222222222222222222222222222222222222222222");
 
 #[program]
@@ -13,17 +19,86 @@ pub mod blueshift_anchor_vault {
         Ok(())
     }
 
-    // Verifies the vault currently holds zero lamports (it must not already exist).
-    // Ensures the deposit amount exceeds the rent-exempt minimum for a SystemAccount.
+    pub fn initialize_config(ctx: Context<InitializeConfig>, guardian: Pubkey) -> Result<()> {
+        ctx.accounts.config.set_inner(Config {
+            admin: ctx.accounts.admin.key(),
+            pending_admin: None,
+            guardian,
+            paused: false,
+            bump: ctx.bumps.config,
+        });
+        Ok(())
+    }
+
+    pub fn pause(ctx: Context<Pause>) -> Result<()> {
+        ctx.accounts.config.paused = true;
+        emit!(PauseEvent {
+            paused: true,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn unpause(ctx: Context<AdminOnly>) -> Result<()> {
+        ctx.accounts.config.paused = false;
+        emit!(PauseEvent {
+            paused: false,
+            authority: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn set_guardian(ctx: Context<AdminOnly>, guardian: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let previous_guardian = config.guardian;
+        config.guardian = guardian;
+
+        emit!(GuardianChangedEvent {
+            previous_guardian,
+            new_guardian: guardian,
+        });
+        Ok(())
+    }
+
+    // The new admin takes over once they call accept_admin
+    pub fn propose_admin(ctx: Context<AdminOnly>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.config.pending_admin = Some(new_admin);
+
+        emit!(AdminProposedEvent {
+            admin: ctx.accounts.admin.key(),
+            pending_admin: new_admin,
+        });
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let previous_admin = config.admin;
+        config.admin = ctx.accounts.new_admin.key();
+        config.pending_admin = None;
+
+        emit!(AdminChangedEvent {
+            previous_admin,
+            new_admin: config.admin,
+        });
+        Ok(())
+    }
+
+    // Creates the vault on the first deposit and tops it up after that.
+    // Ensures the vault ends up with at least the rent-exempt minimum for a SystemAccount.
     // Transfers lamports from the signer to the vault via a CPI to the System Program.
     pub fn deposit(ctx: Context<VaultAction>, amount: u64) -> Result<()> {
-        require_eq!(
-            ctx.accounts.vault.lamports(),
-            0,
-            VaultError::VaultAlreadyExists
-        );
-        require_gt!(
-            amount,
+        require_gt!(amount, 0, VaultError::InvalidAmount);
+        let balance = ctx
+            .accounts
+            .vault
+            .lamports()
+            .checked_add(amount)
+            .ok_or(VaultError::InvalidAmount)?;
+        require_gte!(
+            balance,
             Rent::get()?.minimum_balance(0),
             VaultError::InvalidAmount
         );
@@ -43,24 +118,34 @@ pub mod blueshift_anchor_vault {
     }
 
     // Uses the vault’s PDA to sign the transfer out of the vault on its own behalf.
-    // Transfers all lamports in the vault back to the signer.
+    // Transfers all lamports in the vault back to the signer, failing if a
+    // withdraw limit doesn't allow all of them; withdraw_max takes what it does.
     // TODO: Print the signer.key()
-    pub fn withdraw(ctx: Context<VaultAction>) -> Result<()> {
-        let bindings = ctx.accounts.signer.key();
-        let signer_seeds = &[b"vault", bindings.as_ref(), &[ctx.bumps.vault]];
+    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let amount = ctx.accounts.allowed_amount(true, ctx.bumps.limit)?;
+        ctx.accounts.pay_out(amount, ctx.bumps.vault)
+    }
 
-        transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.system_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.vault.to_account_info(),
-                    to: ctx.accounts.signer.to_account_info(),
-                },
-                &[&signer_seeds[..]],
-            ),
-            ctx.accounts.vault.lamports(),
-        )?;
+    // Transfers as much as the withdraw limit allows, all of the vault without one
+    pub fn withdraw_max(ctx: Context<Withdraw>) -> Result<()> {
+        let amount = ctx.accounts.allowed_amount(false, ctx.bumps.limit)?;
+        ctx.accounts.pay_out(amount, ctx.bumps.vault)
+    }
+
+    // Caps withdrawals to `cap` lamports per rolling `window` seconds, a window
+    // of 0 removes the cap. A stricter limit applies straight away; a looser one
+    // is queued for WithdrawLimit::RAISE_DELAY, then needs apply_withdraw_limit.
+    pub fn set_withdraw_limit(ctx: Context<SetWithdrawLimit>, cap: u64, window: i64) -> Result<()> {
+        let limit = &mut ctx.accounts.limit;
+        limit.bump = ctx.bumps.limit;
+        limit.set(cap, window, Clock::get()?.unix_timestamp)?;
+        Ok(())
+    }
 
+    pub fn apply_withdraw_limit(ctx: Context<ApplyWithdrawLimit>) -> Result<()> {
+        ctx.accounts
+            .limit
+            .apply_pending(Clock::get()?.unix_timestamp)?;
         Ok(())
     }
 }
@@ -77,6 +162,13 @@ pub struct VaultAction<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ VaultError::Paused
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(
         mut,
         seeds = [b"vault", signer.key().as_ref()],
@@ -87,10 +179,171 @@ pub struct VaultAction<'info> {
     pub system_program: Program<'info, System>, // Since vault : SystemAccount implies System Program ownership, Anchor expects the system_program to be available for potential operations.
 }
 
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ VaultError::Paused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", signer.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    // Always required, otherwise a stolen key could just leave it out. The
+    // first withdraw creates it without a limit if set_withdraw_limit hasn't.
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = WithdrawLimit::SIZE,
+        seeds = [b"limit", signer.key().as_ref()],
+        bump
+    )]
+    pub limit: Account<'info, WithdrawLimit>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Withdraw<'info> {
+    // How much this withdraw pays out, recording it against the limit.
+    // With `all`, it's the whole balance or nothing.
+    fn allowed_amount(&mut self, all: bool, limit_bump: u8) -> Result<u64> {
+        let balance = self.vault.lamports();
+        let now = Clock::get()?.unix_timestamp;
+        self.limit.bump = limit_bump;
+
+        // Whatever stays behind has to keep the vault rent exempt
+        let allowance = self.limit.allowance(now);
+        if all {
+            require_gte!(allowance, balance, VaultError::AllowanceExceeded);
+        }
+        let amount = if allowance >= balance {
+            balance
+        } else {
+            allowance.min(balance.saturating_sub(Rent::get()?.minimum_balance(0)))
+        };
+        require_gt!(amount, 0, VaultError::WithdrawLimitExceeded);
+        if amount < balance {
+            msg!(
+                "Withdraw limit reached, {} lamports stay in the vault",
+                balance - amount
+            );
+        }
+
+        self.limit.record(amount, now)?;
+        Ok(amount)
+    }
+
+    fn pay_out(&self, amount: u64, bump: u8) -> Result<()> {
+        let bindings = self.signer.key();
+        let signer_seeds = &[b"vault", bindings.as_ref(), &[bump]];
+
+        transfer(
+            CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.vault.to_account_info(),
+                    to: self.signer.to_account_info(),
+                },
+                &[&signer_seeds[..]],
+            ),
+            amount,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetWithdrawLimit<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ VaultError::Paused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = WithdrawLimit::SIZE,
+        seeds = [b"limit", signer.key().as_ref()],
+        bump
+    )]
+    pub limit: Account<'info, WithdrawLimit>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyWithdrawLimit<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ VaultError::Paused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"limit", signer.key().as_ref()],
+        bump = limit.bump
+    )]
+    pub limit: Account<'info, WithdrawLimit>,
+}
+
+#[event]
+pub struct PauseEvent {
+    pub paused: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuardianChangedEvent {
+    pub previous_guardian: Pubkey,
+    pub new_guardian: Pubkey,
+}
+
+#[event]
+pub struct AdminProposedEvent {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminChangedEvent {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
 #[error_code]
 pub enum VaultError {
-    #[msg("Vault already exists")]
-    VaultAlreadyExists,
     #[msg("Invalid amount")]
     InvalidAmount,
+    #[msg("Withdraw limit needs a cap above 0 and a window of at most 30 days")]
+    InvalidWithdrawLimit,
+    #[msg("Withdraw limit reached for this window")]
+    WithdrawLimitExceeded,
+    #[msg("No withdraw limit change is pending")]
+    NoPendingLimit,
+    #[msg("Withdraw limit change is still timelocked")]
+    LimitChangeNotReady,
+    #[msg("Vaults are paused")]
+    Paused,
+    #[msg("Signer isn't allowed to change the config")]
+    Unauthorized,
+    #[msg("Withdraw limit doesn't allow the whole balance, use withdraw_max")]
+    AllowanceExceeded,
 }
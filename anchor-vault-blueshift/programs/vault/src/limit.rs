@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+
+use crate::VaultError;
+
+// Caps how much can leave a vault in any rolling window, e.g. 10 SOL per 24h.
+// Tightening the cap applies at once; anything that loosens it (a higher cap,
+// a shorter window, or no limit at all) waits out RAISE_DELAY first, so a
+// stolen key can't lift the cap and drain the vault in one go.
+#[account]
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct WithdrawLimit {
+    pub cap: u64,
+    pub window: i64, // seconds, 0 when there's no limit
+    pub withdrawals: Vec<Withdrawal>,
+    pub pending: Option<PendingLimit>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Withdrawal {
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PendingLimit {
+    pub cap: u64,
+    pub window: i64,
+    pub effective_at: i64,
+}
+
+impl WithdrawLimit {
+    pub const RAISE_DELAY: i64 = 2 * 24 * 60 * 60;
+    pub const MAX_WINDOW: i64 = 30 * 24 * 60 * 60;
+    pub const MAX_WITHDRAWALS: usize = 8;
+    // discriminator + cap + window + withdrawals + pending + bump
+    pub const SIZE: usize = 8 + 8 + 8 + (4 + Self::MAX_WITHDRAWALS * (8 + 8)) + (1 + 8 + 8 + 8) + 1;
+
+    pub fn is_enabled(&self) -> bool {
+        self.window > 0
+    }
+
+    // Applies the new limit now if it's at least as strict as the current one,
+    // otherwise queues it. Returns when it takes effect.
+    pub fn set(&mut self, cap: u64, window: i64, now: i64) -> Result<i64> {
+        require!(
+            (0..=Self::MAX_WINDOW).contains(&window) && (window == 0 || cap > 0),
+            VaultError::InvalidWithdrawLimit
+        );
+
+        let stricter =
+            window > 0 && (!self.is_enabled() || (cap <= self.cap && window >= self.window));
+        if stricter {
+            self.cap = cap;
+            self.window = window;
+            self.pending = None;
+            return Ok(now);
+        }
+
+        let effective_at = now + Self::RAISE_DELAY;
+        self.pending = Some(PendingLimit {
+            cap,
+            window,
+            effective_at,
+        });
+        Ok(effective_at)
+    }
+
+    pub fn apply_pending(&mut self, now: i64) -> Result<PendingLimit> {
+        let pending = self.pending.ok_or(VaultError::NoPendingLimit)?;
+        require_gte!(now, pending.effective_at, VaultError::LimitChangeNotReady);
+
+        self.cap = pending.cap;
+        self.window = pending.window;
+        self.pending = None;
+        if !self.is_enabled() {
+            self.withdrawals.clear();
+        }
+        Ok(pending)
+    }
+
+    // What can still be withdrawn in the current window
+    pub fn allowance(&mut self, now: i64) -> u64 {
+        if !self.is_enabled() {
+            return u64::MAX;
+        }
+
+        self.withdrawals
+            .retain(|withdrawal| withdrawal.timestamp > now - self.window);
+
+        let withdrawn: u64 = self.withdrawals.iter().map(|w| w.amount).sum();
+        self.cap.saturating_sub(withdrawn)
+    }
+
+    // Counts amount against the window, failing if it would go over the cap
+    pub fn record(&mut self, amount: u64, now: i64) -> Result<()> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        let allowance = self.allowance(now);
+        if amount > allowance {
+            let refills_in = self
+                .withdrawals
+                .first()
+                .map_or(0, |oldest| oldest.timestamp + self.window - now);
+            msg!(
+                "Only {} lamports can be withdrawn now, more frees up in {} seconds",
+                allowance,
+                refills_in
+            );
+            return err!(VaultError::WithdrawLimitExceeded);
+        }
+
+        // Out of slots, fold the oldest withdrawal into the next one. That
+        // keeps it counted for longer than it needs to be, never shorter.
+        if self.withdrawals.len() == Self::MAX_WITHDRAWALS {
+            let oldest = self.withdrawals.remove(0);
+            self.withdrawals[0].amount += oldest.amount;
+        }
+        self.withdrawals.push(Withdrawal {
+            amount,
+            timestamp: now,
+        });
+        Ok(())
+    }
+}
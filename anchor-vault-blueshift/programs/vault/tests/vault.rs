@@ -0,0 +1,478 @@
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::{
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        entrypoint::ProgramResult,
+    },
+    system_program, AccountDeserialize, InstructionData, ToAccountMetas,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+use vault::{VaultError, WithdrawLimit};
+
+const STARTING_LAMPORTS: u64 = 20 * LAMPORTS_PER_SOL;
+const DEPOSIT: u64 = 10 * LAMPORTS_PER_SOL;
+const CAP: u64 = LAMPORTS_PER_SOL;
+const DAY: i64 = 24 * 60 * 60;
+
+// Anchor's entrypoint ties the account slice to the `AccountInfo` lifetime,
+// which `processor!` can't express, so leak the slice for the test run.
+fn entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    vault::entry(program_id, accounts, data)
+}
+
+// The test payer is the program's upgrade authority and the config admin
+struct TestVault {
+    context: ProgramTestContext,
+    user: Keypair,
+    guardian: Keypair,
+}
+
+impl TestVault {
+    async fn new() -> Self {
+        let mut test = Self::without_config().await;
+        let admin = test.context.payer.insecure_clone();
+        test.initialize_config(&admin).await.unwrap();
+
+        test.deposit(DEPOSIT).await.unwrap();
+        test
+    }
+
+    async fn without_config() -> Self {
+        let mut program_test = ProgramTest::new("vault", vault::ID, processor!(entry));
+        let user = Keypair::new();
+        program_test.add_account(
+            user.pubkey(),
+            Account::new(STARTING_LAMPORTS, 0, &system_program::ID),
+        );
+
+        let mut context = program_test.start_with_context().await;
+        // processor! registers the program as a builtin, so stand in the
+        // ProgramData account an upgradeable deploy would have created
+        let program_data = Account::new_data(
+            LAMPORTS_PER_SOL,
+            &UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(context.payer.pubkey()),
+            },
+            &bpf_loader_upgradeable::ID,
+        )
+        .unwrap();
+        context.set_account(&program_data_address(), &program_data.into());
+
+        Self {
+            context,
+            user,
+            guardian: Keypair::new(),
+        }
+    }
+
+    async fn initialize_config(&mut self, admin: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault::ID,
+            accounts: vault::accounts::InitializeConfig {
+                admin: admin.pubkey(),
+                config: config_address(),
+                program_data: program_data_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: vault::instruction::InitializeConfig {
+                guardian: self.guardian.pubkey(),
+            }
+            .data(),
+        };
+        self.send_as(ix, admin).await
+    }
+
+    async fn deposit(&mut self, amount: u64) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault::ID,
+            accounts: vault::accounts::VaultAction {
+                signer: self.user.pubkey(),
+                vault: self.vault_address(),
+                config: config_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: vault::instruction::Deposit { amount }.data(),
+        };
+        self.send(ix).await
+    }
+
+    async fn withdraw(&mut self) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault::ID,
+            accounts: vault::accounts::Withdraw {
+                signer: self.user.pubkey(),
+                vault: self.vault_address(),
+                limit: self.limit_address(),
+                config: config_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: vault::instruction::Withdraw {}.data(),
+        };
+        self.send(ix).await
+    }
+
+    async fn withdraw_max(&mut self) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault::ID,
+            accounts: vault::accounts::Withdraw {
+                signer: self.user.pubkey(),
+                vault: self.vault_address(),
+                limit: self.limit_address(),
+                config: config_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: vault::instruction::WithdrawMax {}.data(),
+        };
+        self.send(ix).await
+    }
+
+    async fn set_withdraw_limit(&mut self, cap: u64, window: i64) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault::ID,
+            accounts: vault::accounts::SetWithdrawLimit {
+                signer: self.user.pubkey(),
+                limit: self.limit_address(),
+                config: config_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: vault::instruction::SetWithdrawLimit { cap, window }.data(),
+        };
+        self.send(ix).await
+    }
+
+    async fn apply_withdraw_limit(&mut self) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault::ID,
+            accounts: vault::accounts::ApplyWithdrawLimit {
+                signer: self.user.pubkey(),
+                config: config_address(),
+                limit: self.limit_address(),
+            }
+            .to_account_metas(None),
+            data: vault::instruction::ApplyWithdrawLimit {}.data(),
+        };
+        self.send(ix).await
+    }
+
+    async fn pause(&mut self, authority: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault::ID,
+            accounts: vault::accounts::Pause {
+                authority: authority.pubkey(),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: vault::instruction::Pause {}.data(),
+        };
+        self.send_as(ix, authority).await
+    }
+
+    async fn unpause(&mut self, admin: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault::ID,
+            accounts: vault::accounts::AdminOnly {
+                admin: admin.pubkey(),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: vault::instruction::Unpause {}.data(),
+        };
+        self.send_as(ix, admin).await
+    }
+
+    async fn propose_admin(
+        &mut self,
+        admin: &Keypair,
+        new_admin: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault::ID,
+            accounts: vault::accounts::AdminOnly {
+                admin: admin.pubkey(),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: vault::instruction::ProposeAdmin { new_admin }.data(),
+        };
+        self.send_as(ix, admin).await
+    }
+
+    async fn accept_admin(&mut self, new_admin: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault::ID,
+            accounts: vault::accounts::AcceptAdmin {
+                new_admin: new_admin.pubkey(),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: vault::instruction::AcceptAdmin {}.data(),
+        };
+        self.send_as(ix, new_admin).await
+    }
+
+    async fn send(&mut self, ix: Instruction) -> Result<(), BanksClientError> {
+        let user = self.user.insecure_clone();
+        self.send_as(ix, &user).await
+    }
+
+    async fn send_as(&mut self, ix: Instruction, signer: &Keypair) -> Result<(), BanksClientError> {
+        // Refresh the blockhash so identical instructions don't dedupe as the same transaction
+        let recent_blockhash = self.context.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&self.context.payer.pubkey()),
+            &[&self.context.payer, signer],
+            recent_blockhash,
+        );
+        self.context.banks_client.process_transaction(tx).await
+    }
+
+    async fn advance_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    async fn vault_lamports(&mut self) -> u64 {
+        let vault = self.vault_address();
+        self.context.banks_client.get_balance(vault).await.unwrap()
+    }
+
+    async fn limit(&mut self) -> WithdrawLimit {
+        let account = self
+            .context
+            .banks_client
+            .get_account(self.limit_address())
+            .await
+            .unwrap()
+            .unwrap();
+        WithdrawLimit::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    fn vault_address(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"vault", self.user.pubkey().as_ref()], &vault::ID).0
+    }
+
+    fn limit_address(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"limit", self.user.pubkey().as_ref()], &vault::ID).0
+    }
+}
+
+fn config_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &vault::ID).0
+}
+
+fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[vault::ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+fn assert_error(result: Result<(), BanksClientError>, error: VaultError) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, error as u32 + anchor_lang::error::ERROR_CODE_OFFSET)
+        }
+        other => panic!("expected {error:?}, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn withdraw_without_a_limit_drains_the_vault() {
+    let mut test = TestVault::new().await;
+
+    test.withdraw().await.unwrap();
+
+    assert_eq!(test.vault_lamports().await, 0);
+}
+
+#[tokio::test]
+async fn withdraw_fails_over_the_cap() {
+    let mut test = TestVault::new().await;
+    test.set_withdraw_limit(CAP, DAY).await.unwrap();
+
+    assert_error(test.withdraw().await, VaultError::AllowanceExceeded);
+    assert_eq!(test.vault_lamports().await, DEPOSIT);
+    assert!(test.limit().await.withdrawals.is_empty());
+}
+
+#[tokio::test]
+async fn withdraw_takes_everything_within_the_cap() {
+    let mut test = TestVault::new().await;
+    test.set_withdraw_limit(DEPOSIT, DAY).await.unwrap();
+
+    test.withdraw().await.unwrap();
+    assert_eq!(test.vault_lamports().await, 0);
+}
+
+#[tokio::test]
+async fn withdraw_max_pays_out_up_to_the_cap() {
+    let mut test = TestVault::new().await;
+    test.set_withdraw_limit(CAP, DAY).await.unwrap();
+
+    test.withdraw_max().await.unwrap();
+    assert_eq!(test.vault_lamports().await, DEPOSIT - CAP);
+    assert_error(test.withdraw_max().await, VaultError::WithdrawLimitExceeded);
+
+    // The window rolls, so the first withdrawal ages out after a day
+    test.advance_clock(DAY).await;
+    test.withdraw_max().await.unwrap();
+    assert_eq!(test.vault_lamports().await, DEPOSIT - 2 * CAP);
+}
+
+#[tokio::test]
+async fn withdraw_max_leaves_the_vault_rent_exempt() {
+    let mut test = TestVault::new().await;
+    let rent = Rent::default().minimum_balance(0);
+    test.set_withdraw_limit(DEPOSIT - rent / 2, DAY)
+        .await
+        .unwrap();
+
+    test.withdraw_max().await.unwrap();
+
+    assert_eq!(test.vault_lamports().await, rent);
+}
+
+#[tokio::test]
+async fn deposit_tops_up_after_withdraw_max() {
+    let mut test = TestVault::new().await;
+    test.set_withdraw_limit(CAP, DAY).await.unwrap();
+    test.withdraw_max().await.unwrap();
+
+    test.deposit(CAP).await.unwrap();
+    assert_eq!(test.vault_lamports().await, DEPOSIT);
+}
+
+#[tokio::test]
+async fn first_deposit_covers_rent() {
+    let mut test = TestVault::new().await;
+    test.withdraw().await.unwrap();
+    let rent = Rent::default().minimum_balance(0);
+
+    assert_error(test.deposit(rent - 1).await, VaultError::InvalidAmount);
+    test.deposit(rent).await.unwrap();
+    test.deposit(1).await.unwrap();
+    assert_eq!(test.vault_lamports().await, rent + 1);
+}
+
+#[tokio::test]
+async fn raising_the_limit_is_timelocked() {
+    let mut test = TestVault::new().await;
+    test.set_withdraw_limit(CAP, DAY).await.unwrap();
+
+    // Removing the limit is a raise too
+    test.set_withdraw_limit(0, 0).await.unwrap();
+    assert_eq!(test.limit().await.window, DAY);
+
+    test.advance_clock(WithdrawLimit::RAISE_DELAY - 1).await;
+    assert_error(
+        test.apply_withdraw_limit().await,
+        VaultError::LimitChangeNotReady,
+    );
+    test.withdraw_max().await.unwrap();
+    assert_eq!(test.vault_lamports().await, DEPOSIT - CAP);
+
+    test.advance_clock(1).await;
+    test.apply_withdraw_limit().await.unwrap();
+    test.withdraw().await.unwrap();
+    assert_eq!(test.vault_lamports().await, 0);
+}
+
+#[tokio::test]
+async fn tightening_the_limit_applies_at_once() {
+    let mut test = TestVault::new().await;
+    test.set_withdraw_limit(2 * CAP, DAY).await.unwrap();
+    test.set_withdraw_limit(5 * CAP, DAY).await.unwrap();
+
+    // A stricter limit also drops the pending raise
+    test.set_withdraw_limit(CAP, DAY).await.unwrap();
+    let limit = test.limit().await;
+    assert_eq!(limit.cap, CAP);
+    assert_eq!(limit.pending, None);
+
+    test.withdraw_max().await.unwrap();
+    assert_eq!(test.vault_lamports().await, DEPOSIT - CAP);
+    assert_error(
+        test.apply_withdraw_limit().await,
+        VaultError::NoPendingLimit,
+    );
+}
+
+#[tokio::test]
+async fn rejects_invalid_limits() {
+    let mut test = TestVault::new().await;
+
+    assert_error(
+        test.set_withdraw_limit(0, DAY).await,
+        VaultError::InvalidWithdrawLimit,
+    );
+    assert_error(
+        test.set_withdraw_limit(CAP, WithdrawLimit::MAX_WINDOW + 1)
+            .await,
+        VaultError::InvalidWithdrawLimit,
+    );
+}
+
+#[tokio::test]
+async fn only_the_upgrade_authority_initializes_the_config() {
+    let mut test = TestVault::without_config().await;
+    let user = test.user.insecure_clone();
+    let admin = test.context.payer.insecure_clone();
+
+    assert_error(
+        test.initialize_config(&user).await,
+        VaultError::Unauthorized,
+    );
+    test.initialize_config(&admin).await.unwrap();
+    test.deposit(DEPOSIT).await.unwrap();
+}
+
+#[tokio::test]
+async fn guardian_pauses_and_admin_unpauses() {
+    let mut test = TestVault::new().await;
+    let (user, guardian) = (test.user.insecure_clone(), test.guardian.insecure_clone());
+    let admin = test.context.payer.insecure_clone();
+
+    assert_error(test.pause(&user).await, VaultError::Unauthorized);
+    test.pause(&guardian).await.unwrap();
+
+    assert_error(test.withdraw().await, VaultError::Paused);
+    assert_error(test.set_withdraw_limit(CAP, DAY).await, VaultError::Paused);
+
+    // The guardian can only pull the brake
+    assert_error(test.unpause(&guardian).await, VaultError::Unauthorized);
+    test.unpause(&admin).await.unwrap();
+    test.withdraw().await.unwrap();
+}
+
+#[tokio::test]
+async fn admin_handover_takes_two_steps() {
+    let mut test = TestVault::new().await;
+    let (user, new_admin) = (test.user.insecure_clone(), Keypair::new());
+    let admin = test.context.payer.insecure_clone();
+
+    test.propose_admin(&admin, new_admin.pubkey())
+        .await
+        .unwrap();
+    assert_error(test.accept_admin(&user).await, VaultError::Unauthorized);
+
+    test.accept_admin(&new_admin).await.unwrap();
+    assert_error(test.pause(&admin).await, VaultError::Unauthorized);
+    test.pause(&new_admin).await.unwrap();
+    test.unpause(&new_admin).await.unwrap();
+}
@@ -1,16 +1,114 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
+import { LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
 import { Vault } from "../target/types/vault";
 
 describe("vault", () => {
   // Configure the client to use the local cluster.
-  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
 
   const program = anchor.workspace.vault as Program<Vault>;
 
+  const user = anchor.web3.Keypair.generate();
+  const DEPOSIT = LAMPORTS_PER_SOL;
+  const CAP = LAMPORTS_PER_SOL / 4;
+  const DAY = 24 * 60 * 60;
+
+  const [vaultPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("vault"), user.publicKey.toBuffer()],
+    program.programId
+  );
+
+  before(async () => {
+    const signature = await provider.connection.requestAirdrop(
+      user.publicKey,
+      2 * LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(signature);
+
+    // Deposits and withdrawals read the program config. Only the upgrade
+    // authority can create it, `[test] upgradeable` in Anchor.toml makes that
+    // the provider wallet.
+    const [configPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+    if (!(await provider.connection.getAccountInfo(configPDA))) {
+      await program.methods
+        .initializeConfig(provider.wallet.publicKey)
+        .accounts({ admin: provider.wallet.publicKey })
+        .rpc();
+    }
+  });
+
   it("Is initialized!", async () => {
-    // Add your test here.
     const tx = await program.methods.initialize().rpc();
     console.log("Your transaction signature", tx);
   });
+
+  it("deposits into a new vault", async () => {
+    await program.methods
+      .deposit(new anchor.BN(DEPOSIT))
+      .accounts({ signer: user.publicKey })
+      .signers([user])
+      .rpc();
+
+    expect(await provider.connection.getBalance(vaultPDA)).to.equal(DEPOSIT);
+  });
+
+  it("withdraw fails when the limit doesn't cover the vault", async () => {
+    await program.methods
+      .setWithdrawLimit(new anchor.BN(CAP), new anchor.BN(DAY))
+      .accounts({ signer: user.publicKey })
+      .signers([user])
+      .rpc();
+
+    try {
+      await program.methods
+        .withdraw()
+        .accounts({ signer: user.publicKey })
+        .signers([user])
+        .rpc();
+      expect.fail("Expected transaction to fail");
+    } catch (error) {
+      expect(error.toString()).to.include("AllowanceExceeded");
+    }
+    expect(await provider.connection.getBalance(vaultPDA)).to.equal(DEPOSIT);
+  });
+
+  it("withdrawMax takes what the limit allows", async () => {
+    await program.methods
+      .withdrawMax()
+      .accounts({ signer: user.publicKey })
+      .signers([user])
+      .rpc();
+
+    expect(await provider.connection.getBalance(vaultPDA)).to.equal(
+      DEPOSIT - CAP
+    );
+
+    // The cap is spent for this window
+    try {
+      await program.methods
+        .withdrawMax()
+        .accounts({ signer: user.publicKey })
+        .signers([user])
+        .rpc();
+      expect.fail("Expected transaction to fail");
+    } catch (error) {
+      expect(error.toString()).to.include("WithdrawLimitExceeded");
+    }
+  });
+
+  it("deposit tops the vault back up", async () => {
+    await program.methods
+      .deposit(new anchor.BN(CAP))
+      .accounts({ signer: user.publicKey })
+      .signers([user])
+      .rpc();
+
+    expect(await provider.connection.getBalance(vaultPDA)).to.equal(DEPOSIT);
+  });
 });
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{Config, VaultError};
+
+// 10%, the most the admin can set
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+// Rounds down, so withdrawals too small to owe a whole token unit pay nothing
+pub fn withdraw_fee(amount: u64, fee_bps: u16) -> u64 {
+    // can't overflow, fee_bps is at most 10_000
+    (amount as u128 * fee_bps as u128 / 10_000) as u64
+}
+
+// Withdrawal fees are kept per mint in a token account at
+// [b"VAULT_MANAGER_FEES", mint], next to the vault but outside it, so they
+// never count towards what the shares are worth. Only the admin can move them.
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VaultError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: only signs for the fee account
+    #[account(
+        seeds = [b"VAULT_MANAGER"],
+        bump
+    )]
+    pub token_account_owner_pda: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"VAULT_MANAGER_FEES", mint_account.key().as_ref()],
+        bump,
+        token::mint = mint_account,
+        token::authority = token_account_owner_pda,
+        token::token_program = token_program
+    )]
+    pub fee_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::token_program = token_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -1,5 +1,19 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{
+    burn, mint_to, transfer_checked, Burn, Mint, MintTo, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+pub mod config;
+pub use config::*;
+pub mod fees;
+pub use fees::*;
+pub mod registry;
+pub use registry::*;
+pub mod sweep;
+pub use sweep::*;
 
 declare_id!("2eH4VtkkB5X5592hmuQqFQvQ9QKaTEmRZyvQgf9EWyxp");
 
@@ -12,13 +26,186 @@ pub mod vault_manager {
         Ok(())
     }
 
+    pub fn initialize_config(ctx: Context<InitializeConfig>, guardian: Pubkey) -> Result<()> {
+        ctx.accounts.config.set_inner(Config {
+            admin: ctx.accounts.admin.key(),
+            pending_admin: None,
+            guardian,
+            paused: false,
+            fee_bps: 0,
+            bump: ctx.bumps.config,
+        });
+        ctx.accounts.registry.set_inner(Registry {
+            mints: vec![],
+            bump: ctx.bumps.registry,
+        });
+        Ok(())
+    }
+
+    pub fn pause(ctx: Context<Pause>) -> Result<()> {
+        ctx.accounts.config.paused = true;
+        emit!(PauseEvent {
+            paused: true,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn unpause(ctx: Context<AdminOnly>) -> Result<()> {
+        ctx.accounts.config.paused = false;
+        emit!(PauseEvent {
+            paused: false,
+            authority: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn set_guardian(ctx: Context<AdminOnly>, guardian: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let previous_guardian = config.guardian;
+        config.guardian = guardian;
+
+        emit!(GuardianChangedEvent {
+            previous_guardian,
+            new_guardian: guardian,
+        });
+        Ok(())
+    }
+
+    // The new admin takes over once they call accept_admin
+    pub fn propose_admin(ctx: Context<AdminOnly>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.config.pending_admin = Some(new_admin);
+
+        emit!(AdminProposedEvent {
+            admin: ctx.accounts.admin.key(),
+            pending_admin: new_admin,
+        });
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let previous_admin = config.admin;
+        config.admin = ctx.accounts.new_admin.key();
+        config.pending_admin = None;
+
+        emit!(AdminChangedEvent {
+            previous_admin,
+            new_admin: config.admin,
+        });
+        Ok(())
+    }
+
+    pub fn set_fee(ctx: Context<AdminOnly>, fee_bps: u16) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, VaultError::InvalidFee);
+        ctx.accounts.config.fee_bps = fee_bps;
+        msg!("Withdrawal fee set to {} bps", fee_bps);
+        Ok(())
+    }
+
+    // Sends everything in `mint`'s fee account to `destination`
+    pub fn collect_fees(ctx: Context<CollectFees>) -> Result<()> {
+        let amount = ctx.accounts.fee_account.amount;
+        require!(amount > 0, VaultError::NoFeesToCollect);
+
+        let seeds = &[b"VAULT_MANAGER".as_ref(), &[ctx.bumps.token_account_owner_pda]];
+        let signer_seeds = &[&seeds[..]];
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.fee_account.to_account_info(),
+                    mint: ctx.accounts.mint_account.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.token_account_owner_pda.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            ctx.accounts.mint_account.decimals,
+        )?;
+
+        emit!(CollectFeesEvent {
+            mint: ctx.accounts.mint_account.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    // Sends whatever is in `mint`'s vault beyond total_assets to `destination`
+    pub fn sweep(ctx: Context<Sweep>) -> Result<()> {
+        let share_supply = ctx.accounts.share_supply()?;
+        let total_assets = ctx
+            .accounts
+            .vault_state
+            .total_assets(ctx.accounts.vault.amount, share_supply);
+        let amount = ctx.accounts.vault.amount.saturating_sub(total_assets);
+        require!(amount > 0, VaultError::NothingToSweep);
+
+        let seeds = &[b"VAULT_MANAGER".as_ref(), &[ctx.bumps.token_account_owner_pda]];
+        let signer_seeds = &[&seeds[..]];
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint_account.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.token_account_owner_pda.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            ctx.accounts.mint_account.decimals,
+        )?;
+
+        let vault_state = &mut ctx.accounts.vault_state;
+        vault_state.mint = ctx.accounts.mint_account.key();
+        vault_state.total_assets = total_assets;
+        vault_state.bump = ctx.bumps.vault_state;
+
+        emit!(SweepEvent {
+            mint: ctx.accounts.mint_account.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    // Lists `mint` for deposits, or updates its caps if it's already listed
+    pub fn set_allowed_mint(
+        ctx: Context<UpdateRegistry>,
+        mint: Pubkey,
+        total_cap: u64,
+        user_cap: u64,
+    ) -> Result<()> {
+        ctx.accounts.registry.set(AllowedMint {
+            mint,
+            total_cap,
+            user_cap,
+        })?;
+        msg!("Mint {} allowed, total cap {}, user cap {}", mint, total_cap, user_cap);
+        Ok(())
+    }
+
+    pub fn remove_allowed_mint(ctx: Context<UpdateRegistry>, mint: Pubkey) -> Result<()> {
+        ctx.accounts.registry.remove(&mint)?;
+        msg!("Mint {} removed", mint);
+        Ok(())
+    }
+
     pub fn deposit(
         ctx: Context<Deposit>,
         amount: u64,
     ) -> Result<()> {
         msg!("Amount Deposting: {}", amount); //logs the amount deposited
         //creates reference to the senders's token amount from ctx.accounts
-        let sender_token_account: &Account<TokenAccount> = &ctx.accounts.sender_token_account;
+        let sender_token_account: &InterfaceAccount<TokenAccount> = &ctx.accounts.sender_token_account;
 
         //checks the senders has enough tokens to send
         if sender_token_account.amount < amount{
@@ -28,9 +215,22 @@ pub mod vault_manager {
         // display balance in senders account
         msg!("Senders account balance: {}", sender_token_account.amount);
 
-        // Creats a transfer instruction for SPL token program
-        let tx_instruct: anchor_spl::token::Transfer = Transfer {
+        // only listed mints can be deposited, up to their caps
+        let allowed = *ctx
+            .accounts
+            .registry
+            .get(&ctx.accounts.mint_account.key())
+            .ok_or(VaultError::MintNotAllowed)?;
+
+        // shares are priced off what was deposited before this one lands
+        let vault_before = ctx.accounts.vault.amount;
+        let share_supply = ctx.accounts.share_mint.supply;
+        let total_assets = ctx.accounts.vault_state.total_assets(vault_before, share_supply);
+
+        // Creats a transfer instruction for the SPL token or Token-2022 program
+        let tx_instruct: TransferChecked = TransferChecked {
             from: ctx.accounts.sender_token_account.to_account_info(),
+            mint: ctx.accounts.mint_account.to_account_info(),
             to: ctx.accounts.vault.to_account_info(),
             authority: ctx.accounts.signer.to_account_info(),
         };  
@@ -38,17 +238,75 @@ pub mod vault_manager {
         //creates a CPI cross platform invocation Context
         //it combines the token program account with the transfer instruction
         // READ MORE: https://solana.com/docs/core/cpi
-        let cpi_ctx: CpiContext<anchor_spl::token::Transfer> = CpiContext::new(
+        let cpi_ctx: CpiContext<TransferChecked> = CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             tx_instruct
         );
 
         //executes transfer instruction usign CPI Context
         // ? shows any errors the may occur during transfer
-        anchor_spl::token::transfer(cpi_ctx, amount)?;
+        transfer_checked(cpi_ctx, amount, ctx.accounts.mint_account.decimals)?;
+
+        // a transfer fee mint withholds part of the amount in the vault, only
+        // what the vault can actually spend is credited
+        ctx.accounts.vault.reload()?;
+        let received = ctx.accounts.vault.amount - vault_before;
+        msg!("Amount received: {}", received);
+
+        let total_assets_after = total_assets
+            .checked_add(received)
+            .ok_or(VaultError::Overflow)?;
+        require!(
+            total_assets_after <= allowed.total_cap,
+            VaultError::DepositCapExceeded
+        );
+        // shares can change hands, so only count what this user still has in
+        let user_deposit = &ctx.accounts.user_deposit;
+        let outstanding = user_deposit
+            .deposited
+            .saturating_sub(user_deposit.withdrawn)
+            .checked_add(received)
+            .ok_or(VaultError::Overflow)?;
+        require!(outstanding <= allowed.user_cap, VaultError::UserCapExceeded);
+
+        let shares = shares_for_deposit(received, total_assets, share_supply)?;
+
+        // mints the depositor's receipt, signed by the PDA that owns the vaults
+        let seeds = &[b"VAULT_MANAGER".as_ref(), &[ctx.bumps.token_account_owner_pda]];
+        let signer_seeds = &[&seeds[..]];
+        mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.share_mint.to_account_info(),
+                    to: ctx.accounts.share_account.to_account_info(),
+                    authority: ctx.accounts.token_account_owner_pda.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            shares,
+        )?;
+        msg!("Shares minted: {}", shares);
+
+        let user_deposit = &mut ctx.accounts.user_deposit;
+        user_deposit.owner = ctx.accounts.signer.key();
+        user_deposit.mint = ctx.accounts.mint_account.key();
+        user_deposit.deposited = user_deposit
+            .deposited
+            .checked_add(received)
+            .ok_or(VaultError::Overflow)?;
+        user_deposit.bump = ctx.bumps.user_deposit;
+
+        let vault_state = &mut ctx.accounts.vault_state;
+        vault_state.mint = ctx.accounts.mint_account.key();
+        vault_state.total_assets = total_assets_after;
+        vault_state.bump = ctx.bumps.vault_state;
+
         Ok(()) // if no erros are thrown is returns Ok(())
     }
 
+    // `amount` is in the vault's token, the shares it takes are burned from the signer.
+    // The protocol fee and any transfer fee on the mint come out of what the receiver gets.
     pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()>{
         msg!("Amount Withdrawing: {}", amount);
         
@@ -61,17 +319,87 @@ pub mod vault_manager {
         //Vaults balance
         msg!("Vaults Balance: {}", vault.amount);
 
+        // only what the signer's shares are worth can leave
+        let share_supply = ctx.accounts.share_mint.supply;
+        let total_assets = ctx.accounts.vault_state.total_assets(vault.amount, share_supply);
+        let shares = shares_for_withdrawal(amount, total_assets, share_supply)?;
+        if ctx.accounts.share_account.amount < shares {
+            return Err(VaultError::InsufficientShares.into());
+        }
+
+        burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.share_mint.to_account_info(),
+                    from: ctx.accounts.share_account.to_account_info(),
+                    authority: ctx.accounts.signer.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+        msg!("Shares burned: {}", shares);
+
         let seeds = &[b"VAULT_MANAGER".as_ref(), &[ctx.bumps.token_account_owner_pda]];
         let signer_seeds = &[&seeds[..]];
 
-        let tx_instruct = Transfer {
+        let fee = withdraw_fee(amount, ctx.accounts.config.fee_bps);
+        let net = amount - fee;
+
+        let tx_instruct = TransferChecked {
             from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint_account.to_account_info(),
             to: ctx.accounts.receiver_token_account.to_account_info(),
             authority: ctx.accounts.token_account_owner_pda.to_account_info(),
         };
 
         let cpi_program = ctx.accounts.token_program.to_account_info();
-        transfer(CpiContext::new_with_signer(cpi_program, tx_instruct, signer_seeds), amount)?;
+        transfer_checked(
+            CpiContext::new_with_signer(cpi_program, tx_instruct, signer_seeds),
+            net,
+            ctx.accounts.mint_account.decimals,
+        )?;
+
+        if fee > 0 {
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.vault.to_account_info(),
+                        mint: ctx.accounts.mint_account.to_account_info(),
+                        to: ctx.accounts.fee_account.to_account_info(),
+                        authority: ctx.accounts.token_account_owner_pda.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                fee,
+                ctx.accounts.mint_account.decimals,
+            )?;
+        }
+
+        let user_deposit = &mut ctx.accounts.user_deposit;
+        user_deposit.owner = ctx.accounts.signer.key();
+        user_deposit.mint = ctx.accounts.mint_account.key();
+        user_deposit.withdrawn = user_deposit
+            .withdrawn
+            .checked_add(amount)
+            .ok_or(VaultError::Overflow)?;
+        user_deposit.bump = ctx.bumps.user_deposit;
+
+        let vault_state = &mut ctx.accounts.vault_state;
+        vault_state.mint = ctx.accounts.mint_account.key();
+        vault_state.total_assets = total_assets
+            .checked_sub(amount)
+            .ok_or(VaultError::InsufficientShares)?;
+        vault_state.bump = ctx.bumps.vault_state;
+
+        emit!(WithdrawEvent {
+            user: ctx.accounts.signer.key(),
+            mint: ctx.accounts.mint_account.key(),
+            gross: amount,
+            fee,
+            net,
+        });
 
         msg!("Withdrawal successful");
         Ok(())
@@ -102,6 +430,19 @@ pub struct Initialize<'info> {
 
 #[derive(Accounts)]
 pub struct Deposit<'info>{
+	#[account(
+		seeds = [b"config"],
+		bump = config.bump,
+		constraint = !config.paused @ VaultError::Paused
+	)]
+	pub config: Account<'info, Config>,
+
+	#[account(
+		seeds = [b"registry"],
+		bump = registry.bump
+	)]
+	pub registry: Box<Account<'info, Registry>>,
+
 	#[account(mut,
 		seeds=[b"VAULT_MANAGER"],
 		bump
@@ -113,27 +454,103 @@ pub struct Deposit<'info>{
 			b"VAULT_MANAGER_PDA_VAULT",
 			mint_account.key().as_ref()
 		],
-		token::mint      = mint_account,
-		token::authority = token_account_owner_pda,
-		payer            = signer,
+		token::mint          = mint_account,
+		token::authority     = token_account_owner_pda,
+		token::token_program = token_program,
+		payer                = signer,
+		bump
+	)]
+	pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+	// what depositors have put in the vault, see shares_for_deposit
+	#[account(
+		init_if_needed,
+		seeds = [
+			b"VAULT_MANAGER_STATE",
+			mint_account.key().as_ref()
+		],
+		space = 8 + VaultState::INIT_SPACE,
+		payer = signer,
+		bump
+	)]
+	pub vault_state: Box<Account<'info, VaultState>>,
+
+	// withdrawal fees for this mint, see fees.rs
+	#[account(
+		init_if_needed,
+		seeds = [
+			b"VAULT_MANAGER_FEES",
+			mint_account.key().as_ref()
+		],
+		token::mint          = mint_account,
+		token::authority     = token_account_owner_pda,
+		token::token_program = token_program,
+		payer                = signer,
+		bump
+	)]
+	pub fee_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+	// LP receipt for this vault, minted on deposit and burned on withdraw
+	#[account(
+		init_if_needed,
+		seeds = [
+			b"VAULT_MANAGER_SHARES",
+			mint_account.key().as_ref()
+		],
+		mint::decimals      = mint_account.decimals,
+		mint::authority     = token_account_owner_pda,
+		mint::token_program = token_program,
+		payer               = signer,
+		bump
+	)]
+	pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+	#[account(
+		init_if_needed,
+		associated_token::mint          = share_mint,
+		associated_token::authority     = signer,
+		associated_token::token_program = token_program,
+		payer                           = signer,
+	)]
+	pub share_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+	#[account(
+		init_if_needed,
+		seeds = [
+			b"VAULT_MANAGER_DEPOSIT",
+			mint_account.key().as_ref(),
+			signer.key().as_ref()
+		],
+		space = 8 + UserDeposit::INIT_SPACE,
+		payer = signer,
 		bump
 	)]
-	pub vault: Account<'info, TokenAccount>,
+	pub user_deposit: Box<Account<'info, UserDeposit>>,
 
 	#[account(mut)]
 	pub signer: Signer<'info>,
 
-	pub mint_account: Account<'info, Mint>,
+	#[account(mint::token_program = token_program)]
+	pub mint_account: Box<InterfaceAccount<'info, Mint>>,
 
 	#[account(mut)]
-	pub sender_token_account: Account<'info, TokenAccount>,
+	pub sender_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-	pub token_program:  Program<'info, Token>,
+	// either the SPL token program or Token-2022, whichever owns the mint
+	pub token_program:  Interface<'info, TokenInterface>,
+	pub associated_token_program: Program<'info, AssociatedToken>,
 	pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct Withdraw<'info>{
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ VaultError::Paused
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(mut,
     seeds = [b"VAULT_MANAGER"],
     bump
@@ -145,23 +562,200 @@ pub struct Withdraw<'info>{
     bump,
     token::mint = mint_account,
     token::authority = token_account_owner_pda,
+    token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+    init_if_needed,
+    seeds = [b"VAULT_MANAGER_STATE", mint_account.key().as_ref()],
+    bump,
+    space = 8 + VaultState::INIT_SPACE,
+    payer = signer,
+    )]
+    pub vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(mut,
+    seeds = [b"VAULT_MANAGER_FEES", mint_account.key().as_ref()],
+    bump,
+    token::mint = mint_account,
+    token::authority = token_account_owner_pda,
+    token::token_program = token_program,
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub fee_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut,
+    seeds = [b"VAULT_MANAGER_SHARES", mint_account.key().as_ref()],
+    bump,
+    )]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // the token program checks the signer owns it when burning
+    #[account(mut,
+    token::mint = share_mint,
+    token::token_program = token_program,
+    )]
+    pub share_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+    init_if_needed,
+    seeds = [b"VAULT_MANAGER_DEPOSIT", mint_account.key().as_ref(), signer.key().as_ref()],
+    bump,
+    space = 8 + UserDeposit::INIT_SPACE,
+    payer = signer,
+    )]
+    pub user_deposit: Box<Account<'info, UserDeposit>>,
     
-    #[account(mut)]
-    pub receiver_token_account: Account<'info, TokenAccount>,
+    #[account(mut,
+    token::mint = mint_account,
+    token::authority = signer,
+    token::token_program = token_program,
+    )]
+    pub receiver_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
 
-    pub mint_account: Account<'info, Mint>,
+    #[account(mint::token_program = token_program)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
+// What one user has put into and taken out of one mint's vault. Shares are
+// transferable, so withdrawn can end up above deposited for whoever holds them.
+#[account]
+#[derive(InitSpace)]
+pub struct UserDeposit {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub deposited: u64,
+    pub withdrawn: u64,
+    pub bump: u8,
+}
+
+// What has been deposited into one mint's vault and not withdrawn. Shares are
+// priced off this rather than the vault's balance, so tokens sent to the vault
+// directly don't change what a share is worth. They stay in the vault until
+// the admin sweeps them, see sweep.rs.
+#[account]
+#[derive(InitSpace)]
+pub struct VaultState {
+    pub mint: Pubkey,
+    pub total_assets: u64,
+    pub bump: u8,
+}
+
+impl VaultState {
+    // Vaults that had shares before total_assets was tracked start from their balance
+    pub fn total_assets(&self, vault_balance: u64, share_supply: u64) -> u64 {
+        if self.mint == Pubkey::default() && share_supply > 0 {
+            vault_balance
+        } else {
+            self.total_assets
+        }
+    }
+}
+
+// The first deposit sets the price at one share per token, after that shares
+// are minted in proportion to total_assets. A deposit worth less than one share
+// fails rather than minting nothing.
+pub fn shares_for_deposit(amount: u64, total_assets: u64, share_supply: u64) -> Result<u64> {
+    let shares = if share_supply == 0 || total_assets == 0 {
+        amount as u128
+    } else {
+        amount as u128 * share_supply as u128 / total_assets as u128
+    };
+    let shares = u64::try_from(shares).map_err(|_| VaultError::Overflow)?;
+    require!(shares > 0, VaultError::DepositTooSmall);
+    Ok(shares)
+}
+
+// Rounds up, so a withdrawal never takes out more than its shares are worth
+pub fn shares_for_withdrawal(amount: u64, total_assets: u64, share_supply: u64) -> Result<u64> {
+    require!(total_assets > 0, VaultError::InsufficientFunds);
+
+    let shares = (amount as u128 * share_supply as u128).div_ceil(total_assets as u128);
+    u64::try_from(shares).map_err(|_| VaultError::Overflow.into())
+}
+
+#[event]
+pub struct WithdrawEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub gross: u64,
+    pub fee: u64,
+    pub net: u64,
+}
+
+#[event]
+pub struct CollectFeesEvent {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SweepEvent {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PauseEvent {
+    pub paused: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuardianChangedEvent {
+    pub previous_guardian: Pubkey,
+    pub new_guardian: Pubkey,
+}
+
+#[event]
+pub struct AdminProposedEvent {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminChangedEvent {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
 #[error_code]
 pub enum VaultError {
 	#[msg("Insufficient Funds in Wallet!")]
 	InsufficientFunds,
+	#[msg("Vault Manager is paused")]
+	Paused,
+	#[msg("Signer isn't allowed to change the config")]
+	Unauthorized,
+	#[msg("Not enough shares for this withdrawal")]
+	InsufficientShares,
+	#[msg("Deposit is worth less than one share")]
+	DepositTooSmall,
+	#[msg("Arithmetic overflow")]
+	Overflow,
+	#[msg("Mint isn't on the allowlist")]
+	MintNotAllowed,
+	#[msg("Deposit would take the vault over its cap")]
+	DepositCapExceeded,
+	#[msg("Deposit would take the user over their cap")]
+	UserCapExceeded,
+	#[msg("User cap must be above 0 and no more than the total cap")]
+	InvalidCap,
+	#[msg("Allowlist is full")]
+	TooManyMints,
+	#[msg("Fee can't be more than 1000 bps")]
+	InvalidFee,
+	#[msg("No fees to collect")]
+	NoFeesToCollect,
+	#[msg("Vault holds nothing beyond its deposits")]
+	NothingToSweep,
 }
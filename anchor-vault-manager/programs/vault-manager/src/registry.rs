@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+
+use crate::{Config, VaultError};
+
+pub const MAX_ALLOWED_MINTS: usize = 16;
+
+// The mints the vault manager accepts deposits for, one account at
+// [b"registry"] created alongside the config. Only the admin can change it.
+// Withdrawals don't check it, so delisting a mint never traps deposits.
+#[account]
+#[derive(InitSpace)]
+pub struct Registry {
+    #[max_len(MAX_ALLOWED_MINTS)]
+    pub mints: Vec<AllowedMint>,
+    pub bump: u8,
+}
+
+// Caps are in the mint's base units. `total_cap` bounds the mint's
+// VaultState::total_assets, what all users have deposited and not yet
+// withdrawn, `user_cap` what one user has.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct AllowedMint {
+    pub mint: Pubkey,
+    pub total_cap: u64,
+    pub user_cap: u64,
+}
+
+impl Registry {
+    pub fn get(&self, mint: &Pubkey) -> Option<&AllowedMint> {
+        self.mints.iter().find(|allowed| allowed.mint == *mint)
+    }
+
+    // Adds the mint, or replaces its caps if it's already listed
+    pub fn set(&mut self, allowed: AllowedMint) -> Result<()> {
+        require!(
+            allowed.user_cap > 0 && allowed.user_cap <= allowed.total_cap,
+            VaultError::InvalidCap
+        );
+
+        match self
+            .mints
+            .iter_mut()
+            .find(|listed| listed.mint == allowed.mint)
+        {
+            Some(listed) => *listed = allowed,
+            None => {
+                require!(
+                    self.mints.len() < MAX_ALLOWED_MINTS,
+                    VaultError::TooManyMints
+                );
+                self.mints.push(allowed);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, mint: &Pubkey) -> Result<()> {
+        let index = self
+            .mints
+            .iter()
+            .position(|allowed| allowed.mint == *mint)
+            .ok_or(VaultError::MintNotAllowed)?;
+        self.mints.remove(index);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdateRegistry<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VaultError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{Config, VaultError, VaultState};
+
+// Tokens in a vault that no share accounts for: deposits from before shares
+// existed, which left no record of who made them, and tokens sent to the vault
+// directly. The admin can move them out, e.g. to pay back those depositors.
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VaultError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: only signs for the vault
+    #[account(
+        seeds = [b"VAULT_MANAGER"],
+        bump
+    )]
+    pub token_account_owner_pda: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"VAULT_MANAGER_PDA_VAULT", mint_account.key().as_ref()],
+        bump,
+        token::mint = mint_account,
+        token::authority = token_account_owner_pda,
+        token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // a vault from before share accounting has no state yet
+    #[account(
+        init_if_needed,
+        seeds = [b"VAULT_MANAGER_STATE", mint_account.key().as_ref()],
+        bump,
+        space = 8 + VaultState::INIT_SPACE,
+        payer = admin
+    )]
+    pub vault_state: Box<Account<'info, VaultState>>,
+
+    // Always the share mint's address, so leaving it out can't make the
+    // vault look like it has no shares. It may not exist yet.
+    /// CHECK: read as a mint in sweep when it holds data
+    #[account(
+        seeds = [b"VAULT_MANAGER_SHARES", mint_account.key().as_ref()],
+        bump
+    )]
+    pub share_mint: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::token_program = token_program
+    )]
+    pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Sweep<'info> {
+    pub fn share_supply(&self) -> Result<u64> {
+        if self.share_mint.data_is_empty() {
+            return Ok(0);
+        }
+        // only this program can create an account at a PDA, so it's the share mint
+        let data = self.share_mint.try_borrow_data()?;
+        Ok(Mint::try_deserialize(&mut &data[..])?.supply)
+    }
+}
@@ -0,0 +1,911 @@
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::entrypoint::ProgramResult,
+    system_program, AccountDeserialize, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address_with_program_id,
+        spl_associated_token_account::instruction::create_associated_token_account,
+    },
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{transfer_fee, ExtensionType, StateWithExtensions},
+    },
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    program_option::COption,
+    program_pack::Pack,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction, sysvar,
+    transaction::{Transaction, TransactionError},
+};
+use vault_manager::{UserDeposit, VaultError};
+
+const MINTED: u64 = 100;
+// 1%, rounded up, so depositing or withdrawing 100 pays a fee of 1
+const TRANSFER_FEE_BPS: u16 = 100;
+
+// Anchor's entrypoint ties the account slice to the `AccountInfo` lifetime,
+// which `processor!` can't express, so leak the slice for the test run.
+fn entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    vault_manager::entry(program_id, accounts, data)
+}
+
+// The test payer is the upgrade authority, the config admin and the mint
+// authority, and holds MINTED tokens to deposit. The mint is allowed with both caps at MINTED. `bob` starts with SOL and an empty token account.
+struct TestManager {
+    context: ProgramTestContext,
+    guardian: Keypair,
+    bob: Keypair,
+    token_program: Pubkey,
+    mint: Pubkey,
+    tokens: Pubkey,
+    bob_tokens: Pubkey,
+}
+
+impl TestManager {
+    async fn new() -> Self {
+        Self::with_mint(spl_token::ID, None).await
+    }
+
+    // A Token-2022 mint can also charge `transfer_fee_bps` on every transfer
+    async fn with_mint(token_program: Pubkey, transfer_fee_bps: Option<u16>) -> Self {
+        let mut test = Self::without_config().await;
+        test.token_program = token_program;
+        let payer = test.payer();
+        test.initialize_config(&payer).await.unwrap();
+
+        test.send(
+            &[Instruction {
+                program_id: vault_manager::ID,
+                accounts: vault_manager::accounts::Initialize {
+                    token_account_owner_pda: owner_pda_address(),
+                    signer: payer.pubkey(),
+                    system_program: system_program::ID,
+                    token_program: spl_token::ID,
+                    rent: sysvar::rent::ID,
+                }
+                .to_account_metas(None),
+                data: vault_manager::instruction::Initialize {}.data(),
+            }],
+            &[],
+        )
+        .await
+        .unwrap();
+
+        test.mint = test.create_mint(transfer_fee_bps).await;
+        test.tokens = test.create_token_account(&payer.pubkey()).await;
+        test.bob_tokens = test.create_token_account(&test.bob.pubkey()).await;
+        test.mint_tokens(test.tokens, MINTED).await;
+        test.set_allowed_mint(&payer, MINTED, MINTED).await.unwrap();
+        test
+    }
+
+    async fn without_config() -> Self {
+        let mut program_test =
+            ProgramTest::new("vault_manager", vault_manager::ID, processor!(entry));
+        let bob = Keypair::new();
+        program_test.add_account(
+            bob.pubkey(),
+            Account::new(LAMPORTS_PER_SOL, 0, &system_program::ID),
+        );
+
+        let mut context = program_test.start_with_context().await;
+        // processor! registers the program as a builtin, so stand in the
+        // ProgramData account an upgradeable deploy would have created
+        let program_data = Account::new_data(
+            LAMPORTS_PER_SOL,
+            &UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(context.payer.pubkey()),
+            },
+            &bpf_loader_upgradeable::ID,
+        )
+        .unwrap();
+        context.set_account(&program_data_address(), &program_data.into());
+
+        Self {
+            context,
+            guardian: Keypair::new(),
+            bob,
+            token_program: spl_token::ID,
+            mint: Pubkey::default(),
+            tokens: Pubkey::default(),
+            bob_tokens: Pubkey::default(),
+        }
+    }
+
+    async fn initialize_config(&mut self, admin: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault_manager::ID,
+            accounts: vault_manager::accounts::InitializeConfig {
+                admin: admin.pubkey(),
+                config: config_address(),
+                registry: registry_address(),
+                program_data: program_data_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: vault_manager::instruction::InitializeConfig {
+                guardian: self.guardian.pubkey(),
+            }
+            .data(),
+        };
+        self.send(&[ix], &[admin]).await
+    }
+
+    fn payer(&self) -> Keypair {
+        self.context.payer.insecure_clone()
+    }
+
+    async fn create_mint(&mut self, transfer_fee_bps: Option<u16>) -> Pubkey {
+        let payer = self.payer();
+        let mint = Keypair::new();
+        let mut ixs = vec![];
+        let mut extensions = vec![];
+        if let Some(bps) = transfer_fee_bps {
+            extensions.push(ExtensionType::TransferFeeConfig);
+            ixs.push(
+                transfer_fee::instruction::initialize_transfer_fee_config(
+                    &self.token_program,
+                    &mint.pubkey(),
+                    None,
+                    None,
+                    bps,
+                    u64::MAX,
+                )
+                .unwrap(),
+            );
+        }
+
+        let space =
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extensions)
+                .unwrap();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        ixs.insert(
+            0,
+            system_instruction::create_account(
+                &payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(space),
+                space as u64,
+                &self.token_program,
+            ),
+        );
+        ixs.push(
+            spl_token_2022::instruction::initialize_mint2(
+                &self.token_program,
+                &mint.pubkey(),
+                &payer.pubkey(),
+                None,
+                0,
+            )
+            .unwrap(),
+        );
+        self.send(&ixs, &[&mint]).await.unwrap();
+        mint.pubkey()
+    }
+
+    // Associated token accounts, so Token-2022 sizes them for the mint's extensions
+    async fn create_token_account(&mut self, owner: &Pubkey) -> Pubkey {
+        let ix = create_associated_token_account(
+            &self.payer().pubkey(),
+            owner,
+            &self.mint,
+            &self.token_program,
+        );
+        self.send(&[ix], &[]).await.unwrap();
+        get_associated_token_address_with_program_id(owner, &self.mint, &self.token_program)
+    }
+
+    async fn mint_tokens(&mut self, to: Pubkey, amount: u64) {
+        let ix = spl_token_2022::instruction::mint_to(
+            &self.token_program,
+            &self.mint,
+            &to,
+            &self.payer().pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.send(&[ix], &[]).await.unwrap();
+    }
+
+    async fn create_share_account(&mut self, owner: &Pubkey) {
+        let ix = create_associated_token_account(
+            &self.payer().pubkey(),
+            owner,
+            &share_mint_address(&self.mint),
+            &self.token_program,
+        );
+        self.send(&[ix], &[]).await.unwrap();
+    }
+
+    fn share_account(&self, user: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            user,
+            &share_mint_address(&self.mint),
+            &self.token_program,
+        )
+    }
+
+    async fn deposit(&mut self, amount: u64) -> Result<(), BanksClientError> {
+        let (payer, tokens) = (self.payer(), self.tokens);
+        self.deposit_as(&payer, tokens, amount).await
+    }
+
+    async fn deposit_as(
+        &mut self,
+        user: &Keypair,
+        tokens: Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault_manager::ID,
+            accounts: vault_manager::accounts::Deposit {
+                config: config_address(),
+                registry: registry_address(),
+                token_account_owner_pda: owner_pda_address(),
+                vault: vault_address(&self.mint),
+                vault_state: vault_state_address(&self.mint),
+                fee_account: fee_account_address(&self.mint),
+                share_mint: share_mint_address(&self.mint),
+                share_account: self.share_account(&user.pubkey()),
+                user_deposit: user_deposit_address(&user.pubkey(), &self.mint),
+                signer: user.pubkey(),
+                mint_account: self.mint,
+                sender_token_account: tokens,
+                token_program: self.token_program,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: vault_manager::instruction::Deposit { amount }.data(),
+        };
+        self.send(&[ix], &[user]).await
+    }
+
+    async fn withdraw(&mut self, amount: u64) -> Result<(), BanksClientError> {
+        let (payer, tokens) = (self.payer(), self.tokens);
+        self.withdraw_as(&payer, tokens, amount).await
+    }
+
+    // Burns from `user`'s share account and pays out to `receiver`
+    async fn withdraw_as(
+        &mut self,
+        user: &Keypair,
+        receiver: Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault_manager::ID,
+            accounts: vault_manager::accounts::Withdraw {
+                config: config_address(),
+                token_account_owner_pda: owner_pda_address(),
+                vault: vault_address(&self.mint),
+                vault_state: vault_state_address(&self.mint),
+                fee_account: fee_account_address(&self.mint),
+                share_mint: share_mint_address(&self.mint),
+                share_account: self.share_account(&user.pubkey()),
+                user_deposit: user_deposit_address(&user.pubkey(), &self.mint),
+                receiver_token_account: receiver,
+                signer: user.pubkey(),
+                mint_account: self.mint,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: vault_manager::instruction::Withdraw { amount }.data(),
+        };
+        self.send(&[ix], &[user]).await
+    }
+
+    async fn set_allowed_mint(
+        &mut self,
+        admin: &Keypair,
+        total_cap: u64,
+        user_cap: u64,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault_manager::ID,
+            accounts: vault_manager::accounts::UpdateRegistry {
+                admin: admin.pubkey(),
+                config: config_address(),
+                registry: registry_address(),
+            }
+            .to_account_metas(None),
+            data: vault_manager::instruction::SetAllowedMint {
+                mint: self.mint,
+                total_cap,
+                user_cap,
+            }
+            .data(),
+        };
+        self.send(&[ix], &[admin]).await
+    }
+
+    async fn remove_allowed_mint(&mut self, admin: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault_manager::ID,
+            accounts: vault_manager::accounts::UpdateRegistry {
+                admin: admin.pubkey(),
+                config: config_address(),
+                registry: registry_address(),
+            }
+            .to_account_metas(None),
+            data: vault_manager::instruction::RemoveAllowedMint { mint: self.mint }.data(),
+        };
+        self.send(&[ix], &[admin]).await
+    }
+
+    async fn set_fee(&mut self, admin: &Keypair, fee_bps: u16) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault_manager::ID,
+            accounts: vault_manager::accounts::AdminOnly {
+                admin: admin.pubkey(),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: vault_manager::instruction::SetFee { fee_bps }.data(),
+        };
+        self.send(&[ix], &[admin]).await
+    }
+
+    async fn collect_fees(
+        &mut self,
+        admin: &Keypair,
+        destination: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault_manager::ID,
+            accounts: vault_manager::accounts::CollectFees {
+                admin: admin.pubkey(),
+                config: config_address(),
+                token_account_owner_pda: owner_pda_address(),
+                fee_account: fee_account_address(&self.mint),
+                destination,
+                mint_account: self.mint,
+                token_program: self.token_program,
+            }
+            .to_account_metas(None),
+            data: vault_manager::instruction::CollectFees {}.data(),
+        };
+        self.send(&[ix], &[admin]).await
+    }
+
+    async fn sweep(
+        &mut self,
+        admin: &Keypair,
+        destination: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault_manager::ID,
+            accounts: vault_manager::accounts::Sweep {
+                admin: admin.pubkey(),
+                config: config_address(),
+                token_account_owner_pda: owner_pda_address(),
+                vault: vault_address(&self.mint),
+                vault_state: vault_state_address(&self.mint),
+                share_mint: share_mint_address(&self.mint),
+                destination,
+                mint_account: self.mint,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: vault_manager::instruction::Sweep {}.data(),
+        };
+        self.send(&[ix], &[admin]).await
+    }
+
+    // A vault from before share accounting: tokens, but no shares or VaultState
+    fn add_legacy_vault(&mut self, amount: u64) {
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            mint: self.mint,
+            owner: owner_pda_address(),
+            amount,
+            delegate: COption::None,
+            state: spl_token::state::AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        }
+        .pack_into_slice(&mut data);
+        let vault = Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.context
+            .set_account(&vault_address(&self.mint), &vault.into());
+    }
+
+    async fn pause(&mut self, authority: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault_manager::ID,
+            accounts: vault_manager::accounts::Pause {
+                authority: authority.pubkey(),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: vault_manager::instruction::Pause {}.data(),
+        };
+        self.send(&[ix], &[authority]).await
+    }
+
+    async fn unpause(&mut self, admin: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault_manager::ID,
+            accounts: vault_manager::accounts::AdminOnly {
+                admin: admin.pubkey(),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: vault_manager::instruction::Unpause {}.data(),
+        };
+        self.send(&[ix], &[admin]).await
+    }
+
+    async fn propose_admin(
+        &mut self,
+        admin: &Keypair,
+        new_admin: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault_manager::ID,
+            accounts: vault_manager::accounts::AdminOnly {
+                admin: admin.pubkey(),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: vault_manager::instruction::ProposeAdmin { new_admin }.data(),
+        };
+        self.send(&[ix], &[admin]).await
+    }
+
+    async fn accept_admin(&mut self, new_admin: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: vault_manager::ID,
+            accounts: vault_manager::accounts::AcceptAdmin {
+                new_admin: new_admin.pubkey(),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: vault_manager::instruction::AcceptAdmin {}.data(),
+        };
+        self.send(&[ix], &[new_admin]).await
+    }
+
+    // The payer always signs, `signers` are any others the instructions need
+    async fn send(
+        &mut self,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        // Refresh the blockhash so identical instructions don't dedupe as the same transaction
+        let recent_blockhash = self.context.get_new_latest_blockhash().await?;
+        let payer = self.payer();
+        let mut all_signers = vec![&payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&payer.pubkey()),
+            &all_signers,
+            recent_blockhash,
+        );
+        self.context.banks_client.process_transaction(tx).await
+    }
+
+    async fn user_deposit(&mut self, user: &Pubkey) -> UserDeposit {
+        let account = self
+            .context
+            .banks_client
+            .get_account(user_deposit_address(user, &self.mint))
+            .await
+            .unwrap()
+            .unwrap();
+        UserDeposit::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    async fn token_balance(&mut self, address: Pubkey) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+}
+
+fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[vault_manager::ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+fn config_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &vault_manager::ID).0
+}
+
+fn registry_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"registry"], &vault_manager::ID).0
+}
+
+fn owner_pda_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"VAULT_MANAGER"], &vault_manager::ID).0
+}
+
+fn vault_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"VAULT_MANAGER_PDA_VAULT", mint.as_ref()],
+        &vault_manager::ID,
+    )
+    .0
+}
+
+fn vault_state_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"VAULT_MANAGER_STATE", mint.as_ref()], &vault_manager::ID).0
+}
+
+fn fee_account_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"VAULT_MANAGER_FEES", mint.as_ref()], &vault_manager::ID).0
+}
+
+fn share_mint_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"VAULT_MANAGER_SHARES", mint.as_ref()],
+        &vault_manager::ID,
+    )
+    .0
+}
+
+fn user_deposit_address(user: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"VAULT_MANAGER_DEPOSIT", mint.as_ref(), user.as_ref()],
+        &vault_manager::ID,
+    )
+    .0
+}
+
+fn assert_error(result: Result<(), BanksClientError>, error: VaultError) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, error as u32 + anchor_lang::error::ERROR_CODE_OFFSET)
+        }
+        other => panic!("expected {error:?}, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn deposit_and_withdraw() {
+    deposit_and_withdraw_all(TestManager::new().await).await;
+}
+
+#[tokio::test]
+async fn deposit_and_withdraw_token_2022() {
+    deposit_and_withdraw_all(TestManager::with_mint(spl_token_2022::ID, None).await).await;
+}
+
+async fn deposit_and_withdraw_all(mut test: TestManager) {
+    let payer = test.payer().pubkey();
+    let shares = test.share_account(&payer);
+
+    test.deposit(60).await.unwrap();
+    assert_eq!(test.token_balance(vault_address(&test.mint)).await, 60);
+    // The first deposit mints one share per token
+    assert_eq!(test.token_balance(shares).await, 60);
+
+    test.withdraw(20).await.unwrap();
+    assert_eq!(test.token_balance(shares).await, 40);
+    test.withdraw(40).await.unwrap();
+    assert_eq!(test.token_balance(test.tokens).await, MINTED);
+    assert_eq!(test.token_balance(shares).await, 0);
+
+    let user_deposit = test.user_deposit(&payer).await;
+    assert_eq!(user_deposit.owner, payer);
+    assert_eq!((user_deposit.deposited, user_deposit.withdrawn), (60, 60));
+}
+
+#[tokio::test]
+async fn only_share_holders_withdraw() {
+    let mut test = TestManager::new().await;
+    let (bob, bob_tokens) = (test.bob.insecure_clone(), test.bob_tokens);
+    test.deposit(60).await.unwrap();
+
+    // Bob has no shares, so can't take the payer's deposit
+    test.create_share_account(&bob.pubkey()).await;
+    assert_error(
+        test.withdraw_as(&bob, bob_tokens, 1).await,
+        VaultError::InsufficientShares,
+    );
+
+    // Nor can the depositor take more than their shares are worth
+    assert_error(test.withdraw(61).await, VaultError::InsufficientFunds);
+}
+
+#[tokio::test]
+async fn withdraw_pays_only_the_signer() {
+    let mut test = TestManager::new().await;
+    let bob_tokens = test.bob_tokens;
+    test.deposit(60).await.unwrap();
+
+    let payer = test.payer();
+    let result = test.withdraw_as(&payer, bob_tokens, 10).await;
+    assert!(result.is_err());
+    assert_eq!(test.token_balance(bob_tokens).await, 0);
+}
+
+#[tokio::test]
+async fn shares_follow_their_holder() {
+    let mut test = TestManager::new().await;
+    let (payer, bob) = (test.payer(), test.bob.insecure_clone());
+    let bob_tokens = test.bob_tokens;
+    test.deposit(60).await.unwrap();
+
+    test.create_share_account(&bob.pubkey()).await;
+    let ix = spl_token::instruction::transfer(
+        &spl_token::ID,
+        &test.share_account(&payer.pubkey()),
+        &test.share_account(&bob.pubkey()),
+        &payer.pubkey(),
+        &[],
+        25,
+    )
+    .unwrap();
+    test.send(&[ix], &[]).await.unwrap();
+
+    test.withdraw_as(&bob, bob_tokens, 25).await.unwrap();
+    assert_eq!(test.token_balance(bob_tokens).await, 25);
+    test.withdraw(35).await.unwrap();
+}
+
+#[tokio::test]
+async fn donations_do_not_move_the_share_price() {
+    let mut test = TestManager::new().await;
+    let (payer, bob, bob_tokens) = (test.payer(), test.bob.insecure_clone(), test.bob_tokens);
+    let vault = vault_address(&test.mint);
+    test.mint_tokens(bob_tokens, MINTED).await;
+
+    // Bob takes the first share, then sends the vault far more than anyone deposits
+    test.deposit_as(&bob, bob_tokens, 1).await.unwrap();
+    let ix = spl_token::instruction::transfer(
+        &spl_token::ID,
+        &bob_tokens,
+        &vault,
+        &bob.pubkey(),
+        &[],
+        MINTED - 1,
+    )
+    .unwrap();
+    test.send(&[ix], &[&bob]).await.unwrap();
+
+    // The next depositor still gets one share per token, and can take it all back
+    test.deposit(60).await.unwrap();
+    assert_eq!(
+        test.token_balance(test.share_account(&payer.pubkey()))
+            .await,
+        60
+    );
+    test.withdraw(60).await.unwrap();
+    assert_eq!(test.token_balance(test.tokens).await, MINTED);
+
+    // Bob's share is only worth what he deposited, the donation stays in the vault
+    assert_error(
+        test.withdraw_as(&bob, bob_tokens, 2).await,
+        VaultError::InsufficientShares,
+    );
+    test.withdraw_as(&bob, bob_tokens, 1).await.unwrap();
+    assert_eq!(test.token_balance(vault).await, MINTED - 1);
+
+    // Once every share is burned, tokens left in the vault don't go to whoever deposits next
+    test.deposit(10).await.unwrap();
+    assert_error(test.withdraw(11).await, VaultError::InsufficientShares);
+    test.withdraw(10).await.unwrap();
+}
+
+#[tokio::test]
+async fn transfer_fee_mint_credits_amount_received() {
+    let mut test = TestManager::with_mint(spl_token_2022::ID, Some(TRANSFER_FEE_BPS)).await;
+    let payer = test.payer().pubkey();
+    let vault = vault_address(&test.mint);
+
+    // The fee is withheld in the vault, where it can't be spent
+    test.deposit(MINTED).await.unwrap();
+    assert_eq!(test.token_balance(vault).await, MINTED - 1);
+    assert_eq!(
+        test.token_balance(test.share_account(&payer)).await,
+        MINTED - 1
+    );
+    assert_eq!(test.user_deposit(&payer).await.deposited, MINTED - 1);
+
+    // Withdrawing everything empties the vault, the receiver pays the fee
+    test.withdraw(MINTED - 1).await.unwrap();
+    assert_eq!(test.token_balance(vault).await, 0);
+    assert_eq!(test.token_balance(test.tokens).await, MINTED - 2);
+    assert_eq!(test.token_balance(test.share_account(&payer)).await, 0);
+}
+
+#[tokio::test]
+async fn only_allowed_mints_can_be_deposited() {
+    let mut test = TestManager::new().await;
+    let admin = test.payer();
+    let bob = test.bob.insecure_clone();
+
+    assert_error(
+        test.remove_allowed_mint(&bob).await,
+        VaultError::Unauthorized,
+    );
+    test.remove_allowed_mint(&admin).await.unwrap();
+    assert_error(test.deposit(10).await, VaultError::MintNotAllowed);
+
+    // Listing it again takes effect straight away
+    assert_error(
+        test.set_allowed_mint(&bob, MINTED, MINTED).await,
+        VaultError::Unauthorized,
+    );
+    test.set_allowed_mint(&admin, MINTED, MINTED).await.unwrap();
+    test.deposit(10).await.unwrap();
+
+    // Delisting doesn't stop withdrawals
+    test.remove_allowed_mint(&admin).await.unwrap();
+    test.withdraw(10).await.unwrap();
+}
+
+#[tokio::test]
+async fn deposits_respect_caps() {
+    let mut test = TestManager::new().await;
+    let (admin, bob, bob_tokens) = (test.payer(), test.bob.insecure_clone(), test.bob_tokens);
+    test.mint_tokens(bob_tokens, MINTED).await;
+
+    assert_error(
+        test.set_allowed_mint(&admin, 50, 80).await,
+        VaultError::InvalidCap,
+    );
+    test.set_allowed_mint(&admin, 80, 50).await.unwrap();
+
+    test.deposit(50).await.unwrap();
+    assert_error(test.deposit(1).await, VaultError::UserCapExceeded);
+    assert_error(
+        test.deposit_as(&bob, bob_tokens, 31).await,
+        VaultError::DepositCapExceeded,
+    );
+    test.deposit_as(&bob, bob_tokens, 30).await.unwrap();
+
+    // Withdrawing frees up room under both caps
+    test.withdraw(10).await.unwrap();
+    test.deposit(10).await.unwrap();
+}
+
+#[tokio::test]
+async fn withdrawals_pay_the_fee() {
+    let mut test = TestManager::new().await;
+    let (admin, bob, bob_tokens) = (test.payer(), test.bob.insecure_clone(), test.bob_tokens);
+    let fees = fee_account_address(&test.mint);
+
+    assert_error(test.set_fee(&bob, 100).await, VaultError::Unauthorized);
+    assert_error(test.set_fee(&admin, 1_001).await, VaultError::InvalidFee);
+    test.set_fee(&admin, 100).await.unwrap();
+
+    // 1% of the gross amount goes to the fee account, the shares for all of it are burned
+    test.deposit(MINTED).await.unwrap();
+    test.withdraw(MINTED).await.unwrap();
+    assert_eq!(test.token_balance(test.tokens).await, MINTED - 1);
+    assert_eq!(test.token_balance(fees).await, 1);
+    assert_eq!(test.token_balance(vault_address(&test.mint)).await, 0);
+    assert_eq!(
+        test.token_balance(test.share_account(&admin.pubkey()))
+            .await,
+        0
+    );
+
+    assert_error(
+        test.collect_fees(&bob, bob_tokens).await,
+        VaultError::Unauthorized,
+    );
+    test.collect_fees(&admin, bob_tokens).await.unwrap();
+    assert_eq!(test.token_balance(bob_tokens).await, 1);
+    assert_eq!(test.token_balance(fees).await, 0);
+    assert_error(
+        test.collect_fees(&admin, bob_tokens).await,
+        VaultError::NoFeesToCollect,
+    );
+}
+
+#[tokio::test]
+async fn admin_sweeps_tokens_from_before_share_accounting() {
+    let mut test = TestManager::new().await;
+    let (admin, bob, bob_tokens) = (test.payer(), test.bob.insecure_clone(), test.bob_tokens);
+    let vault = vault_address(&test.mint);
+    test.add_legacy_vault(40);
+
+    // They don't count towards the share price, so the next depositor can't claim them
+    test.deposit(60).await.unwrap();
+    assert_error(test.withdraw(61).await, VaultError::InsufficientShares);
+
+    assert_error(test.sweep(&bob, bob_tokens).await, VaultError::Unauthorized);
+    test.sweep(&admin, bob_tokens).await.unwrap();
+    assert_eq!(test.token_balance(bob_tokens).await, 40);
+    assert_eq!(test.token_balance(vault).await, 60);
+
+    // Deposits are never swept
+    assert_error(
+        test.sweep(&admin, bob_tokens).await,
+        VaultError::NothingToSweep,
+    );
+    test.withdraw(60).await.unwrap();
+    assert_eq!(test.token_balance(test.tokens).await, MINTED);
+}
+
+#[tokio::test]
+async fn sweep_before_any_shares_exist() {
+    let mut test = TestManager::new().await;
+    let (admin, bob_tokens) = (test.payer(), test.bob_tokens);
+    test.add_legacy_vault(40);
+
+    test.sweep(&admin, bob_tokens).await.unwrap();
+    assert_eq!(test.token_balance(bob_tokens).await, 40);
+    assert_eq!(test.token_balance(vault_address(&test.mint)).await, 0);
+}
+
+#[tokio::test]
+async fn only_the_upgrade_authority_initializes_the_config() {
+    let mut test = TestManager::without_config().await;
+    let (bob, payer) = (test.bob.insecure_clone(), test.payer());
+
+    assert_error(test.initialize_config(&bob).await, VaultError::Unauthorized);
+    test.initialize_config(&payer).await.unwrap();
+    test.pause(&payer).await.unwrap();
+}
+
+#[tokio::test]
+async fn guardian_pauses_and_admin_unpauses() {
+    let mut test = TestManager::new().await;
+    let (admin, guardian) = (test.payer(), test.guardian.insecure_clone());
+    test.deposit(60).await.unwrap();
+
+    assert_error(test.pause(&Keypair::new()).await, VaultError::Unauthorized);
+    test.pause(&guardian).await.unwrap();
+
+    assert_error(test.deposit(10).await, VaultError::Paused);
+    assert_error(test.withdraw(10).await, VaultError::Paused);
+
+    // The guardian can only pull the brake
+    assert_error(test.unpause(&guardian).await, VaultError::Unauthorized);
+    test.unpause(&admin).await.unwrap();
+    test.withdraw(10).await.unwrap();
+}
+
+#[tokio::test]
+async fn admin_handover_takes_two_steps() {
+    let mut test = TestManager::new().await;
+    let (admin, new_admin) = (test.payer(), Keypair::new());
+
+    test.propose_admin(&admin, new_admin.pubkey())
+        .await
+        .unwrap();
+    assert_error(
+        test.accept_admin(&Keypair::new()).await,
+        VaultError::Unauthorized,
+    );
+
+    test.accept_admin(&new_admin).await.unwrap();
+    assert_error(test.pause(&admin).await, VaultError::Unauthorized);
+    test.pause(&new_admin).await.unwrap();
+    test.unpause(&new_admin).await.unwrap();
+}
@@ -7,6 +7,7 @@ import {
   Account,
   createMint,
   getAccount,
+  getAssociatedTokenAddressSync,
   getOrCreateAssociatedTokenAccount,
   mintTo,
   setAuthority,
@@ -182,6 +183,28 @@ describe("Vault Manager 💰 🕴️", () => {
       skipPreflight: true,
     };
 
+    // Deposits and withdrawals read the program config, the provider
+    // wallet is its admin and guardian. Only the upgrade authority can create
+    // it, and `[test] upgradeable` in Anchor.toml makes that the wallet.
+    try {
+      await program.methods
+        .initializeConfig(program.provider.publicKey)
+        .accounts({ admin: program.provider.publicKey })
+        .rpc(confirmOptions);
+    } catch (err) {
+      console.log(err);
+    }
+
+    // Only listed mints can be deposited
+    await program.methods
+      .setAllowedMint(
+        mintAlice,
+        new anchor.BN(amountAlice),
+        new anchor.BN(amountAlice)
+      )
+      .accounts({ admin: program.provider.publicKey })
+      .rpc(confirmOptions);
+
     try {
       let initVaultTx = await program.methods
         .initialize()
@@ -217,6 +240,7 @@ describe("Vault Manager 💰 🕴️", () => {
         signer: walletAlice.publicKey,
         mintAccount: mintAlice,
         senderTokenAccount: ataAlice.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([walletAlice])
@@ -238,6 +262,7 @@ describe("Vault Manager 💰 🕴️", () => {
         signer: walletAlice.publicKey,
         mintAccount: mintAlice,
         senderTokenAccount: ataAlice.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([walletAlice])
@@ -280,11 +305,25 @@ describe("Vault Manager 💰 🕴️", () => {
       program.programId
     );
 
+    // Withdrawals burn from the signer's share account, which has no seeds
+    // of its own so it has to be passed in
+    const shareMint = await getVaultPda(
+      program,
+      "VAULT_MANAGER_SHARES",
+      mintAlice
+    );
+    const shareAccount = getAssociatedTokenAddressSync(
+      shareMint.pubkey,
+      walletAlice.publicKey
+    );
+    const sharesBefore = (await getAccount(connection, shareAccount)).amount;
+
     let tx = await program.methods
       .withdraw(new anchor.BN(2))
       .accounts({
         tokenAccountOwnerPda: tokenAccountOwnerPda,
         vault: pda.pubkey,
+        shareAccount: shareAccount,
         receiverTokenAccount: ataAlice.address,
         signer: walletAlice.publicKey,
         mintAccount: mintAlice,
@@ -302,6 +341,13 @@ describe("Vault Manager 💰 🕴️", () => {
     );
     console.log("");
 
+    // Nothing has moved the share price since the deposits, so 2 tokens take 2 shares
+    const sharesAfter = (await getAccount(connection, shareAccount)).amount;
+    expect(sharesBefore - sharesAfter).to.equal(BigInt(2));
+    expect((await getAccount(connection, ataAlice.address)).amount).to.equal(
+      BigInt(amountAlice - 5 + 2)
+    );
+
     try {
       const tokenAccountInfo = await getAccount(connection, ataAlice.address);
 
@@ -0,0 +1,28 @@
+use solana_program::program_error::ProgramError;
+use tic_tac_toe_core::RuleError;
+
+// Same order as the Anchor program's errors, minus the ones for wagers and profiles
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TicTacToeError {
+    TileOutOfBounds,
+    TileAlreadySet,
+    GameAlreadyOver,
+    NotPlayersTurn,
+    InvalidBoardConfig,
+}
+
+impl From<TicTacToeError> for ProgramError {
+    fn from(error: TicTacToeError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+impl From<RuleError> for TicTacToeError {
+    fn from(error: RuleError) -> Self {
+        match error {
+            RuleError::InvalidBoardConfig => TicTacToeError::InvalidBoardConfig,
+            RuleError::TileOutOfBounds => TicTacToeError::TileOutOfBounds,
+            RuleError::TileAlreadySet => TicTacToeError::TileAlreadySet,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+};
+use tic_tac_toe_core::Tile;
+
+use crate::{
+    error::TicTacToeError,
+    state::{Game, GameState},
+};
+
+pub fn process(accounts: &[AccountInfo], row: u8, column: u8) -> ProgramResult {
+    let [player, game] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !player.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Only this program can have written a game, so ownership is enough to trust it
+    if game.owner.ne(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let mut game_data = Game::deserialize(&mut &game.try_borrow_data()?[..])?;
+
+    if game_data.state != GameState::Active {
+        return Err(TicTacToeError::GameAlreadyOver.into());
+    }
+
+    if game_data.current_player().ne(player.key) {
+        return Err(TicTacToeError::NotPlayersTurn.into());
+    }
+
+    game_data
+        .play(Tile { row, column })
+        .map_err(TicTacToeError::from)?;
+
+    game_data.serialize(&mut &mut game.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program::invoke_signed,
+    program_error::ProgramError, pubkey::Pubkey, rent::Rent, system_instruction::create_account,
+    sysvar::Sysvar,
+};
+
+use crate::{error::TicTacToeError, state::Game};
+
+// Player one opens a game against player two at [b"game", player_one, player_two],
+// paying for an account sized for the board
+pub fn process(
+    accounts: &[AccountInfo],
+    player_two: Pubkey,
+    board_size: u8,
+    win_length: u8,
+) -> ProgramResult {
+    let [player_one, game, system_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !player_one.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (game_pda, bump) = Pubkey::find_program_address(
+        &[
+            Game::SEED_PREFIX,
+            player_one.key.as_ref(),
+            player_two.as_ref(),
+        ],
+        &crate::ID,
+    );
+    if game.key.ne(&game_pda) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let game_data = Game::new([*player_one.key, player_two], board_size, win_length, bump)
+        .map_err(TicTacToeError::from)?;
+
+    let space = Game::size(board_size);
+    invoke_signed(
+        &create_account(
+            player_one.key,
+            game.key,
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &crate::ID,
+        ),
+        &[player_one.clone(), game.clone(), system_program.clone()],
+        &[&[
+            Game::SEED_PREFIX,
+            player_one.key.as_ref(),
+            player_two.as_ref(),
+            &[bump],
+        ]],
+    )?;
+
+    game_data.serialize(&mut &mut game.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+#[cfg(not(feature = "no-entrypoint"))]
+use solana_program::entrypoint;
+
+pub mod error;
+pub mod instructions;
+pub mod processor;
+pub mod state;
+
+#[cfg(not(feature = "no-entrypoint"))]
+use processor::process_instructions;
+
+#[cfg(not(feature = "no-entrypoint"))]
+entrypoint!(process_instructions);
+
+solana_program::declare_id!("EwDuAVJULkJsyg4Y1RTV38aEduyeJkCX4NvqMdVGtP9E");
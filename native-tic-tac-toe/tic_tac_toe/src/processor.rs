@@ -0,0 +1,41 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::instructions::*;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum Instructions {
+    SetupGame {
+        player_two: Pubkey,
+        board_size: u8,
+        win_length: u8,
+    },
+    Play {
+        row: u8,
+        column: u8,
+    },
+}
+
+pub fn process_instructions(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    if program_id.ne(&crate::ID) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let instruction = Instructions::try_from_slice(instruction_data)?;
+
+    match instruction {
+        Instructions::SetupGame {
+            player_two,
+            board_size,
+            win_length,
+        } => setup_game::process(accounts, player_two, board_size, win_length),
+        Instructions::Play { row, column } => play::process(accounts, row, column),
+    }
+}
@@ -0,0 +1,67 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+use tic_tac_toe_core::{self as rules, Grid, Outcome, RuleError, Tile};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub enum GameState {
+    Active,
+    Tie,
+    Won { winner: Pubkey },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct Game {
+    pub players: [Pubkey; 2], // 32*2
+    pub turn: u8,             // 1
+    pub board_size: u8,       // 1
+    pub win_length: u8,       // 1
+    pub state: GameState,     // 1 + 32
+    pub bump: u8,             // 1
+    pub board: Vec<u8>,       // 4 + board_size^2, row by row, see tic_tac_toe_core::Grid
+}
+
+impl Game {
+    pub const SEED_PREFIX: &'static [u8] = b"game";
+
+    pub fn size(board_size: u8) -> usize {
+        (32 * 2) + 1 + 1 + 1 + (1 + 32) + 1 + 4 + rules::tile_count(board_size)
+    }
+
+    pub fn new(
+        players: [Pubkey; 2],
+        board_size: u8,
+        win_length: u8,
+        bump: u8,
+    ) -> Result<Self, RuleError> {
+        rules::validate_board(board_size, win_length)?;
+
+        Ok(Self {
+            players,
+            turn: 1,
+            board_size,
+            win_length,
+            state: GameState::Active,
+            bump,
+            board: vec![Grid::EMPTY; rules::tile_count(board_size)],
+        })
+    }
+
+    pub fn current_player(&self) -> Pubkey {
+        self.players[rules::Sign::for_turn(self.turn).player_index()]
+    }
+
+    pub fn play(&mut self, tile: Tile) -> Result<(), RuleError> {
+        let mut grid = Grid::new(&mut self.board, self.board_size, self.win_length)?;
+
+        match rules::play(&mut grid, self.turn, tile)? {
+            Outcome::Active => self.turn += 1,
+            Outcome::Tie => self.state = GameState::Tie,
+            Outcome::Won(sign) => {
+                self.state = GameState::Won {
+                    winner: self.players[sign.player_index()],
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+use borsh::BorshDeserialize;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+use tic_tac_toe::{
+    error::TicTacToeError,
+    processor::{process_instructions, Instructions},
+    state::{Game, GameState},
+};
+
+struct TestGame {
+    context: ProgramTestContext,
+    player_one: Keypair,
+    player_two: Keypair,
+    game: Pubkey,
+}
+
+impl TestGame {
+    async fn new() -> Self {
+        let program_test = ProgramTest::new(
+            "tic_tac_toe",
+            tic_tac_toe::ID,
+            processor!(process_instructions),
+        );
+        let context = program_test.start_with_context().await;
+        let player_one = context.payer.insecure_clone();
+        let player_two = Keypair::new();
+        let (game, _) = Pubkey::find_program_address(
+            &[
+                Game::SEED_PREFIX,
+                player_one.pubkey().as_ref(),
+                player_two.pubkey().as_ref(),
+            ],
+            &tic_tac_toe::ID,
+        );
+
+        Self {
+            context,
+            player_one,
+            player_two,
+            game,
+        }
+    }
+
+    async fn send(
+        &mut self,
+        instruction: Instructions,
+        accounts: Vec<AccountMeta>,
+        signer: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let instruction = Instruction::new_with_bytes(
+            tic_tac_toe::ID,
+            &borsh::to_vec(&instruction).unwrap(),
+            accounts,
+        );
+        let payer = self.context.payer.insecure_clone();
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[&payer, signer],
+            blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    async fn setup_game(&mut self, board_size: u8, win_length: u8) -> Result<(), BanksClientError> {
+        let player_one = self.player_one.insecure_clone();
+        let accounts = vec![
+            AccountMeta::new(player_one.pubkey(), true),
+            AccountMeta::new(self.game, false),
+            AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+        ];
+        self.send(
+            Instructions::SetupGame {
+                player_two: self.player_two.pubkey(),
+                board_size,
+                win_length,
+            },
+            accounts,
+            &player_one,
+        )
+        .await
+    }
+
+    async fn play(
+        &mut self,
+        player: &Keypair,
+        row: u8,
+        column: u8,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vec![
+            AccountMeta::new_readonly(player.pubkey(), true),
+            AccountMeta::new(self.game, false),
+        ];
+        self.send(Instructions::Play { row, column }, accounts, player)
+            .await
+    }
+
+    async fn play_moves(&mut self, moves: &[(u8, u8)]) {
+        let players = [
+            self.player_one.insecure_clone(),
+            self.player_two.insecure_clone(),
+        ];
+        for (i, (row, column)) in moves.iter().enumerate() {
+            self.play(&players[i % 2], *row, *column).await.unwrap();
+        }
+    }
+
+    async fn game(&mut self) -> Game {
+        let account = self
+            .context
+            .banks_client
+            .get_account(self.game)
+            .await
+            .unwrap()
+            .unwrap();
+        Game::deserialize(&mut &account.data[..]).unwrap()
+    }
+}
+
+fn assert_error(result: Result<(), BanksClientError>, expected: TicTacToeError) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, expected as u32)
+        }
+        error => panic!("unexpected error: {error:?}"),
+    }
+}
+
+#[tokio::test]
+async fn setup_game_sizes_the_board() {
+    let mut test = TestGame::new().await;
+    test.setup_game(5, 4).await.unwrap();
+
+    let game = test.game().await;
+    assert_eq!(
+        game.players,
+        [test.player_one.pubkey(), test.player_two.pubkey()]
+    );
+    assert_eq!(game.turn, 1);
+    assert_eq!(game.state, GameState::Active);
+    assert_eq!(game.board, vec![0; 25]);
+    // An active game leaves room for the winner's key
+    assert_eq!(borsh::to_vec(&game).unwrap().len() + 32, Game::size(5));
+}
+
+#[tokio::test]
+async fn rejects_invalid_board_config() {
+    let mut test = TestGame::new().await;
+
+    assert_error(
+        test.setup_game(3, 4).await,
+        TicTacToeError::InvalidBoardConfig,
+    );
+}
+
+#[tokio::test]
+async fn player_one_wins() {
+    let mut test = TestGame::new().await;
+    test.setup_game(3, 3).await.unwrap();
+
+    // X X X
+    // O O .
+    // . . .
+    test.play_moves(&[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)])
+        .await;
+
+    assert_eq!(
+        test.game().await.state,
+        GameState::Won {
+            winner: test.player_one.pubkey()
+        }
+    );
+
+    let player_two = test.player_two.insecure_clone();
+    assert_error(
+        test.play(&player_two, 2, 2).await,
+        TicTacToeError::GameAlreadyOver,
+    );
+}
+
+#[tokio::test]
+async fn tie() {
+    let mut test = TestGame::new().await;
+    test.setup_game(3, 3).await.unwrap();
+
+    // X O X
+    // X O O
+    // O X X
+    test.play_moves(&[
+        (0, 0),
+        (0, 1),
+        (0, 2),
+        (1, 1),
+        (1, 0),
+        (1, 2),
+        (2, 1),
+        (2, 0),
+        (2, 2),
+    ])
+    .await;
+
+    assert_eq!(test.game().await.state, GameState::Tie);
+}
+
+#[tokio::test]
+async fn enforces_turns_and_tiles() {
+    let mut test = TestGame::new().await;
+    test.setup_game(3, 3).await.unwrap();
+    let player_one = test.player_one.insecure_clone();
+    let player_two = test.player_two.insecure_clone();
+
+    assert_error(
+        test.play(&player_two, 0, 0).await,
+        TicTacToeError::NotPlayersTurn,
+    );
+
+    test.play(&player_one, 1, 1).await.unwrap();
+    assert_error(
+        test.play(&player_two, 1, 1).await,
+        TicTacToeError::TileAlreadySet,
+    );
+    assert_error(
+        test.play(&player_two, 3, 0).await,
+        TicTacToeError::TileOutOfBounds,
+    );
+
+    test.play(&player_two, 0, 0).await.unwrap();
+    assert_eq!(test.game().await.turn, 3);
+}
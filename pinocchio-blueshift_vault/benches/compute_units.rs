@@ -0,0 +1,66 @@
+use mollusk_svm::{program, Mollusk};
+use mollusk_svm_bencher::MolluskComputeUnitBencher;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+};
+
+const PROGRAM: Pubkey = Pubkey::new_from_array(pinocchio_blueshift_vault::ID);
+
+const OWNER: Pubkey = Pubkey::new_from_array([1; 32]);
+
+fn vault_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", OWNER.as_ref()], &PROGRAM)
+}
+
+// Move `amount` lamports into or out of a vault holding `vault_lamports`
+fn transfer(
+    discriminator: u8,
+    amount: u64,
+    owner_lamports: u64,
+    vault_lamports: u64,
+) -> (Instruction, Vec<(Pubkey, Account)>) {
+    let (vault, bump) = vault_address();
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+
+    let mut data = vec![discriminator];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(bump);
+
+    let instruction = Instruction::new_with_bytes(
+        PROGRAM,
+        &data,
+        vec![
+            AccountMeta::new(OWNER, true),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(system_program, false),
+        ],
+    );
+    let accounts = vec![
+        (OWNER, Account::new(owner_lamports, 0, &system_program)),
+        (vault, Account::new(vault_lamports, 0, &system_program)),
+        (system_program, system_account),
+    ];
+    (instruction, accounts)
+}
+
+fn main() {
+    let mollusk = Mollusk::new(&PROGRAM, "pinocchio_blueshift_vault");
+
+    let (deposit, deposit_accounts) = transfer(0, LAMPORTS_PER_SOL, 10 * LAMPORTS_PER_SOL, 0);
+    let (top_up, top_up_accounts) =
+        transfer(0, LAMPORTS_PER_SOL, 10 * LAMPORTS_PER_SOL, LAMPORTS_PER_SOL);
+    let (withdraw, withdraw_accounts) = transfer(1, LAMPORTS_PER_SOL / 2, 0, LAMPORTS_PER_SOL);
+    let (withdraw_all, withdraw_all_accounts) = transfer(1, LAMPORTS_PER_SOL, 0, LAMPORTS_PER_SOL);
+
+    MolluskComputeUnitBencher::new(mollusk)
+        .bench(("deposit", &deposit, &deposit_accounts))
+        .bench(("deposit_top_up", &top_up, &top_up_accounts))
+        .bench(("withdraw_partial", &withdraw, &withdraw_accounts))
+        .bench(("withdraw_all", &withdraw_all, &withdraw_all_accounts))
+        .must_pass(true)
+        .out_dir("benches")
+        .execute();
+}
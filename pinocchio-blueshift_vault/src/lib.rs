@@ -7,11 +7,15 @@ use pinocchio::{
     instruction::{Seed, Signer},
     nostd_panic_handler,
     program_error::ProgramError,
-    pubkey::{find_program_address, Pubkey},
+    pubkey::{create_program_address, Pubkey},
+    sysvars::{rent::Rent, Sysvar},
     ProgramResult,
 };
 use pinocchio_system::instructions::Transfer;
 
+mod token;
+pub use token::*;
+
 entrypoint!(process_instruction);
 nostd_panic_handler!();
 
@@ -27,13 +31,33 @@ fn process_instruction(
 ) -> ProgramResult {
     match instruction_data.split_first() {
         Some((Deposit::DISCRIMINATOR, data)) => Deposit::try_from((data, accounts))?.process(),
-        Some((Withdraw::DISCRIMINATOR, _)) => Withdraw::try_from(accounts)?.process(),
+        Some((Withdraw::DISCRIMINATOR, data)) => Withdraw::try_from((data, accounts))?.process(),
+        Some((DepositToken::DISCRIMINATOR, data)) => {
+            DepositToken::try_from((data, accounts))?.process()
+        }
+        Some((WithdrawToken::DISCRIMINATOR, data)) => {
+            WithdrawToken::try_from((data, accounts))?.process()
+        }
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
 
+// Every instruction takes the vault's bump after the amount, and only checks
+// it with create_program_address instead of searching for it with
+// find_program_address, which costs far more compute. A non-canonical bump
+// just addresses another vault that only the same owner can sign for.
+pub fn check_vault(vault: &AccountInfo, seeds: &[&[u8]]) -> ProgramResult {
+    let vault_key =
+        create_program_address(seeds, &crate::ID).map_err(|_| ProgramError::InvalidSeeds)?;
+    if vault.key().ne(&vault_key) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(())
+}
+
 // Create an empty vault PDA for the caller if it doesn’t exist.
-// Move lamports from the signer to that vault.
+// Move lamports from the signer to that vault, topping it up if it already exists.
+// The first deposit has to cover the vault's rent-exempt minimum.
 
 pub struct DepositAccounts<'a> {
     pub owner: &'a AccountInfo,
@@ -57,15 +81,6 @@ impl<'a> TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
             return Err(ProgramError::InvalidAccountOwner);
         }
 
-        if vault.lamports().ne(&0) {
-            return Err(ProgramError::InvalidAccountData);
-        }
-
-        let (vault_key, _) = find_program_address(&[b"vault", owner.key()], &crate::ID);
-        if vault.key().ne(&vault_key) {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-
         // Return the accounts
         Ok(Self { owner, vault })
     }
@@ -73,24 +88,31 @@ impl<'a> TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
 
 pub struct DepositInstructionData {
     pub amount: u64,
+    pub bump: [u8; 1],
 }
 
 impl<'a> TryFrom<&'a [u8]> for DepositInstructionData {
     type Error = ProgramError;
 
     fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
-        if data.len() != size_of::<u64>() {
+        let [amount @ .., bump] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        if amount.len() != size_of::<u64>() {
             return Err(ProgramError::InvalidInstructionData);
         }
 
-        let amount = u64::from_le_bytes(data.try_into().unwrap());
+        let amount = u64::from_le_bytes(amount.try_into().unwrap());
 
         // Instruction Checks
         if amount.eq(&0) {
             return Err(ProgramError::InvalidInstructionData);
         }
 
-        Ok(Self { amount })
+        Ok(Self {
+            amount,
+            bump: [*bump],
+        })
     }
 }
 
@@ -106,6 +128,11 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Deposit<'a> {
         let accounts = DepositAccounts::try_from(accounts)?;
         let instruction_datas: DepositInstructionData = DepositInstructionData::try_from(data)?;
 
+        check_vault(
+            accounts.vault,
+            &[b"vault", accounts.owner.key(), &instruction_datas.bump],
+        )?;
+
         Ok(Self {
             accounts,
             instruction_datas,
@@ -117,6 +144,16 @@ impl<'a> Deposit<'a> {
     pub const DISCRIMINATOR: &'a u8 = &0;
 
     pub fn process(&mut self) -> ProgramResult {
+        let balance = self
+            .accounts
+            .vault
+            .lamports()
+            .checked_add(self.instruction_datas.amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if balance < Rent::get()?.minimum_balance(0) {
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+
         Transfer {
             from: self.accounts.owner,
             to: self.accounts.vault,
@@ -131,7 +168,6 @@ impl<'a> Deposit<'a> {
 pub struct WithdrawAccounts<'a> {
     pub owner: &'a AccountInfo,
     pub vault: &'a AccountInfo,
-    pub bumps: [u8; 1],
 }
 
 // Perform sanity checks on the accounts
@@ -152,30 +188,63 @@ impl<'a> TryFrom<&'a [AccountInfo]> for WithdrawAccounts<'a> {
             return Err(ProgramError::InvalidAccountOwner);
         }
 
-        let (vault_key, bump) = find_program_address(&[b"vault", owner.key().as_ref()], &crate::ID);
-        if &vault_key != vault.key() {
-            return Err(ProgramError::InvalidAccountOwner);
+        Ok(Self { owner, vault })
+    }
+}
+
+pub struct WithdrawInstructionData {
+    pub amount: u64,
+    pub bump: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [u8]> for WithdrawInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let [amount @ .., bump] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        if amount.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(amount.try_into().unwrap());
+
+        // Instruction Checks
+        if amount.eq(&0) {
+            return Err(ProgramError::InvalidInstructionData);
         }
 
         Ok(Self {
-            owner,
-            vault,
-            bumps: [bump],
+            amount,
+            bump: [*bump],
         })
     }
 }
 
+// Move `amount` lamports from the vault back to the owner. The vault has to
+// stay rent-exempt, or be emptied completely.
 pub struct Withdraw<'a> {
     pub accounts: WithdrawAccounts<'a>,
+    pub instruction_datas: WithdrawInstructionData,
 }
 
-impl<'a> TryFrom<&'a [AccountInfo]> for Withdraw<'a> {
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Withdraw<'a> {
     type Error = ProgramError;
 
-    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
         let accounts = WithdrawAccounts::try_from(accounts)?;
+        let instruction_datas = WithdrawInstructionData::try_from(data)?;
+
+        check_vault(
+            accounts.vault,
+            &[b"vault", accounts.owner.key(), &instruction_datas.bump],
+        )?;
 
-        Ok(Self { accounts })
+        Ok(Self {
+            accounts,
+            instruction_datas,
+        })
     }
 }
 
@@ -183,18 +252,28 @@ impl<'a> Withdraw<'a> {
     pub const DISCRIMINATOR: &'a u8 = &1;
 
     pub fn process(&mut self) -> ProgramResult {
+        let remaining = self
+            .accounts
+            .vault
+            .lamports()
+            .checked_sub(self.instruction_datas.amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        if remaining.ne(&0) && remaining < Rent::get()?.minimum_balance(0) {
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+
         // Create signer seeds for our CPI
         let seeds = [
             Seed::from(b"vault"),
             Seed::from(self.accounts.owner.key().as_ref()),
-            Seed::from(&self.accounts.bumps),
+            Seed::from(&self.instruction_datas.bump),
         ];
         let signers = [Signer::from(&seeds)];
 
         Transfer {
             from: self.accounts.vault,
             to: self.accounts.owner,
-            lamports: self.accounts.vault.lamports(),
+            lamports: self.instruction_datas.amount,
         }
         .invoke_signed(&signers)?;
 
@@ -0,0 +1,254 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::{self as system, Allocate, Assign, CreateAccount};
+use pinocchio_token::{
+    instructions::{InitializeAccount3, Transfer},
+    state::TokenAccount,
+};
+
+use crate::{check_vault, DepositInstructionData, WithdrawInstructionData};
+
+// Token vaults are token accounts at [b"vault", owner, mint] that are their own
+// authority, so only this program can move tokens out of them.
+
+// Create the token vault for the caller and mint if it doesn’t exist.
+// Move tokens from the owner's token account into that vault.
+
+pub struct DepositTokenAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub owner_token_account: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for DepositTokenAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, vault, owner_token_account, mint, token_program, _system_program] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Accounts Checks
+        if !owner.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if token_program.key().ne(&pinocchio_token::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if !mint.is_owned_by(&pinocchio_token::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // Either an existing vault, or an empty address to create it at
+        if !vault.is_owned_by(&pinocchio_token::ID) && !vault.is_owned_by(&pinocchio_system::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self {
+            owner,
+            vault,
+            owner_token_account,
+            mint,
+        })
+    }
+}
+
+pub struct DepositToken<'a> {
+    pub accounts: DepositTokenAccounts<'a>,
+    pub instruction_datas: DepositInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for DepositToken<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = DepositTokenAccounts::try_from(accounts)?;
+        let instruction_datas = DepositInstructionData::try_from(data)?;
+
+        check_vault(
+            accounts.vault,
+            &[
+                b"vault",
+                accounts.owner.key(),
+                accounts.mint.key(),
+                &instruction_datas.bump,
+            ],
+        )?;
+
+        Ok(Self {
+            accounts,
+            instruction_datas,
+        })
+    }
+}
+
+impl<'a> DepositToken<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &2;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if self.accounts.vault.is_owned_by(&pinocchio_system::ID) {
+            let seeds = [
+                Seed::from(b"vault"),
+                Seed::from(self.accounts.owner.key().as_ref()),
+                Seed::from(self.accounts.mint.key().as_ref()),
+                Seed::from(&self.instruction_datas.bump),
+            ];
+            let signers = [Signer::from(&seeds)];
+            let rent_exempt = Rent::get()?.minimum_balance(TokenAccount::LEN);
+
+            if self.accounts.vault.lamports().eq(&0) {
+                CreateAccount {
+                    from: self.accounts.owner,
+                    to: self.accounts.vault,
+                    lamports: rent_exempt,
+                    space: TokenAccount::LEN as u64,
+                    owner: &pinocchio_token::ID,
+                }
+                .invoke_signed(&signers)?;
+            } else {
+                // Anyone can send lamports to the address first, which makes
+                // CreateAccount fail, so top it up and take it over instead
+                let shortfall = rent_exempt.saturating_sub(self.accounts.vault.lamports());
+                if shortfall > 0 {
+                    system::Transfer {
+                        from: self.accounts.owner,
+                        to: self.accounts.vault,
+                        lamports: shortfall,
+                    }
+                    .invoke()?;
+                }
+
+                Allocate {
+                    account: self.accounts.vault,
+                    space: TokenAccount::LEN as u64,
+                }
+                .invoke_signed(&signers)?;
+
+                Assign {
+                    account: self.accounts.vault,
+                    owner: &pinocchio_token::ID,
+                }
+                .invoke_signed(&signers)?;
+            }
+
+            InitializeAccount3 {
+                account: self.accounts.vault,
+                mint: self.accounts.mint,
+                owner: self.accounts.vault.key(),
+            }
+            .invoke()?;
+        }
+
+        // The token program checks both accounts are for the same mint
+        Transfer {
+            from: self.accounts.owner_token_account,
+            to: self.accounts.vault,
+            authority: self.accounts.owner,
+            amount: self.instruction_datas.amount,
+        }
+        .invoke()?;
+
+        Ok(())
+    }
+}
+
+pub struct WithdrawTokenAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub owner_token_account: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for WithdrawTokenAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, vault, owner_token_account, mint, token_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Basic Accounts Checks
+        if !owner.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if token_program.key().ne(&pinocchio_token::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if !vault.is_owned_by(&pinocchio_token::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self {
+            owner,
+            vault,
+            owner_token_account,
+            mint,
+        })
+    }
+}
+
+// Move `amount` tokens from the vault back to a token account of the owner's choosing.
+pub struct WithdrawToken<'a> {
+    pub accounts: WithdrawTokenAccounts<'a>,
+    pub instruction_datas: WithdrawInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for WithdrawToken<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = WithdrawTokenAccounts::try_from(accounts)?;
+        let instruction_datas = WithdrawInstructionData::try_from(data)?;
+
+        check_vault(
+            accounts.vault,
+            &[
+                b"vault",
+                accounts.owner.key(),
+                accounts.mint.key(),
+                &instruction_datas.bump,
+            ],
+        )?;
+
+        Ok(Self {
+            accounts,
+            instruction_datas,
+        })
+    }
+}
+
+impl<'a> WithdrawToken<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &3;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let seeds = [
+            Seed::from(b"vault"),
+            Seed::from(self.accounts.owner.key().as_ref()),
+            Seed::from(self.accounts.mint.key().as_ref()),
+            Seed::from(&self.instruction_datas.bump),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        // The token program checks the vault holds enough
+        Transfer {
+            from: self.accounts.vault,
+            to: self.accounts.owner_token_account,
+            authority: self.accounts.vault,
+            amount: self.instruction_datas.amount,
+        }
+        .invoke_signed(&signers)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,14 @@
+use mollusk_svm::Mollusk;
+use solana_sdk::pubkey::Pubkey;
+
+// The Mollusk tests run the compiled program, so they're #[ignore]d to keep a
+// plain `cargo test` working. Build the program and run them with:
+// cargo build-sbf && SBF_OUT_DIR=target/deploy cargo test -- --ignored
+
+pub const PROGRAM: Pubkey = Pubkey::new_from_array(pinocchio_blueshift_vault::ID);
+
+pub const OWNER: Pubkey = Pubkey::new_from_array([1; 32]);
+
+pub fn mollusk() -> Mollusk {
+    Mollusk::new(&PROGRAM, "pinocchio_blueshift_vault")
+}
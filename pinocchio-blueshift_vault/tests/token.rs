@@ -0,0 +1,291 @@
+mod common;
+
+use common::{OWNER, PROGRAM};
+use mollusk_svm::{program, result::Check, Mollusk};
+use mollusk_svm_programs_token::token;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use spl_token::{
+    error::TokenError,
+    state::{Account as TokenAccount, AccountState, Mint},
+};
+
+const OWNER_TOKENS: Pubkey = Pubkey::new_from_array([2; 32]);
+const MINT: Pubkey = Pubkey::new_from_array([3; 32]);
+const OTHER_MINT: Pubkey = Pubkey::new_from_array([4; 32]);
+
+const STARTING_TOKENS: u64 = 1_000;
+
+fn mollusk() -> Mollusk {
+    let mut mollusk = common::mollusk();
+    token::add_program(&mut mollusk);
+    mollusk
+}
+
+fn vault_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", OWNER.as_ref(), mint.as_ref()], &PROGRAM)
+}
+
+fn mint_account() -> Account {
+    token::create_account_for_mint(Mint {
+        decimals: 6,
+        is_initialized: true,
+        supply: STARTING_TOKENS,
+        ..Mint::default()
+    })
+}
+
+fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+    token::create_account_for_token_account(TokenAccount {
+        mint,
+        owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    })
+}
+
+fn token_balance(account: &Account) -> u64 {
+    TokenAccount::unpack(&account.data).unwrap().amount
+}
+
+// A token vault for MINT, `vault` being its current account
+fn deposit(amount: u64, vault: Account) -> (Instruction, Vec<(Pubkey, Account)>) {
+    let (vault_key, bump) = vault_address(&MINT);
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+
+    let mut data = vec![2];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(bump);
+
+    let instruction = Instruction::new_with_bytes(
+        PROGRAM,
+        &data,
+        vec![
+            AccountMeta::new(OWNER, true),
+            AccountMeta::new(vault_key, false),
+            AccountMeta::new(OWNER_TOKENS, false),
+            AccountMeta::new_readonly(MINT, false),
+            AccountMeta::new_readonly(token::ID, false),
+            AccountMeta::new_readonly(system_program, false),
+        ],
+    );
+    let accounts = vec![
+        (OWNER, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+        (vault_key, vault),
+        (OWNER_TOKENS, token_account(MINT, OWNER, STARTING_TOKENS)),
+        (MINT, mint_account()),
+        token::keyed_account(),
+        (system_program, system_account),
+    ];
+    (instruction, accounts)
+}
+
+// Out of MINT's vault, which holds `vault_tokens`
+fn withdraw(amount: u64, vault_tokens: u64) -> (Instruction, Vec<(Pubkey, Account)>) {
+    let (vault_key, bump) = vault_address(&MINT);
+
+    let mut data = vec![3];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(bump);
+
+    let instruction = Instruction::new_with_bytes(
+        PROGRAM,
+        &data,
+        vec![
+            AccountMeta::new(OWNER, true),
+            AccountMeta::new(vault_key, false),
+            AccountMeta::new(OWNER_TOKENS, false),
+            AccountMeta::new_readonly(MINT, false),
+            AccountMeta::new_readonly(token::ID, false),
+        ],
+    );
+    let accounts = vec![
+        (
+            OWNER,
+            Account::new(LAMPORTS_PER_SOL, 0, &solana_sdk::system_program::ID),
+        ),
+        (vault_key, token_account(MINT, vault_key, vault_tokens)),
+        (OWNER_TOKENS, token_account(MINT, OWNER, 0)),
+        (MINT, mint_account()),
+        token::keyed_account(),
+    ];
+    (instruction, accounts)
+}
+
+fn empty_vault() -> Account {
+    Account::new(0, 0, &solana_sdk::system_program::ID)
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn first_deposit_creates_the_vault() {
+    let (instruction, accounts) = deposit(400, empty_vault());
+    let (vault, _) = vault_address(&MINT);
+
+    let result = mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[
+            Check::success(),
+            Check::account(&vault)
+                .owner(&token::ID)
+                .space(TokenAccount::LEN)
+                .rent_exempt()
+                .build(),
+        ],
+    );
+
+    let vault_account = result.get_account(&vault).unwrap();
+    let state = TokenAccount::unpack(&vault_account.data).unwrap();
+    assert_eq!((state.mint, state.owner, state.amount), (MINT, vault, 400));
+    assert_eq!(
+        token_balance(result.get_account(&OWNER_TOKENS).unwrap()),
+        STARTING_TOKENS - 400
+    );
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn deposit_tops_up_an_existing_vault() {
+    let (vault, _) = vault_address(&MINT);
+    let (instruction, accounts) = deposit(400, token_account(MINT, vault, 100));
+
+    let result =
+        mollusk().process_and_validate_instruction(&instruction, &accounts, &[Check::success()]);
+
+    assert_eq!(token_balance(result.get_account(&vault).unwrap()), 500);
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn deposit_takes_over_a_prefunded_vault_address() {
+    // Someone sent lamports to the vault address before the first deposit
+    let (instruction, accounts) = deposit(400, Account::new(1, 0, &solana_sdk::system_program::ID));
+    let (vault, _) = vault_address(&MINT);
+
+    let result = mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[
+            Check::success(),
+            Check::account(&vault)
+                .owner(&token::ID)
+                .lamports(Rent::default().minimum_balance(TokenAccount::LEN))
+                .build(),
+        ],
+    );
+
+    assert_eq!(token_balance(result.get_account(&vault).unwrap()), 400);
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn deposit_rejects_another_mints_token_account() {
+    let (instruction, mut accounts) = deposit(400, empty_vault());
+    accounts[2].1 = token_account(OTHER_MINT, OWNER, STARTING_TOKENS);
+
+    mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[Check::err(ProgramError::Custom(
+            TokenError::MintMismatch as u32,
+        ))],
+    );
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn deposit_rejects_someone_elses_token_account() {
+    let (instruction, mut accounts) = deposit(400, empty_vault());
+    accounts[2].1 = token_account(MINT, Pubkey::new_unique(), STARTING_TOKENS);
+
+    mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[Check::err(ProgramError::Custom(
+            TokenError::OwnerMismatch as u32,
+        ))],
+    );
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn deposit_needs_the_owner_to_sign() {
+    let (mut instruction, accounts) = deposit(400, empty_vault());
+    instruction.accounts[0].is_signer = false;
+
+    mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[Check::err(ProgramError::InvalidAccountOwner)],
+    );
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn withdraw_returns_tokens_to_the_owner() {
+    let (instruction, accounts) = withdraw(300, 500);
+    let (vault, _) = vault_address(&MINT);
+
+    let result =
+        mollusk().process_and_validate_instruction(&instruction, &accounts, &[Check::success()]);
+
+    assert_eq!(token_balance(result.get_account(&vault).unwrap()), 200);
+    assert_eq!(
+        token_balance(result.get_account(&OWNER_TOKENS).unwrap()),
+        300
+    );
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn withdraw_rejects_another_mints_vault() {
+    // The vault address is derived from the mint, so it no longer matches
+    let (mut instruction, mut accounts) = withdraw(300, 500);
+    instruction.accounts[3].pubkey = OTHER_MINT;
+    accounts[3].0 = OTHER_MINT;
+
+    mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[Check::err(ProgramError::InvalidSeeds)],
+    );
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn withdraw_rejects_a_vault_the_token_program_does_not_own() {
+    let (instruction, mut accounts) = withdraw(300, 500);
+    accounts[1].1.owner = Pubkey::new_unique();
+
+    mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[Check::err(ProgramError::InvalidAccountOwner)],
+    );
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn withdraw_needs_the_owner_to_sign() {
+    let (mut instruction, accounts) = withdraw(300, 500);
+    instruction.accounts[0].is_signer = false;
+
+    mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[Check::err(ProgramError::InvalidAccountOwner)],
+    );
+}
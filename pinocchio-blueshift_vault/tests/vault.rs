@@ -0,0 +1,171 @@
+mod common;
+
+use common::{mollusk, OWNER, PROGRAM};
+use mollusk_svm::{program, result::Check};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+};
+
+fn vault_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", OWNER.as_ref()], &PROGRAM)
+}
+
+// Move `amount` lamports into or out of a vault holding `vault_lamports`
+fn transfer(
+    discriminator: u8,
+    amount: u64,
+    owner_lamports: u64,
+    vault_lamports: u64,
+) -> (Instruction, Vec<(Pubkey, Account)>) {
+    let (vault, bump) = vault_address();
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+
+    let mut data = vec![discriminator];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(bump);
+
+    let instruction = Instruction::new_with_bytes(
+        PROGRAM,
+        &data,
+        vec![
+            AccountMeta::new(OWNER, true),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(system_program, false),
+        ],
+    );
+    let accounts = vec![
+        (OWNER, Account::new(owner_lamports, 0, &system_program)),
+        (vault, Account::new(vault_lamports, 0, &system_program)),
+        (system_program, system_account),
+    ];
+    (instruction, accounts)
+}
+
+fn rent_exempt() -> u64 {
+    Rent::default().minimum_balance(0)
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn deposit_into_an_existing_vault() {
+    let (vault, _) = vault_address();
+    let (instruction, accounts) = transfer(0, LAMPORTS_PER_SOL, LAMPORTS_PER_SOL, LAMPORTS_PER_SOL);
+
+    mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[
+            Check::success(),
+            Check::account(&OWNER).lamports(0).build(),
+            Check::account(&vault)
+                .lamports(2 * LAMPORTS_PER_SOL)
+                .build(),
+        ],
+    );
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn first_deposit_must_be_rent_exempt() {
+    let (instruction, accounts) = transfer(0, rent_exempt() - 1, LAMPORTS_PER_SOL, 0);
+
+    mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[Check::err(ProgramError::AccountNotRentExempt)],
+    );
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn partial_withdraw() {
+    let (vault, _) = vault_address();
+    let (instruction, accounts) = transfer(1, LAMPORTS_PER_SOL / 4, 0, LAMPORTS_PER_SOL);
+
+    mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[
+            Check::success(),
+            Check::account(&OWNER)
+                .lamports(LAMPORTS_PER_SOL / 4)
+                .build(),
+            Check::account(&vault)
+                .lamports(3 * LAMPORTS_PER_SOL / 4)
+                .build(),
+        ],
+    );
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn withdraw_everything_empties_the_vault() {
+    let (vault, _) = vault_address();
+    let (instruction, accounts) = transfer(1, LAMPORTS_PER_SOL, 0, LAMPORTS_PER_SOL);
+
+    mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[
+            Check::success(),
+            Check::account(&OWNER).lamports(LAMPORTS_PER_SOL).build(),
+            Check::account(&vault).lamports(0).build(),
+        ],
+    );
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn withdraw_above_the_balance() {
+    let (instruction, accounts) = transfer(1, LAMPORTS_PER_SOL + 1, 0, LAMPORTS_PER_SOL);
+
+    mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[Check::err(ProgramError::InsufficientFunds)],
+    );
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn withdraw_cannot_leave_the_vault_below_rent_exempt() {
+    let (instruction, accounts) =
+        transfer(1, LAMPORTS_PER_SOL - rent_exempt() + 1, 0, LAMPORTS_PER_SOL);
+
+    mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[Check::err(ProgramError::AccountNotRentExempt)],
+    );
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn withdraw_needs_the_owner_to_sign() {
+    let (mut instruction, accounts) = transfer(1, LAMPORTS_PER_SOL / 4, 0, LAMPORTS_PER_SOL);
+    instruction.accounts[0].is_signer = false;
+
+    mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[Check::err(ProgramError::InvalidAccountOwner)],
+    );
+}
+
+#[test]
+#[ignore = "needs the program built with cargo build-sbf, see tests/common/mod.rs"]
+fn withdraw_rejects_a_wrong_bump() {
+    let (mut instruction, accounts) = transfer(1, LAMPORTS_PER_SOL / 4, 0, LAMPORTS_PER_SOL);
+    *instruction.data.last_mut().unwrap() ^= 1;
+
+    mollusk().process_and_validate_instruction(
+        &instruction,
+        &accounts,
+        &[Check::err(ProgramError::InvalidSeeds)],
+    );
+}
@@ -0,0 +1,164 @@
+use mollusk_svm::{program, Mollusk};
+use mollusk_svm_bencher::MolluskComputeUnitBencher;
+use pinocchio_tic_tac_toe::state::Game;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use tic_tac_toe_core::{Grid, Sign};
+
+// Keep the scenarios in step with native-tic-tac-toe's bench so the tables compare
+
+const PROGRAM: Pubkey = Pubkey::new_from_array(pinocchio_tic_tac_toe::ID);
+
+const PLAYER_ONE: Pubkey = Pubkey::new_from_array([1; 32]);
+const PLAYER_TWO: Pubkey = Pubkey::new_from_array([2; 32]);
+
+fn game_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[Game::SEED_PREFIX, PLAYER_ONE.as_ref(), PLAYER_TWO.as_ref()],
+        &PROGRAM,
+    )
+}
+
+// A game account after X and O alternated through `moves`
+fn game_account(board_size: u8, win_length: u8, moves: &[(u8, u8)]) -> Account {
+    let (_, bump) = game_address();
+    let mut data = vec![0; Game::space(board_size)];
+
+    Game::initialize(
+        &mut data,
+        [PLAYER_ONE.to_bytes(), PLAYER_TWO.to_bytes()],
+        board_size,
+        win_length,
+        bump,
+    )
+    .unwrap();
+
+    let (game, cells) = Game::load_mut(&mut data).unwrap();
+    for (row, column) in moves {
+        cells[*row as usize * board_size as usize + *column as usize] =
+            Grid::encode(Some(Sign::for_turn(game.turn)));
+        game.turn += 1;
+    }
+
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: PROGRAM,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn setup_game(board_size: u8, win_length: u8) -> (Instruction, Vec<(Pubkey, Account)>) {
+    let (game, _) = game_address();
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+
+    let mut data = vec![0];
+    data.extend_from_slice(PLAYER_TWO.as_ref());
+    data.extend_from_slice(&[board_size, win_length]);
+
+    let instruction = Instruction::new_with_bytes(
+        PROGRAM,
+        &data,
+        vec![
+            AccountMeta::new(PLAYER_ONE, true),
+            AccountMeta::new(game, false),
+            AccountMeta::new_readonly(system_program, false),
+        ],
+    );
+    let accounts = vec![
+        (
+            PLAYER_ONE,
+            Account::new(LAMPORTS_PER_SOL, 0, &system_program),
+        ),
+        (game, Account::new(0, 0, &system_program)),
+        (system_program, system_account),
+    ];
+    (instruction, accounts)
+}
+
+fn play(
+    board_size: u8,
+    win_length: u8,
+    moves: &[(u8, u8)],
+    (row, column): (u8, u8),
+) -> (Instruction, Vec<(Pubkey, Account)>) {
+    let (game, _) = game_address();
+    let player = [PLAYER_ONE, PLAYER_TWO][moves.len() % 2];
+
+    let instruction = Instruction::new_with_bytes(
+        PROGRAM,
+        &[1, row, column],
+        vec![
+            AccountMeta::new_readonly(player, true),
+            AccountMeta::new(game, false),
+        ],
+    );
+    let accounts = vec![
+        (player, Account::default()),
+        (game, game_account(board_size, win_length, moves)),
+    ];
+    (instruction, accounts)
+}
+
+fn main() {
+    let mollusk = Mollusk::new(&PROGRAM, "pinocchio_tic_tac_toe");
+
+    let (setup_3x3, setup_3x3_accounts) = setup_game(3, 3);
+    let (setup_15x15, setup_15x15_accounts) = setup_game(15, 5);
+    let (opening, opening_accounts) = play(3, 3, &[], (1, 1));
+    // X X .
+    // O O .
+    // . . .
+    let (win_3x3, win_3x3_accounts) = play(3, 3, &[(0, 0), (1, 0), (0, 1), (1, 1)], (0, 2));
+    // X O X
+    // X O O
+    // O X .
+    let (tie_3x3, tie_3x3_accounts) = play(
+        3,
+        3,
+        &[
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (1, 1),
+            (1, 0),
+            (1, 2),
+            (2, 1),
+            (2, 0),
+        ],
+        (2, 2),
+    );
+    // Four X in row 7 and four O in row 8, X completes five in a row
+    let (win_15x15, win_15x15_accounts) = play(
+        15,
+        5,
+        &[
+            (7, 3),
+            (8, 3),
+            (7, 4),
+            (8, 4),
+            (7, 5),
+            (8, 5),
+            (7, 6),
+            (8, 6),
+        ],
+        (7, 7),
+    );
+
+    MolluskComputeUnitBencher::new(mollusk)
+        .bench(("setup_game_3x3", &setup_3x3, &setup_3x3_accounts))
+        .bench(("setup_game_15x15", &setup_15x15, &setup_15x15_accounts))
+        .bench(("play_opening_3x3", &opening, &opening_accounts))
+        .bench(("play_win_3x3", &win_3x3, &win_3x3_accounts))
+        .bench(("play_tie_3x3", &tie_3x3, &tie_3x3_accounts))
+        .bench(("play_win_15x15", &win_15x15, &win_15x15_accounts))
+        .must_pass(true)
+        .out_dir("benches")
+        .execute();
+}
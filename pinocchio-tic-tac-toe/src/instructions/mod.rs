@@ -0,0 +1,5 @@
+pub mod play;
+pub mod setup_game;
+
+pub use play::*;
+pub use setup_game::*;
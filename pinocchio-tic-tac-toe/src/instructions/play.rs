@@ -0,0 +1,97 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+use tic_tac_toe_core::Tile;
+
+use crate::{
+    error::TicTacToeError,
+    state::{Game, GAME_ACTIVE},
+};
+
+// The player whose turn it is marks a tile.
+
+pub struct PlayAccounts<'a> {
+    pub player: &'a AccountInfo,
+    pub game: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for PlayAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [player, game] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !player.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        // Only this program can have written a game, so ownership is enough to trust it
+        if !game.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self { player, game })
+    }
+}
+
+pub struct PlayInstructionData {
+    pub tile: Tile,
+}
+
+impl<'a> TryFrom<&'a [u8]> for PlayInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let [row, column] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+
+        Ok(Self {
+            tile: Tile {
+                row: *row,
+                column: *column,
+            },
+        })
+    }
+}
+
+pub struct Play<'a> {
+    pub accounts: PlayAccounts<'a>,
+    pub instruction_datas: PlayInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Play<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = PlayAccounts::try_from(accounts)?;
+        let instruction_datas = PlayInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_datas,
+        })
+    }
+}
+
+impl<'a> Play<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &1;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.game.try_borrow_mut_data()?;
+        let (game, cells) = Game::load_mut(&mut data)?;
+
+        if game.state != GAME_ACTIVE {
+            return Err(TicTacToeError::GameAlreadyOver.into());
+        }
+
+        if game.current_player().ne(self.accounts.player.key()) {
+            return Err(TicTacToeError::NotPlayersTurn.into());
+        }
+
+        game.play(cells, self.instruction_datas.tile)
+            .map_err(TicTacToeError::from)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::{find_program_address, Pubkey},
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use tic_tac_toe_core as rules;
+
+use crate::{error::TicTacToeError, state::Game};
+
+// Player one opens a game against player two at [b"game", player_one, player_two],
+// paying for an account sized for the board.
+
+pub struct SetupGameAccounts<'a> {
+    pub player_one: &'a AccountInfo,
+    pub game: &'a AccountInfo,
+    pub bump: [u8; 1],
+}
+
+pub struct SetupGameInstructionData {
+    pub player_two: Pubkey,
+    pub board_size: u8,
+    pub win_length: u8,
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetupGameInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<Pubkey>() + 2 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (player_two, sizes) = data.split_at(size_of::<Pubkey>());
+        let (board_size, win_length) = (sizes[0], sizes[1]);
+
+        rules::validate_board(board_size, win_length).map_err(TicTacToeError::from)?;
+
+        Ok(Self {
+            player_two: player_two.try_into().unwrap(),
+            board_size,
+            win_length,
+        })
+    }
+}
+
+pub struct SetupGame<'a> {
+    pub accounts: SetupGameAccounts<'a>,
+    pub instruction_datas: SetupGameInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetupGame<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let instruction_datas = SetupGameInstructionData::try_from(data)?;
+
+        let [player_one, game, _system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !player_one.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        // The account is still a plain system account, so the create below decides whether it exists
+        if !game.is_owned_by(&pinocchio_system::ID) {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        let (game_key, bump) = find_program_address(
+            &[
+                Game::SEED_PREFIX,
+                player_one.key(),
+                &instruction_datas.player_two,
+            ],
+            &crate::ID,
+        );
+        if game.key().ne(&game_key) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        Ok(Self {
+            accounts: SetupGameAccounts {
+                player_one,
+                game,
+                bump: [bump],
+            },
+            instruction_datas,
+        })
+    }
+}
+
+impl<'a> SetupGame<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &0;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let SetupGameInstructionData {
+            player_two,
+            board_size,
+            win_length,
+        } = self.instruction_datas;
+        let space = Game::space(board_size);
+
+        let seeds = [
+            Seed::from(Game::SEED_PREFIX),
+            Seed::from(self.accounts.player_one.key()),
+            Seed::from(&player_two),
+            Seed::from(&self.accounts.bump),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        CreateAccount {
+            from: self.accounts.player_one,
+            to: self.accounts.game,
+            lamports: Rent::get()?.minimum_balance(space),
+            space: space as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&signers)?;
+
+        Game::initialize(
+            &mut self.accounts.game.try_borrow_mut_data()?,
+            [*self.accounts.player_one.key(), player_two],
+            board_size,
+            win_length,
+            self.accounts.bump[0],
+        )
+    }
+}
@@ -0,0 +1,36 @@
+#![no_std]
+
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+#[cfg(not(feature = "no-entrypoint"))]
+use pinocchio::{entrypoint, nostd_panic_handler};
+
+pub mod error;
+pub mod instructions;
+pub mod state;
+
+use instructions::*;
+
+#[cfg(not(feature = "no-entrypoint"))]
+entrypoint!(process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
+nostd_panic_handler!();
+
+pub const ID: Pubkey = [
+    0x76, 0xfe, 0xf2, 0xe7, 0x19, 0xec, 0x49, 0x73, 0x72, 0xa3, 0x3f, 0xa0, 0xcc, 0xfd, 0x5b, 0xdf,
+    0x70, 0xb3, 0x3b, 0x04, 0x54, 0x2d, 0xaa, 0xaf, 0xa7, 0xa8, 0x3a, 0x78, 0x20, 0x80, 0xfb, 0x89,
+];
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    match instruction_data.split_first() {
+        Some((SetupGame::DISCRIMINATOR, data)) => SetupGame::try_from((data, accounts))?.process(),
+        Some((Play::DISCRIMINATOR, data)) => Play::try_from((data, accounts))?.process(),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
@@ -0,0 +1,88 @@
+use core::mem::size_of;
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+use tic_tac_toe_core::{self as rules, Grid, Outcome, RuleError, Tile};
+
+pub const GAME_ACTIVE: u8 = 0;
+pub const GAME_TIE: u8 = 1;
+pub const GAME_WON: u8 = 2;
+
+// Fixed header of a game account; the board cells follow it, row by row,
+// in the encoding of tic_tac_toe_core::Grid. Every field is a byte array
+// so the header can be read in place without alignment concerns.
+#[repr(C)]
+pub struct Game {
+    pub players: [Pubkey; 2],
+    pub turn: u8,
+    pub board_size: u8,
+    pub win_length: u8,
+    pub state: u8,
+    pub winner: Pubkey,
+    pub bump: u8,
+}
+
+impl Game {
+    pub const LEN: usize = size_of::<Self>();
+    pub const SEED_PREFIX: &'static [u8] = b"game";
+
+    pub fn space(board_size: u8) -> usize {
+        Self::LEN + rules::tile_count(board_size)
+    }
+
+    // Splits account data into the header and the board cells
+    pub fn load_mut(data: &mut [u8]) -> Result<(&mut Self, &mut [u8]), ProgramError> {
+        let (game, cells) = Self::split_mut(data)?;
+
+        if cells.len() != rules::tile_count(game.board_size) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok((game, cells))
+    }
+
+    // Writes the header of a freshly created, zeroed account, which is already an empty board
+    pub fn initialize(
+        data: &mut [u8],
+        players: [Pubkey; 2],
+        board_size: u8,
+        win_length: u8,
+        bump: u8,
+    ) -> Result<(), ProgramError> {
+        let (game, _) = Self::split_mut(data)?;
+
+        game.players = players;
+        game.turn = 1;
+        game.board_size = board_size;
+        game.win_length = win_length;
+        game.state = GAME_ACTIVE;
+        game.bump = bump;
+
+        Self::load_mut(data).map(|_| ())
+    }
+
+    fn split_mut(data: &mut [u8]) -> Result<(&mut Self, &mut [u8]), ProgramError> {
+        if data.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (header, cells) = data.split_at_mut(Self::LEN);
+        // Safety: Game is repr(C), has an alignment of 1 and header is exactly LEN bytes
+        Ok((unsafe { &mut *(header.as_mut_ptr() as *mut Self) }, cells))
+    }
+
+    pub fn current_player(&self) -> &Pubkey {
+        &self.players[rules::Sign::for_turn(self.turn).player_index()]
+    }
+
+    pub fn play(&mut self, cells: &mut [u8], tile: Tile) -> Result<(), RuleError> {
+        let mut grid = Grid::new(cells, self.board_size, self.win_length)?;
+
+        match rules::play(&mut grid, self.turn, tile)? {
+            Outcome::Active => self.turn += 1,
+            Outcome::Tie => self.state = GAME_TIE,
+            Outcome::Won(sign) => {
+                self.state = GAME_WON;
+                self.winner = self.players[sign.player_index()];
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use pinocchio_tic_tac_toe::{
+    error::TicTacToeError,
+    state::{Game, GAME_ACTIVE, GAME_WON},
+};
+use tic_tac_toe_core::{RuleError, Tile};
+
+// The program itself only runs under the SBF runtime, see benches/compute_units.rs.
+// These cover the zero-copy game layout it plays on.
+
+const PLAYER_ONE: [u8; 32] = [1; 32];
+const PLAYER_TWO: [u8; 32] = [2; 32];
+
+fn new_game(board_size: u8, win_length: u8) -> Vec<u8> {
+    let mut data = vec![0; Game::space(board_size)];
+    Game::initialize(
+        &mut data,
+        [PLAYER_ONE, PLAYER_TWO],
+        board_size,
+        win_length,
+        255,
+    )
+    .unwrap();
+    data
+}
+
+#[test]
+fn header_is_packed() {
+    assert_eq!(Game::LEN, 32 * 2 + 1 + 1 + 1 + 1 + 32 + 1);
+    assert_eq!(core::mem::align_of::<Game>(), 1);
+}
+
+#[test]
+fn rejects_data_that_does_not_match_the_board() {
+    let mut data = new_game(3, 3);
+    data.pop();
+
+    assert!(Game::load_mut(&mut data).is_err());
+    assert!(Game::load_mut(&mut [0; 10]).is_err());
+}
+
+#[test]
+fn plays_to_a_win() {
+    let mut data = new_game(3, 3);
+
+    // X X X
+    // O O .
+    // . . .
+    for (turn, (row, column)) in [(0, 0), (1, 0), (0, 1), (1, 1)].into_iter().enumerate() {
+        let (game, cells) = Game::load_mut(&mut data).unwrap();
+        assert_eq!(game.current_player(), &game.players[turn % 2]);
+        game.play(cells, Tile { row, column }).unwrap();
+        assert_eq!(game.state, GAME_ACTIVE);
+    }
+
+    let (game, cells) = Game::load_mut(&mut data).unwrap();
+    game.play(cells, Tile { row: 0, column: 2 }).unwrap();
+    assert_eq!(game.state, GAME_WON);
+    assert_eq!(game.winner, PLAYER_ONE);
+    assert_eq!(&data[Game::LEN..Game::LEN + 3], &[1, 1, 1]);
+}
+
+#[test]
+fn rule_errors_map_to_program_errors() {
+    let mut data = new_game(3, 3);
+    let (game, cells) = Game::load_mut(&mut data).unwrap();
+    game.play(cells, Tile { row: 1, column: 1 }).unwrap();
+
+    let error = game.play(cells, Tile { row: 1, column: 1 }).unwrap_err();
+    assert_eq!(error, RuleError::TileAlreadySet);
+    assert_eq!(TicTacToeError::from(error), TicTacToeError::TileAlreadySet);
+}
@@ -0,0 +1,199 @@
+#![no_std]
+
+// The rules of N x N tic-tac-toe, won by k in a row, with no framework or
+// allocator behind them. Each program keeps its own account layout and only
+// has to expose its board through the `Board` trait.
+
+pub const MIN_BOARD_SIZE: u8 = 3;
+// 15x15 is a gomoku board, and keeps the move count within a u8 turn counter
+pub const MAX_BOARD_SIZE: u8 = 15;
+
+// Directions a line can run in: across, down and both diagonals
+const DIRECTIONS: [(i16, i16); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sign {
+    X,
+    O,
+}
+
+impl Sign {
+    // Turns count from 1, player one plays X on the odd ones
+    pub fn for_turn(turn: u8) -> Self {
+        if turn % 2 == 1 {
+            Sign::X
+        } else {
+            Sign::O
+        }
+    }
+
+    // Seat of the player who plays this sign
+    pub fn player_index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tile {
+    pub row: u8,
+    pub column: u8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Active,
+    Tie,
+    Won(Sign),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleError {
+    InvalidBoardConfig,
+    TileOutOfBounds,
+    TileAlreadySet,
+}
+
+pub trait Board {
+    fn board_size(&self) -> u8;
+    fn win_length(&self) -> u8;
+    fn tile(&self, row: u8, column: u8) -> Option<Sign>;
+    fn set_tile(&mut self, row: u8, column: u8, sign: Sign);
+}
+
+pub fn validate_board(board_size: u8, win_length: u8) -> Result<(), RuleError> {
+    if !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&board_size)
+        || !(MIN_BOARD_SIZE..=board_size).contains(&win_length)
+    {
+        return Err(RuleError::InvalidBoardConfig);
+    }
+    Ok(())
+}
+
+pub fn tile_count(board_size: u8) -> usize {
+    board_size as usize * board_size as usize
+}
+
+// Places the sign whose turn it is and works out what that did to the game
+pub fn play<B: Board>(board: &mut B, turn: u8, tile: Tile) -> Result<Outcome, RuleError> {
+    let size = board.board_size();
+    if tile.row >= size || tile.column >= size {
+        return Err(RuleError::TileOutOfBounds);
+    }
+    if board.tile(tile.row, tile.column).is_some() {
+        return Err(RuleError::TileAlreadySet);
+    }
+
+    board.set_tile(tile.row, tile.column, Sign::for_turn(turn));
+
+    Ok(update_state(board, turn, tile))
+}
+
+// Only lines through the last placed tile can have changed, so that's all we scan
+pub fn update_state<B: Board>(board: &B, turn: u8, tile: Tile) -> Outcome {
+    if is_winning_line(board, tile) {
+        return Outcome::Won(Sign::for_turn(turn));
+    }
+
+    // Every move fills a tile, so the board is full once the turns run out
+    if turn as usize == tile_count(board.board_size()) {
+        return Outcome::Tie;
+    }
+
+    Outcome::Active
+}
+
+pub fn is_winning_line<B: Board>(board: &B, tile: Tile) -> bool {
+    DIRECTIONS.iter().any(|&(row_step, column_step)| {
+        let line = 1
+            + count_in_direction(board, tile, (row_step, column_step))
+            + count_in_direction(board, tile, (-row_step, -column_step));
+        line >= board.win_length()
+    })
+}
+
+// Counts the tile's sign running away from it in one direction
+fn count_in_direction<B: Board>(board: &B, tile: Tile, (row_step, column_step): (i16, i16)) -> u8 {
+    let sign = board.tile(tile.row, tile.column);
+    let size = board.board_size() as i16;
+    let (mut row, mut column) = (tile.row as i16, tile.column as i16);
+    let mut count = 0;
+
+    while count < board.win_length() {
+        row += row_step;
+        column += column_step;
+
+        if !(0..size).contains(&row)
+            || !(0..size).contains(&column)
+            || board.tile(row as u8, column as u8) != sign
+        {
+            break;
+        }
+        count += 1;
+    }
+
+    count
+}
+
+// A board stored as one byte per tile, row by row: 0 for empty, 1 for X, 2 for O.
+// Used by the programs that lay out their accounts by hand.
+pub struct Grid<'a> {
+    cells: &'a mut [u8],
+    board_size: u8,
+    win_length: u8,
+}
+
+impl<'a> Grid<'a> {
+    pub const EMPTY: u8 = 0;
+
+    pub fn new(cells: &'a mut [u8], board_size: u8, win_length: u8) -> Result<Self, RuleError> {
+        validate_board(board_size, win_length)?;
+        if cells.len() != tile_count(board_size) {
+            return Err(RuleError::InvalidBoardConfig);
+        }
+
+        Ok(Self {
+            cells,
+            board_size,
+            win_length,
+        })
+    }
+
+    pub fn encode(sign: Option<Sign>) -> u8 {
+        match sign {
+            None => Self::EMPTY,
+            Some(sign) => sign as u8 + 1,
+        }
+    }
+
+    pub fn decode(cell: u8) -> Option<Sign> {
+        match cell {
+            1 => Some(Sign::X),
+            2 => Some(Sign::O),
+            _ => None,
+        }
+    }
+
+    fn index(&self, row: u8, column: u8) -> usize {
+        row as usize * self.board_size as usize + column as usize
+    }
+}
+
+impl Board for Grid<'_> {
+    fn board_size(&self) -> u8 {
+        self.board_size
+    }
+
+    fn win_length(&self) -> u8 {
+        self.win_length
+    }
+
+    fn tile(&self, row: u8, column: u8) -> Option<Sign> {
+        Self::decode(self.cells[self.index(row, column)])
+    }
+
+    fn set_tile(&mut self, row: u8, column: u8, sign: Sign) {
+        let index = self.index(row, column);
+        self.cells[index] = Self::encode(Some(sign));
+    }
+}
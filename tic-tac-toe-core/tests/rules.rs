@@ -0,0 +1,139 @@
+use tic_tac_toe_core::{
+    is_winning_line, play, validate_board, Board, Grid, Outcome, RuleError, Sign, Tile,
+};
+
+fn tile(row: u8, column: u8) -> Tile {
+    Tile { row, column }
+}
+
+// Plays the moves in order, returning the outcome of the last one
+fn play_moves(grid: &mut Grid, moves: &[(u8, u8)]) -> Outcome {
+    let mut outcome = Outcome::Active;
+    for (i, &(row, column)) in moves.iter().enumerate() {
+        outcome = play(grid, i as u8 + 1, tile(row, column)).unwrap();
+    }
+    outcome
+}
+
+#[test]
+fn x_moves_first() {
+    let mut cells = [0; 9];
+    let mut grid = Grid::new(&mut cells, 3, 3).unwrap();
+
+    assert_eq!(play(&mut grid, 1, tile(1, 1)), Ok(Outcome::Active));
+    assert_eq!(play(&mut grid, 2, tile(0, 0)), Ok(Outcome::Active));
+    assert_eq!(grid.tile(1, 1), Some(Sign::X));
+    assert_eq!(grid.tile(0, 0), Some(Sign::O));
+    assert_eq!(cells, [2, 0, 0, 0, 1, 0, 0, 0, 0]);
+}
+
+#[test]
+fn illegal_moves() {
+    let mut cells = [0; 9];
+    let mut grid = Grid::new(&mut cells, 3, 3).unwrap();
+    play(&mut grid, 1, tile(0, 0)).unwrap();
+
+    assert_eq!(
+        play(&mut grid, 2, tile(0, 0)),
+        Err(RuleError::TileAlreadySet)
+    );
+    assert_eq!(
+        play(&mut grid, 2, tile(3, 0)),
+        Err(RuleError::TileOutOfBounds)
+    );
+    assert_eq!(
+        play(&mut grid, 2, tile(0, 3)),
+        Err(RuleError::TileOutOfBounds)
+    );
+}
+
+#[test]
+fn wins_in_every_direction() {
+    let lines: [&[(u8, u8)]; 4] = [
+        // across
+        &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)],
+        // down
+        &[(0, 0), (0, 1), (1, 0), (1, 1), (2, 0)],
+        // diagonal
+        &[(0, 0), (0, 1), (1, 1), (0, 2), (2, 2)],
+        // anti-diagonal
+        &[(0, 2), (0, 1), (1, 1), (0, 0), (2, 0)],
+    ];
+
+    for moves in lines {
+        let mut cells = [0; 9];
+        let mut grid = Grid::new(&mut cells, 3, 3).unwrap();
+        assert_eq!(play_moves(&mut grid, moves), Outcome::Won(Sign::X));
+    }
+}
+
+#[test]
+fn o_can_win() {
+    let mut cells = [0; 9];
+    let mut grid = Grid::new(&mut cells, 3, 3).unwrap();
+
+    let outcome = play_moves(&mut grid, &[(0, 0), (0, 2), (0, 1), (1, 1), (1, 0), (2, 0)]);
+    assert_eq!(outcome, Outcome::Won(Sign::O));
+    assert_eq!(Sign::O.player_index(), 1);
+}
+
+#[test]
+fn full_board_is_a_tie() {
+    let mut cells = [0; 9];
+    let mut grid = Grid::new(&mut cells, 3, 3).unwrap();
+
+    let outcome = play_moves(
+        &mut grid,
+        &[
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (1, 1),
+            (1, 0),
+            (1, 2),
+            (2, 1),
+            (2, 0),
+            (2, 2),
+        ],
+    );
+    assert_eq!(outcome, Outcome::Tie);
+}
+
+#[test]
+fn line_completed_in_the_middle() {
+    let mut cells = [0; 25];
+    let mut grid = Grid::new(&mut cells, 5, 4).unwrap();
+
+    // X fills (2, 0), (2, 1), (2, 3) and wins on (2, 2)
+    let outcome = play_moves(
+        &mut grid,
+        &[(2, 0), (0, 0), (2, 1), (0, 1), (2, 3), (0, 2), (2, 2)],
+    );
+    assert_eq!(outcome, Outcome::Won(Sign::X));
+    assert!(is_winning_line(&grid, tile(2, 2)));
+    assert!(!is_winning_line(&grid, tile(0, 1)));
+}
+
+#[test]
+fn three_is_not_enough_when_four_are_needed() {
+    let mut cells = [0; 25];
+    let mut grid = Grid::new(&mut cells, 5, 4).unwrap();
+
+    let outcome = play_moves(&mut grid, &[(0, 0), (4, 4), (1, 1), (4, 3), (2, 2)]);
+    assert_eq!(outcome, Outcome::Active);
+}
+
+#[test]
+fn board_config() {
+    assert_eq!(validate_board(3, 3), Ok(()));
+    assert_eq!(validate_board(15, 5), Ok(()));
+    for (board_size, win_length) in [(2, 2), (16, 5), (3, 4), (5, 2)] {
+        assert_eq!(
+            validate_board(board_size, win_length),
+            Err(RuleError::InvalidBoardConfig)
+        );
+    }
+
+    let mut cells = [0; 8];
+    assert!(Grid::new(&mut cells, 3, 3).is_err());
+}